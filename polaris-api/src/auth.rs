@@ -1,3 +1,5 @@
+use base64::Engine;
+use chrono::{DateTime, Utc};
 use serde::Deserialize;
 
 /// Response from POST /api/auth/v2/authenticate
@@ -7,41 +9,124 @@ pub struct AuthenticateResponse {
     pub jwt: String,
 }
 
+/// Claims read from the payload of a Polaris JWT.
+///
+/// The signature is not verified: this is only for displaying who a token belongs to
+/// and when the session ends, never for making trust decisions.
+#[derive(Debug, Clone, Deserialize)]
+pub struct JwtClaims {
+    #[serde(default)]
+    pub sub: Option<String>,
+    #[serde(default)]
+    pub email: Option<String>,
+    #[serde(default, alias = "preferred_username")]
+    pub username: Option<String>,
+    /// Expiry, in seconds since the Unix epoch.
+    #[serde(default)]
+    pub exp: Option<i64>,
+    /// Issue time, in seconds since the Unix epoch.
+    #[serde(default)]
+    pub iat: Option<i64>,
+}
+
+impl JwtClaims {
+    /// Decode the (unverified) claims from a JWT.
+    pub fn decode(jwt: &str) -> crate::error::Result<Self> {
+        let payload = jwt
+            .split('.')
+            .nth(1)
+            .ok_or_else(|| crate::error::PolarisError::Deserialize("JWT has no payload segment".into()))?;
+        let bytes = base64::engine::general_purpose::URL_SAFE_NO_PAD
+            .decode(payload.trim_end_matches('='))
+            .map_err(|e| crate::error::PolarisError::Deserialize(format!("invalid JWT payload: {e}")))?;
+        serde_json::from_slice(&bytes)
+            .map_err(|e| crate::error::PolarisError::Deserialize(format!("invalid JWT claims: {e}")))
+    }
+
+    /// Best available human identity: email, then username, then subject.
+    pub fn identity(&self) -> Option<&str> {
+        self.email
+            .as_deref()
+            .or(self.username.as_deref())
+            .or(self.sub.as_deref())
+    }
+
+    pub fn expires_at(&self) -> Option<DateTime<Utc>> {
+        DateTime::from_timestamp(self.exp?, 0)
+    }
+}
+
+/// Clean up an API token as typed or pasted: trim whitespace and one pair of
+/// surrounding quotes. Empty tokens, and tokens with whitespace or control characters
+/// inside, are rejected before they reach the server.
+pub fn normalize_api_token(raw: &str) -> crate::error::Result<String> {
+    let trimmed = raw.trim();
+    let unquoted = ['"', '\'']
+        .iter()
+        .find_map(|q| trimmed.strip_prefix(*q).and_then(|t| t.strip_suffix(*q)))
+        .unwrap_or(trimmed)
+        .trim();
+    if unquoted.is_empty() || unquoted.chars().any(|c| c.is_whitespace() || c.is_control()) {
+        return Err(crate::error::PolarisError::AuthFailed {
+            status: None,
+            detail: "API token is empty or malformed".into(),
+        });
+    }
+    Ok(unquoted.to_string())
+}
+
 /// Request body is application/x-www-form-urlencoded with `accesstoken` field.
 /// The response returns a JWT in the body for API token auth.
+#[derive(Clone)]
 pub struct AuthClient {
-    http: reqwest::Client,
     base_url: String,
+    tls: crate::client::TlsConfig,
+    trace: Option<crate::trace::TraceLog>,
 }
 
 impl AuthClient {
     pub fn new(base_url: &str) -> Self {
         Self {
-            http: reqwest::Client::new(),
-            base_url: base_url.trim_end_matches('/').to_string(),
+            base_url: base_url.to_string(),
+            tls: crate::client::TlsConfig::default(),
+            trace: None,
         }
     }
 
+    /// Use custom TLS settings (extra root certificates, or no verification).
+    pub fn with_tls(mut self, tls: crate::client::TlsConfig) -> Self {
+        self.tls = tls;
+        self
+    }
+
+    /// Record the login exchange in `trace`, with the token and JWT redacted.
+    pub fn with_trace(mut self, trace: Option<crate::trace::TraceLog>) -> Self {
+        self.trace = trace;
+        self
+    }
+
     /// Authenticate with an API token to get a JWT.
     pub async fn authenticate_with_token(&self, api_token: &str) -> crate::error::Result<String> {
-        let url = format!("{}/api/auth/v2/authenticate", self.base_url);
+        let api_token = normalize_api_token(api_token)?;
+        let url = crate::http::api_url(&self.base_url, "api/auth/v2/authenticate")?;
 
-        let resp = self
-            .http
-            .post(&url)
+        let http = crate::http::client_builder(&self.tls).build()?;
+        let request = http
+            .post(url)
             .header("Accept", "application/json")
-            .form(&[("accesstoken", api_token)])
-            .send()
-            .await?;
+            .form(&[("accesstoken", api_token.as_str())]);
+        let resp = crate::trace::send(request, self.trace.as_ref()).await?;
 
         if !resp.status().is_success() {
             let status = resp.status().as_u16();
             let body = resp.text().await.unwrap_or_default();
-            return Err(crate::error::PolarisError::AuthFailed(format!(
-                "HTTP {status}: {body}"
-            )));
+            return Err(crate::error::PolarisError::AuthFailed {
+                status: Some(status),
+                detail: body,
+            });
         }
 
+        crate::http::expect_json(&resp)?;
         let auth_resp: AuthenticateResponse = resp
             .json::<AuthenticateResponse>()
             .await
@@ -50,3 +135,34 @@ impl AuthClient {
         Ok(auth_resp.jwt)
     }
 }
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used, clippy::expect_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn normalize_api_token_passes_a_clean_token_through() {
+        assert_eq!(normalize_api_token("abc123").unwrap(), "abc123");
+    }
+
+    #[test]
+    fn normalize_api_token_trims_a_trailing_newline() {
+        assert_eq!(normalize_api_token("abc123\n").unwrap(), "abc123");
+        assert_eq!(normalize_api_token("  abc123\r\n").unwrap(), "abc123");
+    }
+
+    #[test]
+    fn normalize_api_token_strips_one_pair_of_quotes() {
+        assert_eq!(normalize_api_token("\"abc\"").unwrap(), "abc");
+        assert_eq!(normalize_api_token("'abc'\n").unwrap(), "abc");
+    }
+
+    #[test]
+    fn normalize_api_token_rejects_empty_and_malformed_tokens() {
+        for raw in ["", "   \n\t", "\"\"", "abc def", "abc\u{7}"] {
+            let err = normalize_api_token(raw).unwrap_err();
+            assert!(matches!(err, crate::error::PolarisError::AuthFailed { status: None, .. }), "{raw:?}: {err:?}");
+        }
+    }
+}
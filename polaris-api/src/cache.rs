@@ -0,0 +1,224 @@
+//! On-disk cache for conditional GETs (`If-None-Match` / `If-Modified-Since`).
+//!
+//! Only used for listings that rarely change (projects, branches). An entry is kept
+//! only while the server sends an `ETag` or `Last-Modified` for it; a `304 Not Modified`
+//! answer is served from the stored body, a `200` replaces it. Cache I/O errors are
+//! never fatal: the request simply goes out unconditionally.
+
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use reqwest::header::{ETAG, HeaderMap, IF_MODIFIED_SINCE, IF_NONE_MATCH, LAST_MODIFIED};
+use serde::{Deserialize, Serialize};
+use url::Url;
+
+use crate::client::RawResponseHook;
+use crate::error::Result;
+use crate::http::{RetryPolicy, Throttle};
+
+#[derive(Serialize, Deserialize)]
+struct Entry {
+    url: String,
+    etag: Option<String>,
+    last_modified: Option<String>,
+    body: String,
+}
+
+/// A directory of cached response bodies, one file per request URL.
+#[derive(Debug, Clone)]
+pub(crate) struct ResponseCache {
+    dir: PathBuf,
+    verbose: bool,
+}
+
+impl ResponseCache {
+    pub(crate) fn new(dir: PathBuf, verbose: bool) -> Self {
+        Self { dir, verbose }
+    }
+
+    /// The file for `url`. The URL is hashed rather than used as a name because query
+    /// strings easily exceed file name limits; the full URL is stored in the entry and
+    /// checked on load, so a hash collision is only a miss. The hash is FNV-1a, which
+    /// (unlike `DefaultHasher`) gives the same name in every build and Rust release.
+    fn path(&self, url: &Url) -> PathBuf {
+        self.dir.join(format!("{:016x}.json", fnv1a(url.as_str().as_bytes())))
+    }
+
+    fn load(&self, url: &Url) -> Option<Entry> {
+        let text = std::fs::read_to_string(self.path(url)).ok()?;
+        serde_json::from_str::<Entry>(&text).ok().filter(|e| e.url == url.as_str())
+    }
+
+    fn store(&self, url: &Url, headers: &HeaderMap, body: &str) {
+        let header = |name| headers.get(name).and_then(|v| v.to_str().ok()).map(str::to_string);
+        let (etag, last_modified) = (header(ETAG), header(LAST_MODIFIED));
+        let path = self.path(url);
+        if etag.is_none() && last_modified.is_none() {
+            // Nothing to revalidate with; a stale entry would never be used again.
+            let _ = std::fs::remove_file(path);
+            return;
+        }
+        let entry = Entry {
+            url: url.to_string(),
+            etag,
+            last_modified,
+            body: body.to_string(),
+        };
+        let Ok(json) = serde_json::to_string(&entry) else {
+            return;
+        };
+        if create_private_dir(&self.dir).is_ok() {
+            let _ = write_private(&path, json.as_bytes());
+        }
+    }
+}
+
+/// 64-bit FNV-1a.
+fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf2_9ce4_8422_2325, |hash, &b| (hash ^ u64::from(b)).wrapping_mul(0x0000_0100_0000_01b3))
+}
+
+/// Create the cache directory, readable only by the owner on Unix since cached
+/// listings name the user's projects. A directory left by an older version with
+/// wider permissions is narrowed.
+fn create_private_dir(dir: &Path) -> std::io::Result<()> {
+    let mut builder = std::fs::DirBuilder::new();
+    builder.recursive(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::{DirBuilderExt, PermissionsExt};
+        builder.mode(0o700).create(dir)?;
+        std::fs::set_permissions(dir, std::fs::Permissions::from_mode(0o700))?;
+    }
+    #[cfg(not(unix))]
+    builder.create(dir)?;
+    Ok(())
+}
+
+/// Replace `path` with `contents` through a temporary file created owner-only on
+/// Unix, so the entry is never readable by others nor seen half-written.
+fn write_private(path: &Path, contents: &[u8]) -> std::io::Result<()> {
+    let tmp = path.with_extension(format!("{}.tmp", std::process::id()));
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    let written = options.open(&tmp).and_then(|mut file| file.write_all(contents));
+    if let Err(e) = written.and_then(|()| std::fs::rename(&tmp, path)) {
+        let _ = std::fs::remove_file(&tmp);
+        return Err(e);
+    }
+    Ok(())
+}
+
+/// GET `url` and return its body, revalidating against `cache` when there is one.
+pub(crate) async fn get(
+    http: &reqwest::Client,
+    url: Url,
+    policy: &RetryPolicy,
+    throttle: &Throttle,
+    cache: Option<&ResponseCache>,
+    raw: Option<&RawResponseHook>,
+    trace: Option<&crate::trace::TraceLog>,
+) -> Result<String> {
+    let entry = cache.and_then(|c| c.load(&url));
+    let mut request = http.get(url.clone());
+    if let Some(entry) = &entry {
+        if let Some(etag) = &entry.etag {
+            request = request.header(IF_NONE_MATCH, etag);
+        }
+        if let Some(modified) = &entry.last_modified {
+            request = request.header(IF_MODIFIED_SINCE, modified);
+        }
+    }
+
+    let resp = crate::http::send_with_retry(request, policy, throttle, trace).await?;
+    if let (Some(cache), Some(entry)) = (cache, entry)
+        && resp.status() == reqwest::StatusCode::NOT_MODIFIED
+    {
+        if cache.verbose {
+            eprintln!("[polaris] not modified; using cached response");
+        }
+        if let Some(hook) = raw {
+            hook.call(&entry.body);
+        }
+        return Ok(entry.body);
+    }
+
+    crate::http::expect_json(&resp)?;
+    let headers = resp.headers().clone();
+    let body = crate::http::read_body(resp, raw).await?;
+    if let Some(cache) = cache {
+        cache.store(&url, &headers, &body);
+    }
+    Ok(body)
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used, clippy::expect_used)]
+mod tests {
+    use super::*;
+
+    fn temp_cache(name: &str) -> ResponseCache {
+        let dir = std::env::temp_dir().join(format!("polaris-cache-{name}-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        ResponseCache::new(dir, false)
+    }
+
+    fn etag_headers() -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert(ETAG, "\"v1\"".parse().unwrap());
+        headers
+    }
+
+    #[test]
+    fn fnv1a_matches_the_reference_vectors() {
+        assert_eq!(fnv1a(b""), 0xcbf2_9ce4_8422_2325);
+        assert_eq!(fnv1a(b"a"), 0xaf63_dc4c_8601_ec8c);
+        assert_eq!(fnv1a(b"foobar"), 0x8594_4171_f739_67e8);
+    }
+
+    #[test]
+    fn entry_names_are_stable() {
+        let cache = ResponseCache::new(PathBuf::from("/cache"), false);
+        let url = Url::parse("https://polaris.example.com/api/common/v0/projects?page[limit]=100").unwrap();
+        let name = format!("{:016x}.json", fnv1a(url.as_str().as_bytes()));
+        assert_eq!(cache.path(&url), Path::new("/cache").join(name));
+    }
+
+    #[test]
+    fn stored_entries_load_only_for_their_url() {
+        let cache = temp_cache("roundtrip");
+        let url = Url::parse("https://polaris.example.com/api/common/v0/projects").unwrap();
+        cache.store(&url, &etag_headers(), r#"{"data":[]}"#);
+        let entry = cache.load(&url).unwrap();
+        assert_eq!((entry.etag.as_deref(), entry.body.as_str()), (Some("\"v1\""), r#"{"data":[]}"#));
+
+        // Pretend another URL hashed to the same file.
+        let other = Url::parse("https://polaris.example.com/api/common/v0/branches").unwrap();
+        std::fs::rename(cache.path(&url), cache.path(&other)).unwrap();
+        assert!(cache.load(&other).is_none());
+
+        cache.store(&other, &HeaderMap::new(), "{}");
+        assert!(!cache.path(&other).exists(), "entries without validators are dropped");
+        std::fs::remove_dir_all(&cache.dir).unwrap();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn entries_are_private_to_the_owner() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let cache = temp_cache("perms");
+        let url = Url::parse("https://polaris.example.com/api/common/v0/projects").unwrap();
+        cache.store(&url, &etag_headers(), "{}");
+        let mode = |p: &Path| std::fs::metadata(p).unwrap().permissions().mode() & 0o777;
+        assert_eq!(mode(&cache.dir), 0o700);
+        assert_eq!(mode(&cache.path(&url)), 0o600);
+        assert_eq!(std::fs::read_dir(&cache.dir).unwrap().count(), 1, "no temporary file is left behind");
+        std::fs::remove_dir_all(&cache.dir).unwrap();
+    }
+}
@@ -1,45 +1,381 @@
+use std::collections::HashSet;
 use std::sync::Arc;
-use tokio::sync::RwLock;
+use tokio::sync::{Mutex, RwLock};
 use zeroize::Zeroizing;
 
 use crate::auth::AuthClient;
-use crate::common::{CommonClient, JsonApiResponse, Project, Branch};
+use crate::common::{CommonClient, JsonApiResponse, JsonApiSingleResponse, Project, Branch, Run};
 use crate::error::{PolarisError, Result};
+use crate::http::{check_response, check_status, read_body};
 
 /// Configuration for the Polaris client.
 #[derive(Debug, Clone)]
 pub struct PolarisConfig {
     pub base_url: String,
     pub api_token: String,
+    /// Extra query parameters appended to every API request (debugging escape hatch).
+    pub extra_query: Vec<(String, String)>,
+    /// Log each request URL to stderr.
+    pub verbose: bool,
+    pub tls: TlsConfig,
+    /// Per-attempt request timeout.
+    pub timeout: std::time::Duration,
+    /// Retries for 429/5xx responses and connection failures.
+    pub max_retries: u32,
+    /// Retries for requests that hit `timeout`, counted separately from `max_retries`.
+    pub timeout_retries: u32,
+    /// Total time all requests from one client (and its clones) may spend retrying;
+    /// once used up, failures are returned without retrying. `None` means no limit.
+    pub max_total_retry_time: Option<std::time::Duration>,
+    /// Called with the body of every successful API response, before it is parsed.
+    pub raw_responses: Option<RawResponseHook>,
+    /// Related resources requested with issues (`include[issue][]`).
+    pub issue_includes: Vec<String>,
+    /// Rate-limit headers to watch, and when to start slowing down.
+    pub rate_limit: RateLimitConfig,
+    /// `Accept-Language` for event descriptions, unless a call overrides it.
+    pub language: String,
+    /// Idle connections kept open per host for reuse.
+    pub pool_max_idle_per_host: usize,
+    /// How long an idle connection is kept before closing; `None` keeps it indefinitely.
+    pub pool_idle_timeout: Option<std::time::Duration>,
+    /// Directory for cached project and branch listings, revalidated with
+    /// `ETag`/`Last-Modified` on every call. `None` (the default) disables caching.
+    pub cache_dir: Option<std::path::PathBuf>,
+    /// File receiving a redacted JSON-lines record of every HTTP exchange.
+    pub trace: Option<crate::trace::TraceLog>,
+    /// Refuse every call that changes server state (triage updates, `post_raw`) with
+    /// [`PolarisError::ReadOnly`] before anything is sent.
+    pub read_only: bool,
+}
+
+/// Rate-limit response headers. Gateways name these differently, so both are configurable.
+#[derive(Debug, Clone)]
+pub struct RateLimitConfig {
+    /// Header with the number of requests left in the window.
+    pub remaining_header: String,
+    /// Header with the window reset, as seconds from now or a Unix timestamp.
+    pub reset_header: String,
+    /// Below this many remaining requests, later requests are spread out until the reset.
+    pub slow_below: u32,
+}
+
+impl Default for RateLimitConfig {
+    fn default() -> Self {
+        Self {
+            remaining_header: "X-RateLimit-Remaining".into(),
+            reset_header: "X-RateLimit-Reset".into(),
+            slow_below: 5,
+        }
+    }
+}
+
+/// Callback receiving unparsed response bodies; see [`PolarisConfig::with_raw_responses`].
+#[derive(Clone)]
+pub struct RawResponseHook(Arc<dyn Fn(&str) + Send + Sync>);
+
+impl RawResponseHook {
+    pub fn new(hook: impl Fn(&str) + Send + Sync + 'static) -> Self {
+        Self(Arc::new(hook))
+    }
+
+    pub(crate) fn call(&self, body: &str) {
+        (self.0)(body)
+    }
+}
+
+impl std::fmt::Debug for RawResponseHook {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("RawResponseHook")
+    }
+}
+
+impl Default for PolarisConfig {
+    fn default() -> Self {
+        Self {
+            base_url: String::new(),
+            api_token: String::new(),
+            extra_query: Vec::new(),
+            verbose: false,
+            tls: TlsConfig::default(),
+            timeout: std::time::Duration::from_secs(120),
+            max_retries: 2,
+            timeout_retries: 1,
+            max_total_retry_time: None,
+            raw_responses: None,
+            issue_includes: DEFAULT_ISSUE_INCLUDES.iter().map(|s| s.to_string()).collect(),
+            rate_limit: RateLimitConfig::default(),
+            language: "en".into(),
+            pool_max_idle_per_host: DEFAULT_POOL_MAX_IDLE_PER_HOST,
+            pool_idle_timeout: Some(std::time::Duration::from_secs(90)),
+            cache_dir: None,
+            trace: None,
+            read_only: false,
+        }
+    }
+}
+
+/// TLS settings applied to every HTTP client.
+#[derive(Debug, Clone, Default)]
+pub struct TlsConfig {
+    /// Skip certificate verification entirely. Only for self-signed test instances.
+    pub insecure: bool,
+    /// Root certificates trusted in addition to the system roots.
+    pub root_certs: Vec<reqwest::Certificate>,
+}
+
+impl TlsConfig {
+    /// Trust the certificate(s) in a PEM file in addition to the system roots.
+    pub fn add_ca_cert_file(&mut self, path: &std::path::Path) -> Result<()> {
+        let pem = std::fs::read(path)
+            .map_err(|e| PolarisError::Other(format!("cannot read CA certificate {}: {e}", path.display())))?;
+        let certs = reqwest::Certificate::from_pem_bundle(&pem)
+            .map_err(|e| PolarisError::Other(format!("invalid CA certificate {}: {e}", path.display())))?;
+        if certs.is_empty() {
+            return Err(PolarisError::Other(format!(
+                "no PEM certificates found in {}",
+                path.display()
+            )));
+        }
+        self.root_certs.extend(certs);
+        Ok(())
+    }
+
+    /// Trust every `.pem`/`.crt` file in `dir` (not recursive), as corporate trust
+    /// stores are often distributed. Files that can't be read or hold no valid PEM
+    /// certificate are skipped with a warning on stderr. Returns the number of
+    /// certificates added.
+    pub fn add_ca_cert_dir(&mut self, dir: &std::path::Path) -> Result<usize> {
+        let entries = std::fs::read_dir(dir)
+            .map_err(|e| PolarisError::Other(format!("cannot read CA certificate directory {}: {e}", dir.display())))?;
+        let mut paths: Vec<std::path::PathBuf> = entries
+            .filter_map(|entry| entry.ok().map(|e| e.path()))
+            .filter(|path| {
+                path.is_file()
+                    && path
+                        .extension()
+                        .and_then(|e| e.to_str())
+                        .is_some_and(|e| e.eq_ignore_ascii_case("pem") || e.eq_ignore_ascii_case("crt"))
+            })
+            .collect();
+        paths.sort();
+
+        let before = self.root_certs.len();
+        for path in paths {
+            if let Err(e) = self.add_ca_cert_file(&path) {
+                eprintln!("warning: skipping {e}");
+            }
+        }
+        Ok(self.root_certs.len() - before)
+    }
 }
 
 impl PolarisConfig {
+    /// Build a config from the same environment variables the CLI reads:
+    ///
+    /// - `POLARIS_BASE_URL` (required)
+    /// - `POLARIS_API_TOKEN`, or else the contents of the file named by `POLARIS_API_TOKEN_FILE`
+    /// - `POLARIS_CA_CERT`: extra PEM root certificate(s) to trust
+    /// - `POLARIS_CA_CERT_DIR`: a directory of `.pem`/`.crt` root certificates to trust
+    /// - `POLARIS_INSECURE`: disable certificate verification unless empty or
+    ///   `0`/`false`/`no`/`off`
+    /// - `POLARIS_TIMEOUT`: per-attempt timeout in seconds
+    ///
+    /// Proxies come from the standard `HTTPS_PROXY`/`HTTP_PROXY`/`NO_PROXY` variables,
+    /// which reqwest reads itself. Everything else keeps its [`Default`] value.
     pub fn from_env() -> Result<Self> {
-        let api_token = std::env::var("POLARIS_API_TOKEN")
-            .map_err(|_| PolarisError::Other("POLARIS_API_TOKEN env var not set".into()))?;
-        let base_url = std::env::var("POLARIS_BASE_URL")
-            .unwrap_or_else(|_| "https://your-instance.polaris.blackduck.com".into());
-        Ok(Self {
+        let var = |name: &str| std::env::var(name).ok().filter(|v| !v.trim().is_empty());
+
+        let base_url = var("POLARIS_BASE_URL")
+            .ok_or_else(|| PolarisError::Other("POLARIS_BASE_URL env var not set".into()))?;
+        let api_token = match (var("POLARIS_API_TOKEN"), var("POLARIS_API_TOKEN_FILE")) {
+            (Some(token), _) => token,
+            (None, Some(path)) => std::fs::read_to_string(&path)
+                .map_err(|e| PolarisError::Other(format!("cannot read POLARIS_API_TOKEN_FILE {path}: {e}")))?,
+            (None, None) => {
+                return Err(PolarisError::Other(
+                    "POLARIS_API_TOKEN or POLARIS_API_TOKEN_FILE env var not set".into(),
+                ));
+            }
+        };
+
+        let mut tls = TlsConfig {
+            insecure: var("POLARIS_INSECURE").is_some_and(|v| {
+                !matches!(v.trim().to_ascii_lowercase().as_str(), "0" | "false" | "no" | "off" | "n" | "f")
+            }),
+            ..Default::default()
+        };
+        if let Some(path) = var("POLARIS_CA_CERT") {
+            tls.add_ca_cert_file(std::path::Path::new(&path))?;
+        }
+        if let Some(dir) = var("POLARIS_CA_CERT_DIR") {
+            tls.add_ca_cert_dir(std::path::Path::new(&dir))?;
+        }
+
+        let mut config = Self {
             base_url,
             api_token,
-        })
+            tls,
+            ..Default::default()
+        };
+        if let Some(secs) = var("POLARIS_TIMEOUT") {
+            let secs: u64 = secs
+                .trim()
+                .parse()
+                .ok()
+                .filter(|&s| s > 0)
+                .ok_or_else(|| PolarisError::Other(format!("invalid POLARIS_TIMEOUT `{secs}`: expected seconds")))?;
+            config.timeout = std::time::Duration::from_secs(secs);
+        }
+        Ok(config)
+    }
+
+    /// Set the per-attempt request timeout.
+    pub fn with_timeout(mut self, timeout: std::time::Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Set how many times a 429/5xx response or connection failure is retried.
+    pub fn with_max_retries(mut self, retries: u32) -> Self {
+        self.max_retries = retries;
+        self
+    }
+
+    /// Set how many idle connections are kept per host. Match it to the number of
+    /// concurrent requests you make; more only holds sockets open.
+    pub fn with_pool_max_idle_per_host(mut self, max: usize) -> Self {
+        self.pool_max_idle_per_host = max;
+        self
+    }
+
+    /// Set how long idle connections are kept (`None` for no limit).
+    pub fn with_pool_idle_timeout(mut self, timeout: Option<std::time::Duration>) -> Self {
+        self.pool_idle_timeout = timeout;
+        self
+    }
+
+    /// Set how many times a timed-out request is retried.
+    pub fn with_timeout_retries(mut self, retries: u32) -> Self {
+        self.timeout_retries = retries;
+        self
+    }
+
+    /// Pass the body of every successful API response to `hook` before it is parsed,
+    /// e.g. to dump exactly what the server sent. Paginated calls report each page.
+    pub fn with_raw_responses(mut self, hook: impl Fn(&str) + Send + Sync + 'static) -> Self {
+        self.raw_responses = Some(RawResponseHook::new(hook));
+        self
+    }
+
+    /// Refuse mutating calls; see [`read_only`](Self::read_only).
+    pub fn with_read_only(mut self, read_only: bool) -> Self {
+        self.read_only = read_only;
+        self
+    }
+
+    /// Cache project and branch listings in `dir`, sending `If-None-Match` so an
+    /// unchanged listing comes back as `304 Not Modified` and is read from disk.
+    pub fn with_cache_dir(mut self, dir: impl Into<std::path::PathBuf>) -> Self {
+        self.cache_dir = Some(dir.into());
+        self
+    }
+
+    /// Write a redacted record of every HTTP exchange to `trace` (see [`crate::trace`]),
+    /// e.g. to attach to a support ticket.
+    pub fn with_trace(mut self, trace: crate::trace::TraceLog) -> Self {
+        self.trace = Some(trace);
+        self
+    }
+
+    /// Cap the time spent retrying across every request this client sends, so a
+    /// sustained outage fails a many-request operation fast instead of retrying each
+    /// request in full. The per-request budgets still apply within it.
+    pub fn with_max_total_retry_time(mut self, budget: std::time::Duration) -> Self {
+        self.max_total_retry_time = Some(budget);
+        self
+    }
+
+    /// The retry policy for a new client; the total budget is shared by its clones.
+    fn retry_policy(&self) -> crate::http::RetryPolicy {
+        crate::http::RetryPolicy {
+            max_retries: self.max_retries,
+            timeout_retries: self.timeout_retries,
+            budget: self
+                .max_total_retry_time
+                .map(|limit| Arc::new(crate::http::RetryBudget::new(limit))),
+        }
     }
 }
 
+/// Default idle connections kept per host: enough for the crate's own concurrent
+/// requests (see `get_triage_batch`) to reuse connections.
+pub const DEFAULT_POOL_MAX_IDLE_PER_HOST: usize = TRIAGE_FALLBACK_CONCURRENCY;
+
+/// Related resources included with issues unless [`PolarisConfig::issue_includes`] says otherwise.
+pub const DEFAULT_ISSUE_INCLUDES: &[&str] = &["severity", "issue-type", "tool-domain-service"];
+/// Relationships the issue-query service can include.
+pub const KNOWN_ISSUE_INCLUDES: &[&str] = &[
+    "severity",
+    "issue-type",
+    "tool-domain-service",
+    "tool",
+    "path",
+    "transitions",
+    "related-taxa",
+    "related-indicators",
+    "latest-observed-on-run",
+    "first-detected-on-run",
+    "cwe",
+    "owner",
+];
+/// Always included by `get_issue`, which needs them to build the issue's web URL.
+const ISSUE_DETAIL_INCLUDES: &[&str] = &["path", "transitions"];
+
+/// Largest `page[limit]` the issue-query service accepts.
+pub const MAX_ISSUES_PAGE_SIZE: u32 = 500;
+/// Largest `page[limit]` the triage-query service accepts.
+pub const MAX_TRIAGE_PAGE_SIZE: u32 = 100;
+/// Issue keys sent per `get_triage_batch` request; one page of results each.
+pub const TRIAGE_BATCH_SIZE: usize = MAX_TRIAGE_PAGE_SIZE as usize;
+/// Concurrent single-key requests when `get_triage_batch` falls back.
+const TRIAGE_FALLBACK_CONCURRENCY: usize = 8;
+
 /// High-level client for the BlackDuck Polaris API.
+///
+/// Cloning is cheap: clones share the cached JWT, so handles passed to other tasks
+/// reuse one authenticated session.
+#[derive(Clone)]
 pub struct PolarisClient {
     config: PolarisConfig,
     auth: AuthClient,
     jwt: Arc<RwLock<Option<Zeroizing<String>>>>,
+    /// Held while authenticating, so concurrent callers finding the cache empty
+    /// wait for one authentication instead of each starting their own.
+    auth_lock: Arc<Mutex<()>>,
+    throttle: Arc<crate::http::Throttle>,
+    retry: crate::http::RetryPolicy,
+    http: Arc<std::sync::Mutex<Option<CachedHttp>>>,
 }
 
+/// An authenticated HTTP client and the JWT it was built with.
+type CachedHttp = (Zeroizing<String>, reqwest::Client);
+
 impl PolarisClient {
     pub fn new(config: PolarisConfig) -> Self {
-        let auth = AuthClient::new(&config.base_url);
+        let auth = AuthClient::new(&config.base_url)
+            .with_tls(config.tls.clone())
+            .with_trace(config.trace.clone());
+        let throttle = crate::http::Throttle::new(config.rate_limit.clone(), config.verbose);
+        let retry = config.retry_policy();
         Self {
+            retry,
             config,
             auth,
             jwt: Arc::new(RwLock::new(None)),
+            auth_lock: Arc::new(Mutex::new(())),
+            throttle: Arc::new(throttle),
+            http: Arc::new(std::sync::Mutex::new(None)),
         }
     }
 
@@ -55,20 +391,52 @@ impl PolarisClient {
 
     /// Get the current JWT, authenticating if needed.
     async fn get_jwt(&self) -> Result<String> {
-        {
-            let jwt = self.jwt.read().await;
-            if let Some(ref j) = *jwt {
-                return Ok((**j).clone());
-            }
+        if let Some(jwt) = self.cached_jwt().await {
+            return Ok(jwt);
+        }
+        let _guard = self.auth_lock.lock().await;
+        // Another task may have authenticated while we waited for the lock.
+        if let Some(jwt) = self.cached_jwt().await {
+            return Ok(jwt);
         }
         self.authenticate().await
     }
 
+    async fn cached_jwt(&self) -> Option<String> {
+        self.jwt.read().await.as_ref().map(|j| (**j).clone())
+    }
+
+    fn url(&self, path: &str) -> Result<reqwest::Url> {
+        let url = crate::http::api_url(&self.config.base_url, path)?;
+        Ok(crate::http::finish_url(url, &self.config.extra_query, self.config.verbose))
+    }
+
     fn common_client(&self, jwt: &str) -> Result<CommonClient> {
-        CommonClient::new(&self.config.base_url, jwt)
+        Ok(CommonClient::from_http(self.authed_http(jwt)?, &self.config.base_url)
+            .with_debug(self.config.extra_query.clone(), self.config.verbose)
+            .with_retry(self.retry.clone(), self.throttle.clone())
+            .with_raw_responses(self.config.raw_responses.clone())
+            .with_cache(self.config.cache_dir.clone())
+            .with_trace(self.config.trace.clone()))
     }
 
+    /// HTTP client carrying `jwt`. It is built once per JWT and shared by clones, so
+    /// connections are pooled across calls.
     fn authed_http(&self, jwt: &str) -> Result<reqwest::Client> {
+        if let Ok(cached) = self.http.lock()
+            && let Some((cached_jwt, http)) = cached.as_ref()
+            && cached_jwt.as_str() == jwt
+        {
+            return Ok(http.clone());
+        }
+        let http = self.build_http(jwt)?;
+        if let Ok(mut cached) = self.http.lock() {
+            *cached = Some((Zeroizing::new(jwt.to_string()), http.clone()));
+        }
+        Ok(http)
+    }
+
+    fn build_http(&self, jwt: &str) -> Result<reqwest::Client> {
         let mut headers = reqwest::header::HeaderMap::new();
         headers.insert(
             reqwest::header::AUTHORIZATION,
@@ -79,65 +447,85 @@ impl PolarisClient {
             reqwest::header::ACCEPT,
             reqwest::header::HeaderValue::from_static("application/vnd.api+json"),
         );
-        reqwest::Client::builder()
+        crate::http::client_builder(&self.config.tls)
             .default_headers(headers)
-            .timeout(std::time::Duration::from_secs(120))
+            .timeout(self.config.timeout)
             .connect_timeout(std::time::Duration::from_secs(30))
+            .pool_max_idle_per_host(self.config.pool_max_idle_per_host)
+            .pool_idle_timeout(self.config.pool_idle_timeout)
             .build()
             .map_err(PolarisError::Http)
     }
 
+    /// Fail with [`PolarisError::ReadOnly`] in read-only mode. Every method that changes
+    /// server state calls this first, before authenticating.
+    fn ensure_writable(&self, what: &str) -> Result<()> {
+        if self.config.read_only {
+            return Err(PolarisError::ReadOnly(what.to_string()));
+        }
+        Ok(())
+    }
+
+    async fn send(&self, request: reqwest::RequestBuilder) -> Result<reqwest::Response> {
+        crate::http::send_with_retry(request, &self.retry, &self.throttle, self.config.trace.as_ref()).await
+    }
+
     // ── Projects ──
 
-    /// List projects, optionally filtering by name.
+    /// List projects, optionally filtering by name and owner.
     pub async fn list_projects(
         &self,
         name_filter: Option<&str>,
+        owner_filter: Option<&str>,
         limit: u32,
         offset: u32,
     ) -> Result<JsonApiResponse<Project>> {
         let jwt = self.get_jwt().await?;
         self.common_client(&jwt)?
-            .list_projects(name_filter, limit, offset)
+            .list_projects(name_filter, owner_filter, limit, offset)
             .await
     }
 
-    /// Fetch all projects by auto-paginating.
+    /// Fetch all projects by auto-paginating. Included resources (the projects'
+    /// branches) from every page are kept, deduplicated by type and ID.
     pub async fn list_all_projects(
         &self,
         name_filter: Option<&str>,
+        owner_filter: Option<&str>,
         page_size: u32,
     ) -> Result<JsonApiResponse<Project>> {
-        let mut all_data = Vec::new();
-        let mut all_included = Vec::new();
-        let mut offset = 0u32;
-        let mut total = None;
-
-        loop {
-            let resp = self.list_projects(name_filter, page_size, offset).await?;
-            if let Some(ref meta) = resp.meta {
-                total = meta.total;
-            }
-            let count = resp.data.len();
-            all_data.extend(resp.data);
-            all_included.extend(resp.included);
-            if count < page_size as usize {
-                break;
-            }
-            offset += page_size;
-            if let Some(t) = total && offset as u64 >= t {
-                break;
-            }
-        }
-
+        let page_size = crate::http::clamp_page_size("projects", page_size, crate::common::MAX_COMMON_PAGE_SIZE);
+        let Page { data, included, total, .. } = paginate_all(page_size, None, None, |at| async move {
+            self.list_projects(name_filter, owner_filter, page_size, at.offset())
+                .await
+                .map(Page::from)
+        })
+        .await?;
         Ok(JsonApiResponse {
-            data: all_data,
-            included: all_included,
             meta: total.map(|t| crate::common::PaginationMeta {
                 offset: Some(0),
-                limit: None,
+                limit: Some(data.len() as u64),
                 total: Some(t),
             }),
+            data,
+            included,
+        })
+    }
+
+    /// A [`Paginator`] over projects, for fetching one page at a time.
+    pub fn projects_paginator(
+        &self,
+        name_filter: Option<&str>,
+        owner_filter: Option<&str>,
+        page_size: u32,
+    ) -> Paginator<'_, Project> {
+        let page_size = crate::http::clamp_page_size("projects", page_size, crate::common::MAX_COMMON_PAGE_SIZE);
+        let (name, owner) = (name_filter.map(str::to_string), owner_filter.map(str::to_string));
+        Paginator::new(page_size, move |offset| {
+            let (name, owner) = (name.clone(), owner.clone());
+            Box::pin(async move {
+                self.list_projects(name.as_deref(), owner.as_deref(), page_size, offset).await
+            })
         })
     }
 
@@ -154,40 +542,56 @@ impl PolarisClient {
             .await
     }
 
-    /// Fetch all branches for a project by auto-paginating.
+    /// Get a single run, e.g. to poll its status.
+    pub async fn get_run(&self, run_id: &str) -> Result<JsonApiSingleResponse<Run>> {
+        let jwt = self.get_jwt().await?;
+        self.common_client(&jwt)?.get_run(run_id).await
+    }
+
+    /// List runs for a project, optionally narrowed to one revision.
+    pub async fn list_runs(
+        &self,
+        project_id: &str,
+        revision_id: Option<&str>,
+        limit: u32,
+        offset: u32,
+    ) -> Result<JsonApiResponse<Run>> {
+        let jwt = self.get_jwt().await?;
+        self.common_client(&jwt)?
+            .list_runs(project_id, revision_id, limit, offset)
+            .await
+    }
+
+    /// Fetch all branches for a project by auto-paginating. Included resources from
+    /// every page are kept, deduplicated by type and ID.
     pub async fn list_all_branches(
         &self,
         project_id: &str,
         page_size: u32,
     ) -> Result<JsonApiResponse<Branch>> {
-        let mut all_data = Vec::new();
-        let mut offset = 0u32;
-        let mut total = None;
-
-        loop {
-            let resp = self.list_branches(project_id, page_size, offset).await?;
-            if let Some(ref meta) = resp.meta {
-                total = meta.total;
-            }
-            let count = resp.data.len();
-            all_data.extend(resp.data);
-            if count < page_size as usize {
-                break;
-            }
-            offset += page_size;
-            if let Some(t) = total && offset as u64 >= t {
-                break;
-            }
-        }
-
+        let page_size = crate::http::clamp_page_size("branches", page_size, crate::common::MAX_COMMON_PAGE_SIZE);
+        let Page { data, included, total, .. } = paginate_all(page_size, None, None, |at| async move {
+            self.list_branches(project_id, page_size, at.offset()).await.map(Page::from)
+        })
+        .await?;
         Ok(JsonApiResponse {
-            data: all_data,
-            included: vec![],
             meta: total.map(|t| crate::common::PaginationMeta {
                 offset: Some(0),
-                limit: None,
+                limit: Some(data.len() as u64),
                 total: Some(t),
             }),
+            data,
+            included,
+        })
+    }
+
+    /// A [`Paginator`] over a project's branches, for fetching one page at a time.
+    pub fn branches_paginator(&self, project_id: &str, page_size: u32) -> Paginator<'_, Branch> {
+        let page_size = crate::http::clamp_page_size("branches", page_size, crate::common::MAX_COMMON_PAGE_SIZE);
+        let project_id = project_id.to_string();
+        Paginator::new(page_size, move |offset| {
+            let project_id = project_id.clone();
+            Box::pin(async move { self.list_branches(&project_id, page_size, offset).await })
         })
     }
 
@@ -201,30 +605,78 @@ impl PolarisClient {
         run_ids: Option<&[&str]>,
         limit: u32,
         offset: u32,
+    ) -> Result<IssuesResponse> {
+        self.query_issues(project_id, branch_id, run_ids, &IssueFilter::default(), limit, offset)
+            .await
+    }
+
+    /// List issues matching server-side `filter`.
+    pub async fn query_issues(
+        &self,
+        project_id: &str,
+        branch_id: Option<&str>,
+        run_ids: Option<&[&str]>,
+        filter: &IssueFilter,
+        limit: u32,
+        offset: u32,
+    ) -> Result<IssuesResponse> {
+        self.query_issues_at(project_id, branch_id, run_ids, filter, limit, PageAt::Offset(offset))
+            .await
+    }
+
+    /// Like [`query_issues`](Self::query_issues), but continue from a cursor
+    /// (see [`IssuesResponse::next_cursor`]) instead of an offset. Cursors stay valid
+    /// while issues are added or closed, so a resumed scan neither skips nor repeats rows.
+    pub async fn query_issues_after(
+        &self,
+        project_id: &str,
+        branch_id: Option<&str>,
+        run_ids: Option<&[&str]>,
+        filter: &IssueFilter,
+        limit: u32,
+        cursor: &str,
+    ) -> Result<IssuesResponse> {
+        self.query_issues_at(project_id, branch_id, run_ids, filter, limit, PageAt::After(cursor.to_string()))
+            .await
+    }
+
+    async fn query_issues_at(
+        &self,
+        project_id: &str,
+        branch_id: Option<&str>,
+        run_ids: Option<&[&str]>,
+        filter: &IssueFilter,
+        limit: u32,
+        at: PageAt,
     ) -> Result<IssuesResponse> {
         let jwt = self.get_jwt().await?;
         let http = self.authed_http(&jwt)?;
 
-        let mut url = format!(
-            "{}/api/query/v1/issues?project-id={}&page[limit]={limit}&page[offset]={offset}",
-            self.config.base_url,
+        let limit = crate::http::clamp_page_size("issues", limit, MAX_ISSUES_PAGE_SIZE);
+        let mut path = format!(
+            "api/query/v1/issues?project-id={}&page[limit]={limit}",
             urlencoding::encode(project_id),
         );
+        match at {
+            PageAt::Offset(offset) => path.push_str(&format!("&page[offset]={offset}")),
+            PageAt::After(cursor) => path.push_str(&format!("&page[after]={}", urlencoding::encode(&cursor))),
+        }
 
         if let Some(bid) = branch_id {
-            url.push_str(&format!("&branch-id={}", urlencoding::encode(bid)));
+            path.push_str(&format!("&branch-id={}", urlencoding::encode(bid)));
         }
         if let Some(rids) = run_ids {
             for rid in rids {
-                url.push_str(&format!("&run-id[]={}", urlencoding::encode(rid)));
+                path.push_str(&format!("&run-id[]={}", urlencoding::encode(rid)));
             }
         }
 
-        // Include common relationships
-        url.push_str("&include[issue][]=severity&include[issue][]=issue-type&include[issue][]=tool-domain-service");
+        filter.push_query(&mut path);
+
+        push_includes(&mut path, self.config.issue_includes.iter().map(String::as_str));
 
-        let resp = http.get(&url).send().await?;
-        check_response(resp).await
+        let resp = self.send(http.get(self.url(&path)?)).await?;
+        check_response(resp, self.config.raw_responses.as_ref()).await
     }
 
     /// Fetch all issues by auto-paginating.
@@ -235,41 +687,114 @@ impl PolarisClient {
         run_ids: Option<&[&str]>,
         page_size: u32,
     ) -> Result<IssuesResponse> {
-        let mut all_data = Vec::new();
-        let mut all_included = Vec::new();
-        let mut offset = 0u32;
-        let mut total = None;
-
-        loop {
-            let resp = self
-                .list_issues(project_id, branch_id, run_ids, page_size, offset)
-                .await?;
-            if let Some(ref meta) = resp.meta {
-                total = meta.total;
-            }
-            let count = resp.data.len();
-            all_data.extend(resp.data);
-            all_included.extend(resp.included);
-            if count < page_size as usize {
-                break;
-            }
-            offset += page_size;
-            if let Some(t) = total && offset as u64 >= t {
-                break;
-            }
-        }
+        self.list_issues_up_to(project_id, branch_id, run_ids, &IssueFilter::default(), page_size, None)
+            .await
+    }
+
+    /// Auto-paginate issues, stopping once `max_items` have been fetched.
+    ///
+    /// `meta.total` still reports the server's total, so callers can tell when the
+    /// result was truncated. When the server returns cursors (`page[after]` in
+    /// `links.next`) they are followed instead of offsets, and the last one is kept
+    /// in `links.next` for resuming with [`query_issues_after`](Self::query_issues_after).
+    pub async fn list_issues_up_to(
+        &self,
+        project_id: &str,
+        branch_id: Option<&str>,
+        run_ids: Option<&[&str]>,
+        filter: &IssueFilter,
+        page_size: u32,
+        max_items: Option<usize>,
+    ) -> Result<IssuesResponse> {
+        self.list_issues_from(project_id, branch_id, run_ids, filter, page_size, max_items, None)
+            .await
+    }
+
+    /// [`list_issues_up_to`](Self::list_issues_up_to), resuming a scan from the cursor
+    /// an earlier call left in `links.next` ([`IssuesResponse::next_cursor`]).
+    #[allow(clippy::too_many_arguments)]
+    pub async fn resume_issues(
+        &self,
+        project_id: &str,
+        branch_id: Option<&str>,
+        run_ids: Option<&[&str]>,
+        filter: &IssueFilter,
+        page_size: u32,
+        max_items: Option<usize>,
+        cursor: &str,
+    ) -> Result<IssuesResponse> {
+        self.list_issues_from(project_id, branch_id, run_ids, filter, page_size, max_items, Some(cursor.to_string()))
+            .await
+    }
 
+    #[allow(clippy::too_many_arguments)]
+    async fn list_issues_from(
+        &self,
+        project_id: &str,
+        branch_id: Option<&str>,
+        run_ids: Option<&[&str]>,
+        filter: &IssueFilter,
+        page_size: u32,
+        max_items: Option<usize>,
+        cursor: Option<String>,
+    ) -> Result<IssuesResponse> {
+        let page_size = crate::http::clamp_page_size("issues", page_size, MAX_ISSUES_PAGE_SIZE);
+        let page = paginate_all(page_size, max_items, cursor, |at| async move {
+            self.query_issues_at(project_id, branch_id, run_ids, filter, page_size, at)
+                .await
+                .map(|resp| Page {
+                    next_cursor: resp.next_cursor(),
+                    total: resp.meta.and_then(|m| m.total),
+                    data: resp.data,
+                    included: resp.included,
+                })
+        })
+        .await?;
         Ok(IssuesResponse {
-            data: all_data,
-            included: all_included,
-            meta: total.map(|t| IssuesMeta {
+            meta: page.total.map(|t| IssuesMeta {
                 offset: Some(0),
-                limit: None,
+                limit: Some(page.data.len() as u64),
                 total: Some(t),
             }),
+            links: page.next_cursor.map(|cursor| PageLinks {
+                next: Some(format!("?page[after]={}", urlencoding::encode(&cursor))),
+            }),
+            data: page.data,
+            included: page.included,
         })
     }
 
+    /// Count the issues matching `filter` from a single one-item page, without
+    /// paginating. `None` when the server doesn't report `meta.total`.
+    pub async fn count_issues(
+        &self,
+        project_id: &str,
+        branch_id: Option<&str>,
+        filter: &IssueFilter,
+    ) -> Result<Option<u64>> {
+        let resp = self.query_issues(project_id, branch_id, None, filter, 1, 0).await?;
+        Ok(resp.meta.and_then(|m| m.total))
+    }
+
+    /// Find the issue with the given finding key on a branch.
+    pub async fn find_issue_by_finding_key(
+        &self,
+        project_id: &str,
+        branch_id: Option<&str>,
+        finding_key: &str,
+    ) -> Result<Issue> {
+        let filter = IssueFilter {
+            finding_key: Some(finding_key.to_string()),
+            ..Default::default()
+        };
+        self.query_issues(project_id, branch_id, None, &filter, 1, 0)
+            .await?
+            .data
+            .into_iter()
+            .next()
+            .ok_or_else(|| PolarisError::NotFound(format!("no issue with finding key {finding_key}")))
+    }
+
     /// Get a single issue by ID.
     pub async fn get_issue(
         &self,
@@ -280,51 +805,57 @@ impl PolarisClient {
         let jwt = self.get_jwt().await?;
         let http = self.authed_http(&jwt)?;
 
-        let url = format!(
-            "{}/api/query/v1/issues/{}?project-id={}&branch-id={}&include[issue][]=severity&include[issue][]=issue-type&include[issue][]=tool-domain-service&include[issue][]=path&include[issue][]=transitions",
-            self.config.base_url,
+        let mut path = format!(
+            "api/query/v1/issues/{}?project-id={}&branch-id={}",
             urlencoding::encode(issue_id),
             urlencoding::encode(project_id),
             urlencoding::encode(branch_id),
         );
-
-        let resp = http.get(&url).send().await?;
-        check_response(resp).await
+        let extra = ISSUE_DETAIL_INCLUDES
+            .iter()
+            .copied()
+            .filter(|inc| !self.config.issue_includes.iter().any(|i| i == inc));
+        push_includes(&mut path, self.config.issue_includes.iter().map(String::as_str).chain(extra));
+
+        let resp = self.send(http.get(self.url(&path)?)).await?;
+        check_response(resp, self.config.raw_responses.as_ref()).await
     }
 
     // ── Code Analysis Events ──
 
     /// Get the event tree with source code snippets for a finding.
+    ///
+    /// Event descriptions are requested in `language` (an `Accept-Language` value),
+    /// falling back to [`PolarisConfig::language`].
     pub async fn get_events_with_source(
         &self,
         finding_key: &str,
         run_id: &str,
         occurrence_number: Option<u32>,
         max_depth: Option<u32>,
+        language: Option<&str>,
     ) -> Result<serde_json::Value> {
         let jwt = self.get_jwt().await?;
         let http = self.authed_http(&jwt)?;
 
-        let mut url = format!(
-            "{}/api/code-analysis/v0/events-with-source?finding-key={}&run-id={}",
-            self.config.base_url,
+        let mut path = format!(
+            "api/code-analysis/v0/events-with-source?finding-key={}&run-id={}",
             urlencoding::encode(finding_key),
             urlencoding::encode(run_id),
         );
         if let Some(occ) = occurrence_number {
-            url.push_str(&format!("&occurrence-number={occ}"));
+            path.push_str(&format!("&occurrence-number={occ}"));
         }
         if let Some(depth) = max_depth {
-            url.push_str(&format!("&max-depth={depth}"));
+            path.push_str(&format!("&max-depth={depth}"));
         }
 
-        let resp = http
-            .get(&url)
-            .header("Accept-Language", "en")
-            .header("Accept", "application/json")
-            .send()
-            .await?;
-        check_response(resp).await
+        let request = http
+            .get(self.url(&path)?)
+            .header("Accept-Language", language.unwrap_or(&self.config.language))
+            .header("Accept", "application/json");
+        let resp = self.send(request).await?;
+        check_response(resp, self.config.raw_responses.as_ref()).await
     }
 
     /// Get full source code for a file in a run.
@@ -336,27 +867,55 @@ impl PolarisClient {
         let jwt = self.get_jwt().await?;
         let http = self.authed_http(&jwt)?;
 
-        let url = format!(
-            "{}/api/code-analysis/v0/source-code?run-id={}&path={}",
-            self.config.base_url,
+        let path = format!(
+            "api/code-analysis/v0/source-code?run-id={}&path={}",
             urlencoding::encode(run_id),
             urlencoding::encode(path),
         );
 
-        let resp = http
-            .get(&url)
-            .header("Accept", "text/plain")
-            .send()
-            .await?;
-        let status = resp.status();
-        if !status.is_success() {
-            let detail = resp.text().await.unwrap_or_default();
-            return Err(PolarisError::Api {
-                status: status.as_u16(),
-                detail,
-            });
+        let request = http
+            .get(self.url(&path)?)
+            .header("Accept", "text/plain");
+        let resp = self.send(request).await?;
+        read_body(resp, self.config.raw_responses.as_ref()).await
+    }
+
+    /// Stream source code for a file in a run into `out`, chunk by chunk, returning
+    /// the number of bytes written. Unlike [`get_source_code`](Self::get_source_code)
+    /// the file is never held in memory whole, so prefer this for large or generated
+    /// files. The raw-response hook is not called for streamed bodies.
+    pub async fn get_source_code_to_writer<W>(
+        &self,
+        run_id: &str,
+        path: &str,
+        out: &mut W,
+    ) -> Result<u64>
+    where
+        W: tokio::io::AsyncWrite + Unpin + ?Sized,
+    {
+        use tokio::io::AsyncWriteExt;
+
+        let jwt = self.get_jwt().await?;
+        let http = self.authed_http(&jwt)?;
+
+        let path = format!(
+            "api/code-analysis/v0/source-code?run-id={}&path={}",
+            urlencoding::encode(run_id),
+            urlencoding::encode(path),
+        );
+
+        let request = http
+            .get(self.url(&path)?)
+            .header("Accept", "text/plain");
+        let mut resp = check_status(self.send(request).await?).await?;
+        let write_err = |e: std::io::Error| PolarisError::Other(format!("failed to write source code: {e}"));
+        let mut written = 0;
+        while let Some(chunk) = resp.chunk().await? {
+            out.write_all(&chunk).await.map_err(write_err)?;
+            written += chunk.len() as u64;
         }
-        Ok(resp.text().await?)
+        out.flush().await.map_err(write_err)?;
+        Ok(written)
     }
 
     // ── Triage ──
@@ -370,15 +929,86 @@ impl PolarisClient {
         let jwt = self.get_jwt().await?;
         let http = self.authed_http(&jwt)?;
 
-        let url = format!(
-            "{}/api/triage-query/v1/triage-current?filter[triage-current][project-id][$eq]={}&filter[triage-current][issue-key][$eq]={}",
-            self.config.base_url,
+        let path = format!(
+            "api/triage-query/v1/triage-current?filter[triage-current][project-id][$eq]={}&filter[triage-current][issue-key][$eq]={}",
             urlencoding::encode(project_id),
             urlencoding::encode(issue_key),
         );
 
-        let resp = http.get(&url).send().await?;
-        check_response(resp).await
+        let resp = self.send(http.get(self.url(&path)?)).await?;
+        check_response(resp, self.config.raw_responses.as_ref()).await
+    }
+
+    /// Get current triage for many issues, with one `$in`-filtered request per
+    /// [`TRIAGE_BATCH_SIZE`] keys.
+    ///
+    /// The result has one entry per key, in the order given: the issue's current
+    /// triage, `None` when it has none, or the error that kept it from being read. A
+    /// batch the server fails is retried one key at a time, a few concurrently, so one
+    /// bad key doesn't cost the others their results; if the server rejects the `$in`
+    /// filter itself, every remaining key is queried that way.
+    pub async fn get_triage_batch(
+        &self,
+        project_id: &str,
+        issue_keys: &[&str],
+    ) -> Vec<Result<Option<TriageCurrent>>> {
+        let mut results = Vec::with_capacity(issue_keys.len());
+        let mut batch_supported = true;
+        for chunk in issue_keys.chunks(TRIAGE_BATCH_SIZE) {
+            if batch_supported {
+                match self.get_triage_in(project_id, chunk).await {
+                    Ok(resp) => {
+                        let by_key: std::collections::HashMap<&str, &TriageCurrent> =
+                            resp.data.iter().map(|tc| (tc.attributes.issue_key.as_str(), tc)).collect();
+                        results.extend(chunk.iter().map(|key| Ok(by_key.get(key).map(|tc| (*tc).clone()))));
+                        continue;
+                    }
+                    Err(PolarisError::Api { status: 400, .. }) => batch_supported = false,
+                    Err(_) => {}
+                }
+            }
+            results.extend(self.get_triage_each(project_id, chunk).await);
+        }
+        results
+    }
+
+    async fn get_triage_in(&self, project_id: &str, issue_keys: &[&str]) -> Result<TriageCurrentResponse> {
+        let jwt = self.get_jwt().await?;
+        let http = self.authed_http(&jwt)?;
+
+        let keys: Vec<_> = issue_keys.iter().map(|k| urlencoding::encode(k)).collect();
+        let path = format!(
+            "api/triage-query/v1/triage-current?filter[triage-current][project-id][$eq]={}&filter[triage-current][issue-key][$in]={}&page[limit]={MAX_TRIAGE_PAGE_SIZE}",
+            urlencoding::encode(project_id),
+            keys.join(","),
+        );
+
+        let resp = self.send(http.get(self.url(&path)?)).await?;
+        check_response(resp, self.config.raw_responses.as_ref()).await
+    }
+
+    /// [`get_triage`](Self::get_triage) for each key, in the order given.
+    async fn get_triage_each(&self, project_id: &str, issue_keys: &[&str]) -> Vec<Result<Option<TriageCurrent>>> {
+        let mut results: Vec<Option<Result<Option<TriageCurrent>>>> = issue_keys.iter().map(|_| None).collect();
+        for (group_index, group) in issue_keys.chunks(TRIAGE_FALLBACK_CONCURRENCY).enumerate() {
+            let mut tasks = tokio::task::JoinSet::new();
+            for (i, key) in group.iter().enumerate() {
+                let client = self.clone();
+                let index = group_index * TRIAGE_FALLBACK_CONCURRENCY + i;
+                let (project_id, key) = (project_id.to_string(), key.to_string());
+                tasks.spawn(async move { (index, client.get_triage(&project_id, &key).await) });
+            }
+            while let Some(joined) = tasks.join_next().await {
+                if let Ok((index, resp)) = joined {
+                    results[index] = Some(resp.map(|resp| resp.data.into_iter().next()));
+                }
+            }
+        }
+        // A task that panicked leaves its slot empty.
+        results
+            .into_iter()
+            .map(|r| r.unwrap_or_else(|| Err(PolarisError::Other("triage query task failed".to_string()))))
+            .collect()
     }
 
     /// Update triage for one or more issues.
@@ -387,25 +1017,18 @@ impl PolarisClient {
         project_id: &str,
         issue_keys: &[&str],
         triage_values: &TriageValues,
-    ) -> Result<serde_json::Value> {
+    ) -> Result<TriageUpdateResponse> {
+        self.ensure_writable("triage update")?;
         let jwt = self.get_jwt().await?;
         let http = self.authed_http(&jwt)?;
 
-        let url = format!(
-            "{}/api/triage-command/v1/triage-issues",
-            self.config.base_url
-        );
+        let path = "api/triage-command/v1/triage-issues";
 
-        let mut triage_map = serde_json::Map::new();
-        if let Some(ref dismiss) = triage_values.dismiss {
-            triage_map.insert("DISMISS".into(), serde_json::Value::String(dismiss.clone()));
-        }
-        if let Some(ref owner) = triage_values.owner {
-            triage_map.insert("OWNER".into(), serde_json::Value::String(owner.clone()));
-        }
-        if let Some(ref comment) = triage_values.commentary {
-            triage_map.insert("COMMENTARY".into(), serde_json::Value::String(comment.clone()));
-        }
+        let triage_map: serde_json::Map<String, serde_json::Value> = triage_values
+            .pairs()
+            .into_iter()
+            .map(|(name, value)| (name.to_string(), serde_json::Value::String(value.to_string())))
+            .collect();
 
         let body = serde_json::json!({
             "data": {
@@ -418,14 +1041,64 @@ impl PolarisClient {
             }
         });
 
-        let resp = http
-            .post(&url)
+        // Not retried: a repeated POST would add the commentary twice.
+        let request = http
+            .post(self.url(path)?)
             .header("Content-Type", "application/vnd.api+json")
-            .json(&body)
-            .send()
-            .await?;
+            .json(&body);
+        let resp = crate::trace::send(request, self.config.trace.as_ref()).await?;
 
-        check_response(resp).await
+        check_response(resp, self.config.raw_responses.as_ref()).await
+    }
+
+    /// Check whether a triage update is reflected in the issues' current triage.
+    ///
+    /// [`update_triage`](Self::update_triage) sends its POST once, so a timeout or
+    /// dropped connection leaves it unknown whether the server applied the change.
+    /// This re-reads the current triage and reports, per key, whether every value in
+    /// `triage_values` is now set. A value that was already set before the update also
+    /// counts as applied.
+    pub async fn verify_triage(
+        &self,
+        project_id: &str,
+        issue_keys: &[&str],
+        triage_values: &TriageValues,
+    ) -> Vec<TriageOutcome> {
+        let current = self.get_triage_batch(project_id, issue_keys).await;
+        let expected = triage_values.pairs();
+        issue_keys
+            .iter()
+            .zip(current)
+            .map(|(key, current)| {
+                let tc = match current {
+                    Ok(tc) => tc,
+                    Err(e) => {
+                        return TriageOutcome {
+                            issue_key: key.to_string(),
+                            status: TriageOutcomeStatus::Unknown,
+                            reason: Some(format!("could not read current triage: {e}")),
+                        };
+                    }
+                };
+                let missing: Vec<&str> = expected
+                    .iter()
+                    .filter(|(name, value)| !tc.as_ref().is_some_and(|tc| tc.attributes.has_value(name, value)))
+                    .map(|(name, _)| *name)
+                    .collect();
+                TriageOutcome {
+                    issue_key: key.to_string(),
+                    status: if missing.is_empty() {
+                        TriageOutcomeStatus::Applied
+                    } else {
+                        TriageOutcomeStatus::Failed
+                    },
+                    reason: (!missing.is_empty()).then(|| match tc {
+                        Some(_) => format!("current triage does not show {}", missing.join(", ")),
+                        None => "no triage data for this issue".to_string(),
+                    }),
+                }
+            })
+            .collect()
     }
 
     /// Get triage history for an issue.
@@ -439,15 +1112,15 @@ impl PolarisClient {
         let jwt = self.get_jwt().await?;
         let http = self.authed_http(&jwt)?;
 
-        let url = format!(
-            "{}/api/triage-query/v1/triage-history-items?filter[triage-history-items][project-id][$eq]={}&filter[triage-history-items][issue-key][$eq]={}&page[limit]={limit}&page[offset]={offset}",
-            self.config.base_url,
+        let limit = crate::http::clamp_page_size("triage history", limit, MAX_TRIAGE_PAGE_SIZE);
+        let path = format!(
+            "api/triage-query/v1/triage-history-items?filter[triage-history-items][project-id][$eq]={}&filter[triage-history-items][issue-key][$eq]={}&page[limit]={limit}&page[offset]={offset}",
             urlencoding::encode(project_id),
             urlencoding::encode(issue_key),
         );
 
-        let resp = http.get(&url).send().await?;
-        check_response(resp).await
+        let resp = self.send(http.get(self.url(&path)?)).await?;
+        check_response(resp, self.config.raw_responses.as_ref()).await
     }
 
     // ── Metrics & Discovery ──
@@ -462,20 +1135,19 @@ impl PolarisClient {
         let jwt = self.get_jwt().await?;
         let http = self.authed_http(&jwt)?;
 
-        let mut url = format!(
-            "{}/api/query/v1/roll-up-counts?project-id={}&page[limit]=100",
-            self.config.base_url,
+        let mut path = format!(
+            "api/query/v1/roll-up-counts?project-id={}&page[limit]=100",
             urlencoding::encode(project_id),
         );
         if let Some(bid) = branch_id {
-            url.push_str(&format!("&branch-id={}", urlencoding::encode(bid)));
+            path.push_str(&format!("&branch-id={}", urlencoding::encode(bid)));
         }
         if let Some(gb) = group_by {
-            url.push_str(&format!("&group-by={}", urlencoding::encode(gb)));
+            path.push_str(&format!("&group-by={}", urlencoding::encode(gb)));
         }
 
-        let resp = http.get(&url).send().await?;
-        check_response(resp).await
+        let resp = self.send(http.get(self.url(&path)?)).await?;
+        check_response(resp, self.config.raw_responses.as_ref()).await
     }
 
     /// Get issue counts over time, grouped by status or severity.
@@ -491,33 +1163,31 @@ impl PolarisClient {
         let jwt = self.get_jwt().await?;
         let http = self.authed_http(&jwt)?;
 
-        let mut url = format!(
-            "{}/api/query/v1/counts/issues-over-time?project-id={}",
-            self.config.base_url,
+        let mut path = format!(
+            "api/query/v1/counts/issues-over-time?project-id={}",
             urlencoding::encode(project_id),
         );
         if let Some(bid) = branch_id {
-            url.push_str(&format!("&branch-id={}", urlencoding::encode(bid)));
+            path.push_str(&format!("&branch-id={}", urlencoding::encode(bid)));
         }
         if let Some(gb) = group_by {
-            url.push_str(&format!("&group-by={}", urlencoding::encode(gb)));
+            path.push_str(&format!("&group-by={}", urlencoding::encode(gb)));
         }
         if let Some(sd) = start_date {
-            url.push_str(&format!("&start-date={}", urlencoding::encode(sd)));
+            path.push_str(&format!("&start-date={}", urlencoding::encode(sd)));
         }
         if let Some(ed) = end_date {
-            url.push_str(&format!("&end-date={}", urlencoding::encode(ed)));
+            path.push_str(&format!("&end-date={}", urlencoding::encode(ed)));
         }
         if let Some(g) = granularity {
-            url.push_str(&format!("&granularity={}", urlencoding::encode(g)));
+            path.push_str(&format!("&granularity={}", urlencoding::encode(g)));
         }
 
-        let resp = http
-            .get(&url)
-            .header("Accept", "application/json")
-            .send()
-            .await?;
-        check_response(resp).await
+        let request = http
+            .get(self.url(&path)?)
+            .header("Accept", "application/json");
+        let resp = self.send(request).await?;
+        check_response(resp, self.config.raw_responses.as_ref()).await
     }
 
     /// Get issue age metrics (average for outstanding or resolved issues).
@@ -531,16 +1201,15 @@ impl PolarisClient {
         let http = self.authed_http(&jwt)?;
 
         let metric_val = metric.unwrap_or("average-for-outstanding-issues");
-        let url = format!(
-            "{}/api/query/v1/issue-metrics/age?project-id={}&branch-id={}&metric={}&include[issue-metrics-age]=taxon&page[limit]=100",
-            self.config.base_url,
+        let path = format!(
+            "api/query/v1/issue-metrics/age?project-id={}&branch-id={}&metric={}&include[issue-metrics-age]=taxon&page[limit]=100",
             urlencoding::encode(project_id),
             urlencoding::encode(branch_id),
             urlencoding::encode(metric_val),
         );
 
-        let resp = http.get(&url).send().await?;
-        check_response(resp).await
+        let resp = self.send(http.get(self.url(&path)?)).await?;
+        check_response(resp, self.config.raw_responses.as_ref()).await
     }
 
     /// Get available filter keys for issue queries.
@@ -548,13 +1217,10 @@ impl PolarisClient {
         let jwt = self.get_jwt().await?;
         let http = self.authed_http(&jwt)?;
 
-        let url = format!(
-            "{}/api/query/v1/discovery/filter-keys",
-            self.config.base_url,
-        );
+        let path = "api/query/v1/discovery/filter-keys";
 
-        let resp = http.get(&url).send().await?;
-        check_response(resp).await
+        let resp = self.send(http.get(self.url(path)?)).await?;
+        check_response(resp, self.config.raw_responses.as_ref()).await
     }
 
     /// Get available group-by values for issue queries.
@@ -562,14 +1228,52 @@ impl PolarisClient {
         let jwt = self.get_jwt().await?;
         let http = self.authed_http(&jwt)?;
 
-        let url = format!(
-            "{}/api/query/v1/discovery/group-bys",
-            self.config.base_url,
-        );
+        let path = "api/query/v1/discovery/group-bys";
+
+        let resp = self.send(http.get(self.url(path)?)).await?;
+        check_response(resp, self.config.raw_responses.as_ref()).await
+    }
+
+    // ── Raw access ──
+
+    /// GET an arbitrary API path (e.g. `api/query/v1/issues?project-id=...`) with
+    /// authentication handled. An escape hatch for endpoints without a typed method;
+    /// the response shape is whatever the server sends.
+    pub async fn get_raw(&self, path: &str) -> Result<serde_json::Value> {
+        // Resolved first so a path off the base URL's origin fails before logging in.
+        let url = self.url(path)?;
+        let jwt = self.get_jwt().await?;
+        let http = self.authed_http(&jwt)?;
 
-        let resp = http.get(&url).send().await?;
-        check_response(resp).await
+        let resp = self.send(http.get(url)).await?;
+        crate::http::expect_json(&resp)?;
+        raw_json(&read_body(resp, self.config.raw_responses.as_ref()).await?)
     }
+
+    /// POST `body` to an arbitrary API path as `application/vnd.api+json`. Like
+    /// [`update_triage`](Self::update_triage) the request is sent once, never retried.
+    /// An empty response body (e.g. `204 No Content`) comes back as `null`.
+    pub async fn post_raw(&self, path: &str, body: &serde_json::Value) -> Result<serde_json::Value> {
+        self.ensure_writable(&format!("POST {path}"))?;
+        let url = self.url(path)?;
+        let jwt = self.get_jwt().await?;
+        let http = self.authed_http(&jwt)?;
+
+        let request = http
+            .post(url)
+            .header("Content-Type", "application/vnd.api+json")
+            .json(body);
+        let resp = crate::trace::send(request, self.config.trace.as_ref()).await?;
+        crate::http::expect_json(&resp)?;
+        raw_json(&read_body(resp, self.config.raw_responses.as_ref()).await?)
+    }
+}
+
+fn raw_json(body: &str) -> Result<serde_json::Value> {
+    if body.trim().is_empty() {
+        return Ok(serde_json::Value::Null);
+    }
+    crate::http::parse_json(body)
 }
 
 // ── Response types ──
@@ -583,6 +1287,28 @@ pub struct IssuesResponse {
     pub included: Vec<serde_json::Value>,
     #[serde(default)]
     pub meta: Option<IssuesMeta>,
+    #[serde(default)]
+    pub links: Option<PageLinks>,
+}
+
+impl IssuesResponse {
+    /// The `page[after]` cursor from `links.next`, when the server paginates with
+    /// cursors and there are more results.
+    pub fn next_cursor(&self) -> Option<String> {
+        let next = self.links.as_ref()?.next.as_deref()?;
+        let url = url::Url::parse("http://localhost/").ok()?.join(next).ok()?;
+        url.query_pairs()
+            .find(|(key, _)| key == "page[after]")
+            .map(|(_, value)| value.into_owned())
+            .filter(|cursor| !cursor.is_empty())
+    }
+}
+
+/// JSON:API pagination links.
+#[derive(Debug, Default, Deserialize)]
+pub struct PageLinks {
+    #[serde(default)]
+    pub next: Option<String>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -613,6 +1339,15 @@ pub struct IssueAttributes {
     pub finding_key: String,
     #[serde(rename = "sub-tool", default)]
     pub sub_tool: Option<String>,
+    #[serde(rename = "first-detected-on", default)]
+    pub first_detected_on: Option<String>,
+}
+
+impl IssueAttributes {
+    /// When the issue was first detected, if the timestamp parses.
+    pub fn first_detected(&self) -> Option<chrono::DateTime<chrono::Utc>> {
+        self.first_detected_on.as_deref().and_then(crate::common::parse_timestamp)
+    }
 }
 
 #[derive(Debug, Deserialize, Serialize)]
@@ -620,7 +1355,7 @@ pub struct TriageCurrentResponse {
     pub data: Vec<TriageCurrent>,
 }
 
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct TriageCurrent {
     #[serde(rename = "type")]
     pub resource_type: String,
@@ -628,7 +1363,7 @@ pub struct TriageCurrent {
     pub attributes: TriageCurrentAttributes,
 }
 
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct TriageCurrentAttributes {
     #[serde(rename = "issue-key")]
     pub issue_key: String,
@@ -640,6 +1375,209 @@ pub struct TriageCurrentAttributes {
     pub triage_current_values: Vec<serde_json::Value>,
 }
 
+impl TriageCurrentAttributes {
+    /// Whether `attribute` (`DISMISS`, `OWNER`, `COMMENTARY`) currently has `value`.
+    ///
+    /// Current values are matched loosely: an entry counts when its string fields
+    /// include both the attribute name and the value. Dismiss values are also
+    /// compared against `dismissal-status`, ignoring case and `_`/`-`.
+    pub fn has_value(&self, attribute: &str, value: &str) -> bool {
+        let norm = |s: &str| s.to_ascii_lowercase().replace('_', "-");
+        if attribute == "DISMISS" && self.dismissal_status.as_deref().is_some_and(|s| norm(s) == norm(value)) {
+            return true;
+        }
+        self.triage_current_values.iter().any(|entry| {
+            let Some(fields) = entry.as_object() else {
+                return false;
+            };
+            let strings: Vec<&str> = fields.values().filter_map(|v| v.as_str()).collect();
+            strings.iter().any(|s| s.eq_ignore_ascii_case(attribute))
+                && strings.iter().any(|s| *s == value || (attribute == "DISMISS" && norm(s) == norm(value)))
+        })
+    }
+}
+
+/// Response of the triage-command `triage-issues` endpoint
+/// (`TriageIssueV1JsonApiResource` in `specs/triage-command-v1-oas3.yaml`).
+#[derive(Debug, Deserialize, Serialize)]
+pub struct TriageUpdateResponse {
+    #[serde(default)]
+    pub data: Option<TriageUpdate>,
+    #[serde(default)]
+    pub meta: Option<TriageUpdateMeta>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct TriageUpdate {
+    #[serde(default)]
+    pub id: Option<String>,
+    #[serde(default)]
+    pub attributes: Option<TriageUpdateAttributes>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct TriageUpdateAttributes {
+    /// The issues that were triaged. The spec only says these are objects, so both
+    /// `{"issue-key": ...}` objects and bare key strings are accepted.
+    #[serde(rename = "issue-keys", default)]
+    pub issue_keys: Vec<serde_json::Value>,
+    /// `REQUESTED` when a non-admin's dismissal awaits approval, `SYSTEM_APPROVED`
+    /// otherwise.
+    #[serde(rename = "dismissal-status", default)]
+    pub dismissal_status: Option<String>,
+}
+
+/// How many issues of a triage request succeeded.
+#[derive(Debug, Deserialize, Serialize)]
+pub struct TriageUpdateMeta {
+    #[serde(default)]
+    pub succeeded: Option<u64>,
+    #[serde(default)]
+    pub failed: Option<u64>,
+    #[serde(default)]
+    pub total: Option<u64>,
+}
+
+/// Outcome of a triage update for a single issue key.
+#[derive(Debug, Clone, Serialize)]
+pub struct TriageOutcome {
+    pub issue_key: String,
+    pub status: TriageOutcomeStatus,
+    pub reason: Option<String>,
+}
+
+/// Whether a triage update reached an issue.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TriageOutcomeStatus {
+    Applied,
+    Failed,
+    /// The response doesn't say; [`PolarisClient::verify_triage`] can find out.
+    Unknown,
+}
+
+impl TriageOutcomeStatus {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Applied => "applied",
+            Self::Failed => "failed",
+            Self::Unknown => "unknown",
+        }
+    }
+}
+
+impl TriageUpdateResponse {
+    /// Issue keys the server reports as triaged.
+    pub fn triaged_keys(&self) -> Vec<&str> {
+        let Some(attrs) = self.data.as_ref().and_then(|d| d.attributes.as_ref()) else {
+            return Vec::new();
+        };
+        attrs
+            .issue_keys
+            .iter()
+            .filter_map(|k| k.as_str().or_else(|| k.get("issue-key")?.as_str()))
+            .collect()
+    }
+
+    /// Per-issue outcomes for the keys sent in the request.
+    ///
+    /// When the server lists the keys it triaged, the others failed. Otherwise only
+    /// the `meta` counts are left: they settle the outcome when every issue succeeded
+    /// or every issue failed, and anything short of that is reported as unknown.
+    pub fn outcomes(&self, requested: &[&str]) -> Vec<TriageOutcome> {
+        let triaged = self.triaged_keys();
+        let outcome = |key: &str, status, reason: Option<String>| TriageOutcome {
+            issue_key: key.to_string(),
+            status,
+            reason,
+        };
+        if !triaged.is_empty() {
+            return requested
+                .iter()
+                .map(|key| {
+                    if triaged.contains(key) {
+                        outcome(key, TriageOutcomeStatus::Applied, None)
+                    } else {
+                        outcome(key, TriageOutcomeStatus::Failed, Some("not among the issues the server triaged".into()))
+                    }
+                })
+                .collect();
+        }
+
+        let total = requested.len() as u64;
+        let (status, reason) = match self.meta.as_ref().map(|m| (m.succeeded, m.failed.unwrap_or(0))) {
+            Some((Some(succeeded), 0)) if succeeded == total => (TriageOutcomeStatus::Applied, None),
+            Some((Some(0), failed)) if failed == total => {
+                (TriageOutcomeStatus::Failed, Some("the server reported every issue as failed".to_string()))
+            }
+            Some((succeeded, failed)) => (
+                TriageOutcomeStatus::Unknown,
+                Some(format!(
+                    "the server reported {} succeeded and {failed} failed without naming issues",
+                    succeeded.map_or("?".to_string(), |n| n.to_string())
+                )),
+            ),
+            None => (
+                TriageOutcomeStatus::Unknown,
+                Some("the server response does not say which issues were triaged".to_string()),
+            ),
+        };
+        requested.iter().map(|key| outcome(key, status, reason.clone())).collect()
+    }
+}
+
+/// Server-side filters for issue queries.
+#[derive(Debug, Clone, Default)]
+pub struct IssueFilter {
+    /// Exact finding key.
+    pub finding_key: Option<String>,
+    /// Triage statuses to match (`not-triaged`, `to-be-fixed`, `dismissed-other`, ...).
+    pub triage_statuses: Vec<String>,
+    /// Only issues first detected at or after this time.
+    pub first_detected_since: Option<chrono::DateTime<chrono::Utc>>,
+    /// Only issues first detected at or before this time.
+    pub first_detected_until: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+/// Triage statuses of issues that are still open.
+pub const OPEN_TRIAGE_STATUSES: &[&str] = &["not-triaged", "dismiss-requested", "to-be-fixed"];
+/// Triage statuses of dismissed issues.
+pub const DISMISSED_TRIAGE_STATUSES: &[&str] = &[
+    "dismissed-false-positive",
+    "dismissed-intentional",
+    "dismissed-other",
+];
+
+impl IssueFilter {
+    fn push_query(&self, path: &mut String) {
+        if let Some(ref fk) = self.finding_key {
+            path.push_str(&format!(
+                "&filter[issue][finding-key][$eq]={}",
+                urlencoding::encode(fk)
+            ));
+        }
+        if !self.triage_statuses.is_empty() {
+            let statuses: Vec<_> = self
+                .triage_statuses
+                .iter()
+                .map(|s| urlencoding::encode(s))
+                .collect();
+            path.push_str(&format!(
+                "&filter[issue][triage-status][$in]={}",
+                statuses.join(",")
+            ));
+        }
+        for (op, bound) in [("$gte", self.first_detected_since), ("$lte", self.first_detected_until)] {
+            if let Some(t) = bound {
+                path.push_str(&format!(
+                    "&filter[issue][first-detected-on][{op}]={}",
+                    urlencoding::encode(&t.to_rfc3339_opts(chrono::SecondsFormat::Secs, true))
+                ));
+            }
+        }
+    }
+}
+
 /// Values for updating triage on issues.
 #[derive(Debug, Clone, Default, Serialize)]
 pub struct TriageValues {
@@ -651,23 +1589,889 @@ pub struct TriageValues {
     pub commentary: Option<String>,
 }
 
-async fn check_response<T: serde::de::DeserializeOwned>(
-    resp: reqwest::Response,
-) -> Result<T> {
-    let status = resp.status();
-    if !status.is_success() {
-        let code = status.as_u16();
-        let body = resp.text().await.unwrap_or_default();
-        if code == 404 {
-            return Err(PolarisError::NotFound(body));
-        }
-        return Err(PolarisError::Api {
-            status: code,
-            detail: body,
+impl TriageValues {
+    /// The set values as `(attribute, value)`, using the API's attribute names.
+    fn pairs(&self) -> Vec<(&'static str, &str)> {
+        [
+            ("DISMISS", &self.dismiss),
+            ("OWNER", &self.owner),
+            ("COMMENTARY", &self.commentary),
+        ]
+        .into_iter()
+        .filter_map(|(name, value)| Some((name, value.as_deref()?)))
+        .collect()
+    }
+}
+
+// ── Reachability ──
+
+/// API roots probed by [`probe_apis`], as `(label, path)`.
+const PROBE_ENDPOINTS: &[(&str, &str)] = &[
+    ("auth v2", "api/auth/v2/authenticate"),
+    ("common v0", "api/common/v0/projects"),
+    ("query v1", "api/query/v1/discovery/group-bys"),
+    ("triage-query v1", "api/triage-query/v1/triage-stores"),
+    ("triage-command v1", "api/triage-command/v1/triage-issues"),
+    ("code-analysis v0", "api/code-analysis/v0/events-with-source"),
+];
+
+/// Outcome of probing one API root.
+#[derive(Debug, Clone, Serialize)]
+pub struct ApiProbe {
+    pub api: &'static str,
+    pub url: String,
+    /// HTTP status, or `None` if no response arrived (DNS, TLS, timeout, ...).
+    pub status: Option<u16>,
+    pub latency_ms: u64,
+    pub error: Option<String>,
+}
+
+impl ApiProbe {
+    /// The API answered. Auth and method errors (401/403/405) count: they prove the
+    /// service is there. 404 and 5xx suggest a wrong base URL or an unhealthy service.
+    pub fn responded(&self) -> bool {
+        matches!(self.status, Some(s) if s != 404 && s < 500)
+    }
+}
+
+/// Probe the well-known API roots with unauthenticated GETs, so reachability can be
+/// checked even when the token is missing or invalid.
+pub async fn probe_apis(base_url: &str, tls: &TlsConfig) -> Result<Vec<ApiProbe>> {
+    let http = crate::http::client_builder(tls)
+        .timeout(std::time::Duration::from_secs(10))
+        .build()
+        .map_err(PolarisError::Http)?;
+
+    let mut probes = Vec::new();
+    for (api, path) in PROBE_ENDPOINTS {
+        let url = crate::http::api_url(base_url, path)?;
+        let start = std::time::Instant::now();
+        let result = http.get(url.clone()).send().await;
+        let latency_ms = start.elapsed().as_millis() as u64;
+        let (status, error) = match result {
+            Ok(resp) => (Some(resp.status().as_u16()), None),
+            Err(e) => (None, Some(error_chain(&e))),
+        };
+        probes.push(ApiProbe {
+            api,
+            url: url.to_string(),
+            status,
+            latency_ms,
+            error,
         });
     }
+    Ok(probes)
+}
+
+/// Render an error with its sources, since reqwest's top-level message omits the cause.
+fn error_chain(err: &dyn std::error::Error) -> String {
+    let mut msg = err.to_string();
+    let mut source = err.source();
+    while let Some(cause) = source {
+        msg.push_str(&format!(": {cause}"));
+        source = cause.source();
+    }
+    msg
+}
+
+/// Where the next page starts: an offset, or a server-issued cursor.
+enum PageAt {
+    Offset(u32),
+    After(String),
+}
+
+impl PageAt {
+    /// The offset for endpoints without cursor support; they never hand out cursors,
+    /// so [`paginate_all`] only asks them for offsets.
+    fn offset(&self) -> u32 {
+        match *self {
+            PageAt::Offset(offset) => offset,
+            PageAt::After(_) => 0,
+        }
+    }
+}
+
+type PageFuture<'a, T> = std::pin::Pin<Box<dyn std::future::Future<Output = Result<JsonApiResponse<T>>> + Send + 'a>>;
+
+/// Page-at-a-time access to a listing, for callers that decide themselves when (and
+/// whether) to fetch more, e.g. rendering each page before asking for the next.
+///
+/// The `list_all_*` methods fetch everything up front with the same stopping rules;
+/// a paginator only requests a page when [`next_page`](Self::next_page) is called.
+/// Each page is returned as the server sent it (`included` is not deduplicated across
+/// pages).
+///
+/// ```no_run
+/// # async fn demo(client: &polaris_api::client::PolarisClient) -> polaris_api::error::Result<()> {
+/// let mut pages = client.projects_paginator(None, None, 50);
+/// while let Some(page) = pages.next_page().await? {
+///     for project in &page.data {
+///         println!("{}", project.attributes.name);
+///     }
+/// }
+/// # Ok(())
+/// # }
+/// ```
+pub struct Paginator<'a, T> {
+    fetch: Box<dyn FnMut(u32) -> PageFuture<'a, T> + Send + 'a>,
+    page_size: u32,
+    offset: u32,
+    total: Option<u64>,
+    done: bool,
+}
+
+impl<'a, T> Paginator<'a, T> {
+    fn new(page_size: u32, fetch: impl FnMut(u32) -> PageFuture<'a, T> + Send + 'a) -> Self {
+        Self {
+            fetch: Box::new(fetch),
+            page_size,
+            offset: 0,
+            total: None,
+            done: false,
+        }
+    }
+
+    /// Fetch the next page, or `None` once the listing is exhausted: after a short or
+    /// empty page, or when the server's total has been reached. A failed fetch can be
+    /// retried by calling this again; the offset only advances on success.
+    pub async fn next_page(&mut self) -> Result<Option<JsonApiResponse<T>>> {
+        if self.done {
+            return Ok(None);
+        }
+        let page = (self.fetch)(self.offset).await?;
+        self.total = page.meta.as_ref().and_then(|m| m.total).or(self.total);
+        let count = page.data.len() as u32;
+        self.offset += count;
+        self.done = count < self.page_size || self.total.is_some_and(|t| self.offset as u64 >= t);
+        if count == 0 {
+            return Ok(None);
+        }
+        Ok(Some(page))
+    }
+
+    /// Offset of the next page, i.e. the number of items fetched so far.
+    pub fn offset(&self) -> u32 {
+        self.offset
+    }
+
+    /// The server's total, as reported by the last page fetched.
+    pub fn total(&self) -> Option<u64> {
+        self.total
+    }
+
+    /// Whether [`next_page`](Self::next_page) would make another request.
+    pub fn has_more(&self) -> bool {
+        !self.done
+    }
+}
+
+impl<T> std::fmt::Debug for Paginator<'_, T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Paginator")
+            .field("page_size", &self.page_size)
+            .field("offset", &self.offset)
+            .field("total", &self.total)
+            .field("done", &self.done)
+            .finish()
+    }
+}
+
+/// One page of a paginated listing, as fetched for [`paginate_all`], or all of them
+/// merged.
+struct Page<T> {
+    data: Vec<T>,
+    included: Vec<serde_json::Value>,
+    total: Option<u64>,
+    next_cursor: Option<String>,
+}
+
+impl<T> From<JsonApiResponse<T>> for Page<T> {
+    fn from(resp: JsonApiResponse<T>) -> Self {
+        Self {
+            total: resp.meta.and_then(|m| m.total),
+            data: resp.data,
+            included: resp.included,
+            next_cursor: None,
+        }
+    }
+}
+
+/// Fetch pages until a short page, the server's total, or `max_items` (the result is
+/// truncated to it). Pages continue from the previous page's cursor when it has one,
+/// else from the next offset; `start` resumes from an earlier cursor. Once a page has
+/// been fetched by cursor, a full page without a next cursor ends the scan: offsets
+/// counted from here wouldn't line up with where the cursors left off.
+///
+/// The merged page has the deduplicated `included` resources, the last reported total
+/// and the cursor to resume from, which is only kept when the scan stopped at a page
+/// boundary (a cursor past items dropped by the truncation would skip them).
+async fn paginate_all<T, F, Fut>(
+    page_size: u32,
+    max_items: Option<usize>,
+    start: Option<String>,
+    mut fetch: F,
+) -> Result<Page<T>>
+where
+    F: FnMut(PageAt) -> Fut,
+    Fut: std::future::Future<Output = Result<Page<T>>>,
+{
+    let mut all_data = Vec::new();
+    let mut all_included = Vec::new();
+    let mut seen_included = HashSet::new();
+    let mut offset = 0u32;
+    let mut total = None;
+    let mut cursor = start;
+
+    loop {
+        let at = cursor.take().map_or(PageAt::Offset(offset), PageAt::After);
+        let by_cursor = matches!(at, PageAt::After(_));
+        let page = fetch(at).await?;
+        total = page.total.or(total);
+        cursor = page.next_cursor;
+        let count = page.data.len();
+        all_data.extend(page.data);
+        merge_included(&mut all_included, &mut seen_included, page.included);
+        if let Some(max) = max_items && all_data.len() >= max {
+            if all_data.len() > max {
+                all_data.truncate(max);
+                cursor = None;
+            }
+            break;
+        }
+        if count < page_size as usize || (by_cursor && cursor.is_none()) {
+            cursor = None;
+            break;
+        }
+        offset += page_size;
+        if let Some(t) = total && offset as u64 >= t {
+            cursor = None;
+            break;
+        }
+    }
+    Ok(Page {
+        data: all_data,
+        included: all_included,
+        total,
+        next_cursor: cursor,
+    })
+}
+
+/// Append a page's `included` resources, skipping any `(type, id)` already merged
+/// from an earlier page. First-seen order is preserved.
+fn merge_included(
+    all: &mut Vec<serde_json::Value>,
+    seen: &mut HashSet<(String, String)>,
+    page: Vec<serde_json::Value>,
+) {
+    for resource in page {
+        let key = resource
+            .get("type")
+            .and_then(|v| v.as_str())
+            .zip(resource.get("id").and_then(|v| v.as_str()))
+            .map(|(t, id)| (t.to_string(), id.to_string()));
+        if key.is_none_or(|key| seen.insert(key)) {
+            all.push(resource);
+        }
+    }
+}
+
+fn push_includes<'a>(path: &mut String, includes: impl IntoIterator<Item = &'a str>) {
+    for include in includes {
+        path.push_str(&format!("&include[issue][]={}", urlencoding::encode(include)));
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used, clippy::expect_used)]
+mod tests {
+    use super::*;
+    use crate::test_server::Reply;
+
+    /// A listing of `len` numbered items served `page_size` at a time, handing out the
+    /// next start index as a cursor while `cursors` is set. Records where each page began.
+    struct FakeListing {
+        len: usize,
+        cursors: bool,
+        calls: std::sync::Mutex<Vec<String>>,
+    }
+
+    impl FakeListing {
+        fn new(len: usize, cursors: bool) -> Self {
+            Self { len, cursors, calls: Default::default() }
+        }
+
+        async fn page(&self, at: PageAt, page_size: u32) -> Result<Page<usize>> {
+            let start = match &at {
+                PageAt::Offset(offset) => *offset as usize,
+                PageAt::After(cursor) => cursor.parse().unwrap(),
+            };
+            self.calls.lock().unwrap().push(match at {
+                PageAt::Offset(offset) => format!("offset {offset}"),
+                PageAt::After(cursor) => format!("after {cursor}"),
+            });
+            let end = (start + page_size as usize).min(self.len);
+            Ok(Page {
+                data: (start..end).collect(),
+                included: Vec::new(),
+                total: Some(self.len as u64),
+                next_cursor: (self.cursors && end < self.len).then(|| end.to_string()),
+            })
+        }
+
+        fn calls(&self) -> Vec<String> {
+            self.calls.lock().unwrap().clone()
+        }
+    }
+
+    #[tokio::test]
+    async fn paginate_all_reads_every_offset_page() {
+        let listing = FakeListing::new(10, false);
+        let page = paginate_all(4, None, None, |at| listing.page(at, 4)).await.unwrap();
+        assert_eq!(page.data, (0..10).collect::<Vec<_>>());
+        assert_eq!(listing.calls(), ["offset 0", "offset 4", "offset 8"]);
+        assert_eq!(page.next_cursor, None);
+    }
+
+    #[tokio::test]
+    async fn paginate_all_follows_cursors() {
+        let listing = FakeListing::new(10, true);
+        let page = paginate_all(4, None, None, |at| listing.page(at, 4)).await.unwrap();
+        assert_eq!(page.data, (0..10).collect::<Vec<_>>());
+        assert_eq!(listing.calls(), ["offset 0", "after 4", "after 8"]);
+    }
+
+    #[tokio::test]
+    async fn paginate_all_drops_cursor_when_truncating_mid_page() {
+        let listing = FakeListing::new(10, true);
+        let page = paginate_all(4, Some(6), None, |at| listing.page(at, 4)).await.unwrap();
+        assert_eq!(page.data, (0..6).collect::<Vec<_>>());
+        // Resuming from the page's cursor (8) would skip items 6 and 7.
+        assert_eq!(page.next_cursor, None);
+    }
+
+    #[tokio::test]
+    async fn paginate_all_keeps_cursor_at_page_boundary() {
+        let listing = FakeListing::new(10, true);
+        let page = paginate_all(4, Some(8), None, |at| listing.page(at, 4)).await.unwrap();
+        assert_eq!(page.data, (0..8).collect::<Vec<_>>());
+        assert_eq!(page.next_cursor.as_deref(), Some("8"));
+    }
+
+    #[tokio::test]
+    async fn paginate_all_stops_when_resumed_scan_runs_out_of_cursors() {
+        // The server honours the start cursor but hands out no further ones.
+        let listing = FakeListing::new(20, false);
+        let page = paginate_all(4, None, Some("4".into()), |at| listing.page(at, 4)).await.unwrap();
+        assert_eq!(page.data, (4..8).collect::<Vec<_>>());
+        // No fallback to `offset 4`, which would count from the start of the list.
+        assert_eq!(listing.calls(), ["after 4"]);
+        assert_eq!(page.next_cursor, None);
+    }
+
+    fn triage_json(keys: &[&str]) -> String {
+        let data: Vec<serde_json::Value> = keys
+            .iter()
+            .map(|key| {
+                serde_json::json!({
+                    "type": "triage-current",
+                    "id": format!("t-{key}"),
+                    "attributes": { "issue-key": key, "project-id": "p1", "dismissal-status": "NOT_DISMISSED" },
+                })
+            })
+            .collect();
+        serde_json::json!({ "data": data }).to_string()
+    }
+
+    /// The value of the `[$eq]` issue-key filter in a triage query.
+    fn eq_key(target: &str) -> Option<&str> {
+        let rest = &target[target.find("[issue-key][$eq]=")? + "[issue-key][$eq]=".len()..];
+        Some(rest.split('&').next().unwrap_or(rest))
+    }
+
+    #[tokio::test]
+    async fn triage_batch_maps_results_to_keys() {
+        let server = crate::test_server::TestServer::start(|_| Reply::json(200, &triage_json(&["b", "a"]))).await;
+        let client = crate::test_server::test_client(&server);
+        let results = client.get_triage_batch("p1", &["a", "b", "c"]).await;
+        let keys: Vec<Option<String>> = results
+            .into_iter()
+            .map(|r| r.unwrap().map(|tc| tc.attributes.issue_key))
+            .collect();
+        assert_eq!(keys, [Some("a".into()), Some("b".into()), None]);
+        assert_eq!(server.requests().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn triage_batch_falls_back_per_key_and_keeps_partial_results() {
+        let server = crate::test_server::TestServer::start(|request| match eq_key(&request.target) {
+            None => Reply::json(500, r#"{"errors":[{"detail":"batch failed"}]}"#),
+            Some("bad") => Reply::json(503, r#"{"errors":[{"detail":"unavailable"}]}"#),
+            Some("none") => Reply::json(200, r#"{"data":[]}"#),
+            Some(key) => Reply::json(200, &triage_json(&[key])),
+        })
+        .await;
+        let client = crate::test_server::test_client(&server);
+        let results = client.get_triage_batch("p1", &["a", "bad", "none", "d"]).await;
+        assert_eq!(results.len(), 4);
+        assert_eq!(results[0].as_ref().unwrap().as_ref().unwrap().attributes.issue_key, "a");
+        assert!(matches!(results[1], Err(PolarisError::Api { status: 503, .. })));
+        assert!(results[2].as_ref().unwrap().is_none());
+        assert_eq!(results[3].as_ref().unwrap().as_ref().unwrap().attributes.issue_key, "d");
+    }
+
+    #[tokio::test]
+    async fn triage_batch_stops_batching_when_in_filter_is_rejected() {
+        let server = crate::test_server::TestServer::start(|request| match eq_key(&request.target) {
+            None => Reply::json(400, r#"{"errors":[{"detail":"unsupported operator $in"}]}"#),
+            Some(key) => Reply::json(200, &triage_json(&[key])),
+        })
+        .await;
+        let client = crate::test_server::test_client(&server);
+        let keys: Vec<String> = (0..TRIAGE_BATCH_SIZE + 5).map(|i| format!("k{i}")).collect();
+        let keys: Vec<&str> = keys.iter().map(String::as_str).collect();
+        let results = client.get_triage_batch("p1", &keys).await;
+        assert!(results.iter().all(|r| matches!(r, Ok(Some(_)))));
+        let batches = server.requests().iter().filter(|r| r.contains("$in")).count();
+        assert_eq!(batches, 1, "the second chunk shouldn't try `$in` again");
+    }
+
+    /// A `triage-issues` response shaped like `TriageIssueV1JsonApiResource` in the
+    /// triage-command spec: two of three issues triaged, one refused.
+    const TRIAGE_UPDATE_MIXED: &str = r#"{
+        "data": {
+            "id": "t-1",
+            "type": "triage-issues",
+            "attributes": {
+                "issue-keys": [{"issue-key": "k1"}, {"issue-key": "k3"}],
+                "project-id": "p1",
+                "dismissal-status": "SYSTEM_APPROVED",
+                "triage-values": {"DISMISS": "DISMISSED_AS_FP"}
+            }
+        },
+        "meta": {"succeeded": 2, "failed": 1, "total": 3}
+    }"#;
+
+    fn statuses(outcomes: &[TriageOutcome]) -> Vec<(&str, TriageOutcomeStatus)> {
+        outcomes.iter().map(|o| (o.issue_key.as_str(), o.status)).collect()
+    }
+
+    #[tokio::test]
+    async fn triage_update_reports_mixed_results_per_key() {
+        let server = crate::test_server::TestServer::start(|_| Reply::json(201, TRIAGE_UPDATE_MIXED)).await;
+        let client = crate::test_server::test_client(&server);
+        let values = TriageValues {
+            dismiss: Some("DISMISSED_AS_FP".into()),
+            ..Default::default()
+        };
+        let resp = client.update_triage("p1", &["k1", "k2", "k3"], &values).await.unwrap();
+        let outcomes = resp.outcomes(&["k1", "k2", "k3"]);
+        assert_eq!(
+            statuses(&outcomes),
+            [
+                ("k1", TriageOutcomeStatus::Applied),
+                ("k2", TriageOutcomeStatus::Failed),
+                ("k3", TriageOutcomeStatus::Applied),
+            ]
+        );
+        assert!(outcomes[1].reason.is_some());
+        assert_eq!(server.requests(), ["POST /api/triage-command/v1/triage-issues"]);
+    }
+
+    #[test]
+    fn triage_outcomes_without_key_echo_use_meta_counts() {
+        let parse = |body: &str| serde_json::from_str::<TriageUpdateResponse>(body).unwrap();
+        let all_ok = parse(r#"{"meta": {"succeeded": 2, "failed": 0, "total": 2}}"#);
+        let all_failed = parse(r#"{"meta": {"succeeded": 0, "failed": 2, "total": 2}}"#);
+        let mixed = parse(r#"{"meta": {"succeeded": 1, "failed": 1, "total": 2}}"#);
+        let status = |resp: &TriageUpdateResponse| resp.outcomes(&["a", "b"]).iter().map(|o| o.status).collect::<Vec<_>>();
+        assert_eq!(status(&all_ok), [TriageOutcomeStatus::Applied; 2]);
+        assert_eq!(status(&all_failed), [TriageOutcomeStatus::Failed; 2]);
+        assert_eq!(status(&mixed), [TriageOutcomeStatus::Unknown; 2]);
+    }
+
+    #[test]
+    fn triage_outcomes_are_unknown_for_an_unreadable_response() {
+        for body in ["{}", r#"{"data": {"id": "t-1"}}"#, r#"{"data": {"attributes": {"issue-keys": []}}}"#] {
+            let resp: TriageUpdateResponse = serde_json::from_str(body).unwrap();
+            let outcomes = resp.outcomes(&["a"]);
+            assert_eq!(statuses(&outcomes), [("a", TriageOutcomeStatus::Unknown)], "{body}");
+            assert!(!outcomes[0].reason.as_deref().unwrap().contains("REQUESTED"));
+        }
+    }
+
+    fn gzip(body: &[u8]) -> Vec<u8> {
+        use std::io::Write;
+        let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+        encoder.write_all(body).unwrap();
+        encoder.finish().unwrap()
+    }
+
+    /// A `query/v1/issues` page of `n` issues with their severity, type and tool
+    /// included, as the server sends it.
+    fn issue_listing(n: usize) -> String {
+        let data: Vec<serde_json::Value> = (0..n)
+            .map(|i| {
+                serde_json::json!({
+                    "type": "issue",
+                    "id": format!("1c0f9f6e-3b7a-4d2e-9a55-{i:012}"),
+                    "attributes": {
+                        "issue-key": format!("4f1a2b3c4d5e6f708192a3b4c5d6e7f8-{i}"),
+                        "finding-key": format!("9e8d7c6b5a4f3e2d1c0b9a8f7e6d5c4b-{i}"),
+                        "sub-tool": if i % 2 == 0 { "NULL_RETURNS" } else { "RESOURCE_LEAK" },
+                        "first-detected-on": "2025-01-01T00:00:00.000Z",
+                    },
+                    "relationships": {
+                        "severity": { "data": { "type": "taxon", "id": "high" } },
+                        "issue-type": { "data": { "type": "issue-type", "id": "null-returns" } },
+                        "tool-domain-service": { "data": { "type": "tool-domain-service", "id": "sast" } },
+                    },
+                })
+            })
+            .collect();
+        serde_json::json!({
+            "data": data,
+            "included": [
+                { "type": "taxon", "id": "high", "attributes": { "name": "High" } },
+                { "type": "issue-type", "id": "null-returns", "attributes": { "name": "Null pointer dereference" } },
+                { "type": "tool-domain-service", "id": "sast", "attributes": { "name": "Coverity" } },
+            ],
+            "meta": { "total": n, "offset": 0, "limit": n },
+        })
+        .to_string()
+    }
+
+    /// Serve `body` gzip-compressed, but only to clients that asked for gzip.
+    async fn gzip_server(content_type: &'static str, body: Vec<u8>) -> crate::test_server::TestServer {
+        crate::test_server::TestServer::start(move |request| {
+            if !request.header("accept-encoding").is_some_and(|v| v.contains("gzip")) {
+                return Reply::new(406, "text/plain", "gzip required");
+            }
+            Reply::new(200, content_type, gzip(&body)).header("Content-Encoding", "gzip")
+        })
+        .await
+    }
+
+    #[tokio::test]
+    async fn issue_listing_is_decoded_from_gzip() {
+        let body = issue_listing(300);
+        let compressed = gzip(body.as_bytes());
+        // The repeated keys and relationships are what make listings compress well.
+        assert!(compressed.len() * 10 < body.len(), "{} -> {} bytes", body.len(), compressed.len());
+
+        let server = gzip_server("application/vnd.api+json", body.into_bytes()).await;
+        let client = crate::test_server::test_client(&server);
+        let resp = client.list_issues("p1", Some("b1"), None, 300, 0).await.unwrap();
+        assert_eq!(resp.data.len(), 300);
+        assert_eq!(resp.data[299].attributes.sub_tool.as_deref(), Some("RESOURCE_LEAK"));
+    }
+
+    #[tokio::test]
+    async fn source_code_is_decoded_from_gzip() {
+        let source = "int f(int *p) {\n    return *p;\n}\n".repeat(50);
+        let server = gzip_server("text/plain;charset=utf-8", source.clone().into_bytes()).await;
+        let client = crate::test_server::test_client(&server);
+        assert_eq!(client.get_source_code("r1", "src/a.c").await.unwrap(), source);
+
+        let mut out = Vec::new();
+        let written = client.get_source_code_to_writer("r1", "src/a.c", &mut out).await.unwrap();
+        assert_eq!((written as usize, out), (source.len(), source.into_bytes()));
+    }
+
+    #[tokio::test]
+    async fn source_code_streams_a_chunked_response() {
+        // "é" is split across chunks, and the last line has no newline.
+        let chunks: Vec<Vec<u8>> = vec![
+            b"int caf\xc3".to_vec(),
+            b"\xa9(void);\n".to_vec(),
+            b"int f(void);\n".repeat(400),
+            b"// end".to_vec(),
+        ];
+        let expected = chunks.concat();
+        let server = crate::test_server::TestServer::start(move |_| {
+            Reply::chunked(200, "text/plain;charset=utf-8", chunks.clone())
+        })
+        .await;
+        let client = crate::test_server::test_client(&server);
+
+        let mut out = Vec::new();
+        let written = client.get_source_code_to_writer("r1", "src/a.c", &mut out).await.unwrap();
+        assert_eq!(written as usize, expected.len());
+        assert_eq!(out, expected);
+    }
+
+    #[tokio::test]
+    async fn read_only_client_refuses_mutations_before_any_request() {
+        let server = crate::test_server::TestServer::start(|_| Reply::json(201, "{}")).await;
+        let client = PolarisClient::new(crate::test_server::test_config(&server).with_read_only(true));
+        let values = TriageValues {
+            commentary: Some("looked at it".into()),
+            ..Default::default()
+        };
+
+        let err = client.update_triage("p1", &["k1"], &values).await.unwrap_err();
+        assert!(matches!(&err, PolarisError::ReadOnly(what) if what == "triage update"), "{err:?}");
+        let err = client.post_raw("api/triage-command/v1/triage-issues", &serde_json::json!({})).await.unwrap_err();
+        assert!(matches!(&err, PolarisError::ReadOnly(what) if what.starts_with("POST ")), "{err:?}");
+
+        assert_eq!(server.logins(), 0, "not even a login should be sent");
+        assert!(server.requests().is_empty(), "{:?}", server.requests());
+    }
 
-    resp.json::<T>()
+    #[tokio::test]
+    async fn concurrent_get_jwt_logs_in_once() {
+        let server = crate::test_server::TestServer::start(|_| Reply::json(200, "{}")).await;
+        let client = crate::test_server::test_client(&server);
+        let jwts = tokio::join!(
+            client.get_jwt(),
+            client.get_jwt(),
+            client.get_jwt(),
+            client.get_jwt(),
+            client.get_jwt(),
+        );
+        for jwt in [jwts.0, jwts.1, jwts.2, jwts.3, jwts.4] {
+            assert_eq!(jwt.unwrap(), crate::test_server::TEST_JWT);
+        }
+        assert_eq!(server.logins(), 1);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn cloned_clients_share_the_jwt() {
+        let server = crate::test_server::TestServer::start(|_| Reply::json(200, r#"{"data":[]}"#)).await;
+        let client = crate::test_server::test_client(&server);
+        let tasks: Vec<_> = (0..2)
+            .map(|_| {
+                let client = client.clone();
+                tokio::spawn(async move { client.list_projects(None, None, 10, 0).await })
+            })
+            .collect();
+        for task in tasks {
+            task.await.unwrap().unwrap();
+        }
+        assert_eq!(server.logins(), 1);
+        assert_eq!(server.requests().len(), 2);
+        // The original sees the JWT its clones fetched.
+        assert_eq!(client.cached_jwt().await.as_deref(), Some(crate::test_server::TEST_JWT));
+    }
+
+    /// The `page[offset]` of a listing request.
+    fn page_offset(target: &str) -> usize {
+        target
+            .replace("%5B", "[")
+            .replace("%5D", "]")
+            .split(['?', '&'])
+            .find_map(|pair| pair.strip_prefix("page[offset]=")?.parse().ok())
+            .unwrap_or(0)
+    }
+
+    #[tokio::test]
+    async fn list_all_branches_keeps_included_from_every_page() {
+        // Five branches, two per page; each page includes the project and its own user.
+        let server = crate::test_server::TestServer::start(|request| {
+            let offset = page_offset(&request.target);
+            let data: Vec<serde_json::Value> = (offset..(offset + 2).min(5))
+                .map(|i| {
+                    serde_json::json!({ "type": "branch", "id": format!("b{i}"), "attributes": { "name": format!("branch-{i}") } })
+                })
+                .collect();
+            let included = [
+                serde_json::json!({ "type": "project", "id": "p1" }),
+                serde_json::json!({ "type": "user", "id": format!("u{}", offset / 2) }),
+            ];
+            let meta = serde_json::json!({ "total": 5, "offset": offset, "limit": 2 });
+            Reply::json(200, &serde_json::json!({ "data": data, "included": included, "meta": meta }).to_string())
+        })
+        .await;
+        let client = crate::test_server::test_client(&server);
+        let resp = client.list_all_branches("p1", 2).await.unwrap();
+
+        let names: Vec<&str> = resp.data.iter().map(|b| b.attributes.name.as_str()).collect();
+        assert_eq!(names, ["branch-0", "branch-1", "branch-2", "branch-3", "branch-4"]);
+        let included: Vec<String> = resp
+            .included
+            .iter()
+            .map(|r| format!("{}/{}", r["type"].as_str().unwrap(), r["id"].as_str().unwrap()))
+            .collect();
+        assert_eq!(included, ["project/p1", "user/u0", "user/u1", "user/u2"]);
+        assert_eq!(server.requests().len(), 3);
+    }
+
+    /// Pages of `len` branches, two per page, with the total in `meta` only if `total`.
+    async fn branch_server(len: usize, total: bool) -> crate::test_server::TestServer {
+        crate::test_server::TestServer::start(move |request| {
+            let offset = page_offset(&request.target);
+            let data: Vec<serde_json::Value> = (offset..(offset + 2).min(len))
+                .map(|i| {
+                    serde_json::json!({ "type": "branch", "id": format!("b{i}"), "attributes": { "name": format!("branch-{i}") } })
+                })
+                .collect();
+            let mut body = serde_json::json!({ "data": data });
+            if total {
+                body["meta"] = serde_json::json!({ "total": len, "offset": offset, "limit": 2 });
+            }
+            Reply::json(200, &body.to_string())
+        })
         .await
-        .map_err(|e| PolarisError::Deserialize(e.to_string()))
+    }
+
+    /// Every page `paginator` yields, as lists of branch IDs.
+    async fn drain(mut paginator: Paginator<'_, crate::common::Branch>) -> Vec<Vec<String>> {
+        let mut pages = Vec::new();
+        while let Some(page) = paginator.next_page().await.unwrap() {
+            pages.push(page.data.into_iter().map(|b| b.id).collect());
+        }
+        assert!(!paginator.has_more());
+        assert_eq!(paginator.next_page().await.unwrap().map(|p| p.data.len()), None);
+        pages
+    }
+
+    #[tokio::test]
+    async fn paginator_stops_after_a_short_page() {
+        let server = branch_server(3, false).await;
+        let client = crate::test_server::test_client(&server);
+        let pages = drain(client.branches_paginator("p1", 2)).await;
+        assert_eq!(pages, [vec!["b0", "b1"], vec!["b2"]]);
+        assert_eq!(server.requests().len(), 2, "{:?}", server.requests());
+    }
+
+    #[tokio::test]
+    async fn paginator_stops_at_the_total_without_an_empty_request() {
+        let server = branch_server(4, true).await;
+        let client = crate::test_server::test_client(&server);
+        let pages = drain(client.branches_paginator("p1", 2)).await;
+        assert_eq!(pages, [vec!["b0", "b1"], vec!["b2", "b3"]]);
+        assert_eq!(server.requests().len(), 2, "{:?}", server.requests());
+    }
+
+    /// Held by tests that set environment variables, which are process-wide.
+    static ENV_LOCK: std::sync::Mutex<()> = std::sync::Mutex::new(());
+
+    /// The variables [`PolarisConfig::from_env`] reads.
+    const CONFIG_VARS: [&str; 7] = [
+        "POLARIS_BASE_URL",
+        "POLARIS_API_TOKEN",
+        "POLARIS_API_TOKEN_FILE",
+        "POLARIS_INSECURE",
+        "POLARIS_CA_CERT",
+        "POLARIS_CA_CERT_DIR",
+        "POLARIS_TIMEOUT",
+    ];
+
+    /// Sets exactly `vars` among [`CONFIG_VARS`] (the rest are removed) until dropped,
+    /// then restores what was there before.
+    struct EnvGuard {
+        saved: Vec<(&'static str, Option<std::ffi::OsString>)>,
+        _lock: std::sync::MutexGuard<'static, ()>,
+    }
+
+    impl EnvGuard {
+        fn set(vars: &[(&str, &str)]) -> Self {
+            let lock = ENV_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+            let saved = CONFIG_VARS.iter().map(|&name| (name, std::env::var_os(name))).collect();
+            for name in CONFIG_VARS {
+                let value = vars.iter().find(|(n, _)| *n == name).map(|(_, v)| *v);
+                // SAFETY: every test that touches the environment holds ENV_LOCK.
+                unsafe {
+                    match value {
+                        Some(value) => std::env::set_var(name, value),
+                        None => std::env::remove_var(name),
+                    }
+                }
+            }
+            Self { saved, _lock: lock }
+        }
+    }
+
+    impl Drop for EnvGuard {
+        fn drop(&mut self) {
+            for (name, value) in &self.saved {
+                // SAFETY: ENV_LOCK is still held.
+                unsafe {
+                    match value {
+                        Some(value) => std::env::set_var(name, value),
+                        None => std::env::remove_var(name),
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn from_env_requires_base_url() {
+        let _env = EnvGuard::set(&[("POLARIS_API_TOKEN", "t")]);
+        let err = PolarisConfig::from_env().unwrap_err();
+        assert!(err.to_string().contains("POLARIS_BASE_URL"), "{err}");
+    }
+
+    #[test]
+    fn from_env_requires_a_token() {
+        let _env = EnvGuard::set(&[("POLARIS_BASE_URL", "https://polaris.example.com")]);
+        let err = PolarisConfig::from_env().unwrap_err();
+        assert!(err.to_string().contains("POLARIS_API_TOKEN_FILE"), "{err}");
+    }
+
+    #[test]
+    fn from_env_reads_the_token_file() {
+        let path = std::env::temp_dir().join(format!("polaris-token-{}", std::process::id()));
+        std::fs::write(&path, "file-token\n").unwrap();
+        let _env = EnvGuard::set(&[
+            ("POLARIS_BASE_URL", "https://polaris.example.com"),
+            ("POLARIS_API_TOKEN_FILE", path.to_str().unwrap()),
+        ]);
+        let config = PolarisConfig::from_env();
+        std::fs::remove_file(&path).unwrap();
+        // The trailing newline goes at login, with the rest of the token clean-up.
+        let token = config.unwrap().api_token;
+        assert_eq!(crate::auth::normalize_api_token(&token).unwrap(), "file-token");
+    }
+
+    #[test]
+    fn from_env_prefers_the_token_to_the_token_file() {
+        let _env = EnvGuard::set(&[
+            ("POLARIS_BASE_URL", "https://polaris.example.com"),
+            ("POLARIS_API_TOKEN", "env-token"),
+            ("POLARIS_API_TOKEN_FILE", "/nonexistent/token"),
+        ]);
+        assert_eq!(PolarisConfig::from_env().unwrap().api_token, "env-token");
+    }
+
+    #[test]
+    fn from_env_reads_insecure() {
+        let insecure = |value: Option<&str>| {
+            let mut vars = vec![("POLARIS_BASE_URL", "https://polaris.example.com"), ("POLARIS_API_TOKEN", "t")];
+            vars.extend(value.map(|v| ("POLARIS_INSECURE", v)));
+            let _env = EnvGuard::set(&vars);
+            PolarisConfig::from_env().unwrap().tls.insecure
+        };
+        assert!(!insecure(None));
+        assert!(insecure(Some("1")));
+        assert!(insecure(Some("true")));
+        assert!(!insecure(Some("0")));
+        assert!(!insecure(Some("Off")));
+    }
+
+    #[test]
+    fn from_env_reads_timeout() {
+        let timeout = |value: &str| {
+            let _env = EnvGuard::set(&[
+                ("POLARIS_BASE_URL", "https://polaris.example.com"),
+                ("POLARIS_API_TOKEN", "t"),
+                ("POLARIS_TIMEOUT", value),
+            ]);
+            PolarisConfig::from_env().map(|c| c.timeout)
+        };
+        assert_eq!(timeout("45").unwrap(), std::time::Duration::from_secs(45));
+        for bad in ["0", "soon"] {
+            let err = timeout(bad).unwrap_err();
+            assert!(err.to_string().contains("invalid POLARIS_TIMEOUT"), "{err}");
+        }
+    }
+
+    #[test]
+    fn merge_included_skips_duplicates() {
+        let (mut all, mut seen) = (Vec::new(), HashSet::new());
+        let taxon = serde_json::json!({ "type": "taxon", "id": "high" });
+        merge_included(&mut all, &mut seen, vec![taxon.clone()]);
+        merge_included(&mut all, &mut seen, vec![taxon, serde_json::json!({ "type": "taxon", "id": "low" })]);
+        assert_eq!(all.len(), 2);
+    }
 }
@@ -1,3 +1,4 @@
+use chrono::{DateTime, Utc};
 use serde::Deserialize;
 
 // JSON:API resource types for Common Object Service
@@ -63,11 +64,51 @@ pub struct Project {
     pub relationships: Option<serde_json::Value>,
 }
 
+/// Largest `page[limit]` the common-object service (projects, branches, runs) accepts.
+pub const MAX_COMMON_PAGE_SIZE: u32 = 100;
+
+/// Custom project property conventionally holding the owner's email address.
+pub const PROJECT_OWNER_PROPERTY: &str = "owner";
+
 #[derive(Debug, Clone, Deserialize)]
 pub struct ProjectAttributes {
     pub name: String,
     #[serde(rename = "description", default)]
     pub description: Option<String>,
+    /// Custom key/value properties set on the project.
+    #[serde(default)]
+    pub properties: Option<serde_json::Value>,
+    #[serde(rename = "date-created", alias = "creation-date", default)]
+    pub date_created: Option<String>,
+    #[serde(rename = "date-modified", alias = "last-modified-date", default)]
+    pub date_modified: Option<String>,
+}
+
+impl ProjectAttributes {
+    /// Look up a custom project property by key.
+    pub fn property(&self, key: &str) -> Option<&str> {
+        self.properties.as_ref()?.get(key)?.as_str()
+    }
+
+    /// The project owner, taken from the `owner` custom property.
+    pub fn owner(&self) -> Option<&str> {
+        self.property(PROJECT_OWNER_PROPERTY)
+    }
+
+    /// Last activity on the project: the modification date, falling back to creation.
+    pub fn last_activity(&self) -> Option<DateTime<Utc>> {
+        self.date_modified
+            .as_deref()
+            .and_then(parse_timestamp)
+            .or_else(|| self.date_created.as_deref().and_then(parse_timestamp))
+    }
+}
+
+/// Parse an RFC 3339 timestamp as returned by the Polaris APIs.
+pub fn parse_timestamp(s: &str) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(s)
+        .ok()
+        .map(|d| d.with_timezone(&Utc))
 }
 
 #[derive(Debug, Clone, Deserialize)]
@@ -83,6 +124,20 @@ pub struct BranchAttributes {
     pub name: String,
     #[serde(rename = "main-for-project", default)]
     pub main_for_project: Option<bool>,
+    #[serde(rename = "date-created", alias = "creation-date", default)]
+    pub date_created: Option<String>,
+    #[serde(rename = "date-modified", alias = "last-modified-date", default)]
+    pub date_modified: Option<String>,
+}
+
+impl BranchAttributes {
+    /// Last activity on the branch: the modification date, falling back to creation.
+    pub fn last_activity(&self) -> Option<DateTime<Utc>> {
+        self.date_modified
+            .as_deref()
+            .and_then(parse_timestamp)
+            .or_else(|| self.date_created.as_deref().and_then(parse_timestamp))
+    }
 }
 
 #[derive(Debug, Clone, Deserialize)]
@@ -91,6 +146,15 @@ pub struct Run {
     pub resource_type: String,
     pub id: String,
     pub attributes: RunAttributes,
+    #[serde(default)]
+    pub relationships: Option<serde_json::Value>,
+}
+
+impl Run {
+    /// The ID of the project the run belongs to, when the server links it.
+    pub fn project_id(&self) -> Option<&str> {
+        self.relationships.as_ref()?.pointer("/project/data/id")?.as_str()
+    }
 }
 
 #[derive(Debug, Clone, Deserialize)]
@@ -103,13 +167,37 @@ pub struct RunAttributes {
     pub date_completed: Option<String>,
 }
 
+impl RunAttributes {
+    /// Whether the run has stopped: `Some(true)` once completed, `Some(false)` once
+    /// failed or cancelled, `None` while it is still queued or running.
+    pub fn outcome(&self) -> Option<bool> {
+        match self.status.as_deref()?.to_ascii_lowercase().as_str() {
+            "completed" | "complete" | "succeeded" | "success" => Some(true),
+            "failed" | "failure" | "error" | "cancelled" | "canceled" | "aborted" => Some(false),
+            _ => None,
+        }
+    }
+}
+
 pub struct CommonClient {
     http: reqwest::Client,
     base_url: String,
+    extra_query: Vec<(String, String)>,
+    verbose: bool,
+    retry: crate::http::RetryPolicy,
+    throttle: std::sync::Arc<crate::http::Throttle>,
+    raw_responses: Option<crate::client::RawResponseHook>,
+    cache: Option<crate::cache::ResponseCache>,
+    trace: Option<crate::trace::TraceLog>,
 }
 
 impl CommonClient {
     pub fn new(base_url: &str, jwt: &str) -> crate::error::Result<Self> {
+        Self::with_tls(base_url, jwt, &crate::client::TlsConfig::default())
+    }
+
+    /// Like [`CommonClient::new`], with custom TLS settings.
+    pub fn with_tls(base_url: &str, jwt: &str, tls: &crate::client::TlsConfig) -> crate::error::Result<Self> {
         let mut headers = reqwest::header::HeaderMap::new();
         headers.insert(
             reqwest::header::AUTHORIZATION,
@@ -121,43 +209,119 @@ impl CommonClient {
             reqwest::header::HeaderValue::from_static("application/vnd.api+json"),
         );
 
-        let http = reqwest::Client::builder()
+        let http = crate::http::client_builder(tls)
             .default_headers(headers)
             .timeout(std::time::Duration::from_secs(120))
             .connect_timeout(std::time::Duration::from_secs(30))
             .build()
             .map_err(crate::error::PolarisError::Http)?;
 
-        Ok(Self {
+        Ok(Self::from_http(http, base_url))
+    }
+
+    /// Wrap an already-authenticated HTTP client.
+    pub(crate) fn from_http(http: reqwest::Client, base_url: &str) -> Self {
+        Self {
             http,
-            base_url: base_url.trim_end_matches('/').to_string(),
-        })
+            base_url: base_url.to_string(),
+            extra_query: Vec::new(),
+            verbose: false,
+            retry: crate::http::RetryPolicy::default(),
+            throttle: Default::default(),
+            raw_responses: None,
+            cache: None,
+            trace: None,
+        }
+    }
+
+    /// Append `extra_query` to every request URL and optionally log the URLs to stderr.
+    pub fn with_debug(mut self, extra_query: Vec<(String, String)>, verbose: bool) -> Self {
+        self.extra_query = extra_query;
+        self.verbose = verbose;
+        self
+    }
+
+    /// Retry failed requests according to `policy`, pacing them with a shared throttle.
+    pub(crate) fn with_retry(
+        mut self,
+        policy: crate::http::RetryPolicy,
+        throttle: std::sync::Arc<crate::http::Throttle>,
+    ) -> Self {
+        self.retry = policy;
+        self.throttle = throttle;
+        self
+    }
+
+    /// Pass each successful response body to `hook` before parsing it.
+    pub(crate) fn with_raw_responses(mut self, hook: Option<crate::client::RawResponseHook>) -> Self {
+        self.raw_responses = hook;
+        self
+    }
+
+    /// Revalidate project and branch listings against an on-disk cache in `dir`.
+    pub(crate) fn with_cache(mut self, dir: Option<std::path::PathBuf>) -> Self {
+        self.cache = dir.map(|dir| crate::cache::ResponseCache::new(dir, self.verbose));
+        self
+    }
+
+    /// Record every exchange in `trace`.
+    pub(crate) fn with_trace(mut self, trace: Option<crate::trace::TraceLog>) -> Self {
+        self.trace = trace;
+        self
+    }
+
+    /// GET a listing through the response cache (a plain GET when caching is off).
+    async fn get_cached<T: serde::de::DeserializeOwned>(&self, path: &str) -> crate::error::Result<T> {
+        let body = crate::cache::get(
+            &self.http,
+            self.url(path)?,
+            &self.retry,
+            &self.throttle,
+            self.cache.as_ref(),
+            self.raw_responses.as_ref(),
+            self.trace.as_ref(),
+        )
+        .await?;
+        crate::http::parse_json(&body)
     }
 
-    /// List projects, optionally filtering by name.
+    fn url(&self, path: &str) -> crate::error::Result<reqwest::Url> {
+        let url = crate::http::api_url(&self.base_url, path)?;
+        Ok(crate::http::finish_url(url, &self.extra_query, self.verbose))
+    }
+
+    /// List projects, optionally filtering by name and owner.
+    ///
+    /// The owner filter is applied server-side against the `owner` custom project property.
     pub async fn list_projects(
         &self,
         name_filter: Option<&str>,
+        owner_filter: Option<&str>,
         limit: u32,
         offset: u32,
     ) -> crate::error::Result<JsonApiResponse<Project>> {
-        let mut url = format!(
-            "{}/api/common/v0/projects?page[limit]={limit}&page[offset]={offset}",
-            self.base_url
+        let limit = crate::http::clamp_page_size("projects", limit, MAX_COMMON_PAGE_SIZE);
+        let mut path = format!(
+            "api/common/v0/projects?page[limit]={limit}&page[offset]={offset}",
         );
 
         if let Some(name) = name_filter {
-            url.push_str(&format!(
+            path.push_str(&format!(
                 "&filter[project][name][$eq]={}",
                 urlencoding::encode(name)
             ));
         }
+        if let Some(owner) = owner_filter {
+            path.push_str(&format!(
+                "&filter[project][properties][{PROJECT_OWNER_PROPERTY}][$eq]={}",
+                urlencoding::encode(owner)
+            ));
+        }
 
         // Always include branches
-        url.push_str("&include[project][]=branches");
+        path.push_str("&include[project][]=branches");
 
-        let resp = self.http.get(&url).send().await?;
-        Self::check_response(resp).await
+        self.get_cached(&path).await
     }
 
     /// List branches for a project.
@@ -167,14 +331,26 @@ impl CommonClient {
         limit: u32,
         offset: u32,
     ) -> crate::error::Result<JsonApiResponse<Branch>> {
-        let url = format!(
-            "{}/api/common/v0/branches?filter[branch][project][id][$eq]={}&page[limit]={limit}&page[offset]={offset}",
-            self.base_url,
+        let limit = crate::http::clamp_page_size("branches", limit, MAX_COMMON_PAGE_SIZE);
+        let path = format!(
+            "api/common/v0/branches?filter[branch][project][id][$eq]={}&page[limit]={limit}&page[offset]={offset}",
             urlencoding::encode(project_id),
         );
 
-        let resp = self.http.get(&url).send().await?;
-        Self::check_response(resp).await
+        self.get_cached(&path).await
+    }
+
+    /// Get a single run by ID.
+    pub async fn get_run(&self, run_id: &str) -> crate::error::Result<JsonApiSingleResponse<Run>> {
+        let path = format!("api/common/v0/runs/{}", urlencoding::encode(run_id));
+        let resp = crate::http::send_with_retry(
+            self.http.get(self.url(&path)?),
+            &self.retry,
+            &self.throttle,
+            self.trace.as_ref(),
+        )
+        .await?;
+        crate::http::check_response(resp, self.raw_responses.as_ref()).await
     }
 
     /// List runs for a project/revision.
@@ -185,35 +361,48 @@ impl CommonClient {
         limit: u32,
         offset: u32,
     ) -> crate::error::Result<JsonApiResponse<Run>> {
-        let mut url = format!(
-            "{}/api/common/v0/runs?filter[run][project][id][$eq]={}&page[limit]={limit}&page[offset]={offset}",
-            self.base_url,
+        let limit = crate::http::clamp_page_size("runs", limit, MAX_COMMON_PAGE_SIZE);
+        let mut path = format!(
+            "api/common/v0/runs?filter[run][project][id][$eq]={}&page[limit]={limit}&page[offset]={offset}",
             urlencoding::encode(project_id),
         );
 
         if let Some(rev) = revision_id {
-            url.push_str(&format!("&filter[run][revision][id][$eq]={}", urlencoding::encode(rev)));
+            path.push_str(&format!("&filter[run][revision][id][$eq]={}", urlencoding::encode(rev)));
         }
 
-        let resp = self.http.get(&url).send().await?;
-        Self::check_response(resp).await
-    }
-
-    async fn check_response<T: serde::de::DeserializeOwned>(
-        resp: reqwest::Response,
-    ) -> crate::error::Result<T> {
-        let status = resp.status();
-        if !status.is_success() {
-            let code = status.as_u16();
-            let body = resp.text().await.unwrap_or_default();
-            return Err(crate::error::PolarisError::Api {
-                status: code,
-                detail: body,
-            });
-        }
+        let resp = crate::http::send_with_retry(
+            self.http.get(self.url(&path)?),
+            &self.retry,
+            &self.throttle,
+            self.trace.as_ref(),
+        )
+        .await?;
+        crate::http::check_response(resp, self.raw_responses.as_ref()).await
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used, clippy::expect_used)]
+mod tests {
+    use crate::error::PolarisError;
+    use crate::test_server::{Reply, TestServer, test_client};
+
+    async fn not_found_server() -> TestServer {
+        TestServer::start(|_| Reply::json(404, r#"{"errors":[{"status":"404","detail":"Project p9 not found"}]}"#)).await
+    }
+
+    #[tokio::test]
+    async fn missing_project_branches_are_not_found() {
+        let server = not_found_server().await;
+        let err = test_client(&server).list_branches("p9", 10, 0).await.unwrap_err();
+        assert!(matches!(&err, PolarisError::NotFound(detail) if detail == "Project p9 not found"), "{err:?}");
+    }
 
-        resp.json::<T>()
-            .await
-            .map_err(|e| crate::error::PolarisError::Deserialize(e.to_string()))
+    #[tokio::test]
+    async fn missing_run_is_not_found() {
+        let server = not_found_server().await;
+        let err = test_client(&server).get_run("r9").await.unwrap_err();
+        assert!(matches!(err, PolarisError::NotFound(_)), "{err:?}");
     }
 }
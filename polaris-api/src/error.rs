@@ -5,8 +5,8 @@ pub enum PolarisError {
     #[error("HTTP error: {0}")]
     Http(#[from] reqwest::Error),
 
-    #[error("Authentication failed: {0}")]
-    AuthFailed(String),
+    #[error("Authentication failed{}: {detail}", http_status_suffix(*.status))]
+    AuthFailed { status: Option<u16>, detail: String },
 
     #[error("API error {status}: {detail}")]
     Api { status: u16, detail: String },
@@ -17,8 +17,62 @@ pub enum PolarisError {
     #[error("Deserialization error: {0}")]
     Deserialize(String),
 
+    /// A successful response that isn't JSON, typically an HTML page from a wrong base
+    /// URL or an SSO portal that intercepted the request.
+    #[error(
+        "expected JSON but received {content_type}; check the base URL, and that requests aren't being redirected to an SSO login page"
+    )]
+    NotJson { content_type: String },
+
+    /// A mutating call on a client configured with
+    /// [`PolarisConfig::read_only`](crate::client::PolarisConfig::read_only); nothing was sent.
+    #[error("read-only mode: mutation refused ({0})")]
+    ReadOnly(String),
+
     #[error("{0}")]
     Other(String),
 }
 
+impl PolarisError {
+    /// HTTP status code behind the error, when there is one.
+    pub fn status(&self) -> Option<u16> {
+        match self {
+            PolarisError::Http(e) => e.status().map(|s| s.as_u16()),
+            PolarisError::AuthFailed { status, .. } => *status,
+            PolarisError::Api { status, .. } => Some(*status),
+            PolarisError::NotFound(_) => Some(404),
+            PolarisError::Deserialize(_)
+            | PolarisError::NotJson { .. }
+            | PolarisError::ReadOnly(_)
+            | PolarisError::Other(_) => None,
+        }
+    }
+
+    /// Whether retrying the same request might succeed: timeouts, connection
+    /// failures, 429 and 5xx responses. Authentication and other client errors
+    /// are never retryable.
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            PolarisError::Http(e) => {
+                e.is_timeout() || e.is_connect() || e.status().is_some_and(|s| retryable_status(s.as_u16()))
+            }
+            PolarisError::Api { status, .. } => retryable_status(*status),
+            PolarisError::AuthFailed { .. }
+            | PolarisError::NotFound(_)
+            | PolarisError::Deserialize(_)
+            | PolarisError::NotJson { .. }
+            | PolarisError::ReadOnly(_)
+            | PolarisError::Other(_) => false,
+        }
+    }
+}
+
+pub(crate) fn retryable_status(status: u16) -> bool {
+    status == 429 || (500..600).contains(&status)
+}
+
+fn http_status_suffix(status: Option<u16>) -> String {
+    status.map(|s| format!(" (HTTP {s})")).unwrap_or_default()
+}
+
 pub type Result<T> = std::result::Result<T, PolarisError>;
@@ -0,0 +1,702 @@
+use url::Url;
+
+use crate::client::{RateLimitConfig, RawResponseHook, TlsConfig};
+use crate::error::{PolarisError, Result};
+
+/// Resolve an API path (e.g. `api/query/v1/issues?...`) against the base URL.
+///
+/// The base URL may carry a path prefix for reverse-proxied deployments
+/// (`https://host/polaris`); the prefix is kept and the API path is joined below it.
+///
+/// The path must stay on the base URL's origin: an absolute URL or `//host/...` for
+/// another scheme, host or port is an error, so a caller-supplied path (`polaris api
+/// get`) can't carry the session token elsewhere.
+pub(crate) fn api_url(base_url: &str, path: &str) -> Result<Url> {
+    let mut base = Url::parse(base_url)
+        .map_err(|e| PolarisError::Other(format!("invalid base URL `{base_url}`: {e}")))?;
+    if !base.path().ends_with('/') {
+        let prefixed = format!("{}/", base.path());
+        base.set_path(&prefixed);
+    }
+    let url = base
+        .join(path.trim_start_matches('/'))
+        .map_err(|e| PolarisError::Other(format!("invalid API path `{path}`: {e}")))?;
+    if url.scheme() != base.scheme()
+        || url.host_str() != base.host_str()
+        || url.port_or_known_default() != base.port_or_known_default()
+    {
+        return Err(PolarisError::Other(format!(
+            "API path `{path}` points outside the base URL {base}; pass a path such as `api/...`"
+        )));
+    }
+    Ok(url)
+}
+
+/// Append caller-supplied query parameters (URL-encoded) to a resolved API URL,
+/// logging the final URL to stderr when `verbose` is set.
+pub(crate) fn finish_url(mut url: Url, extra_query: &[(String, String)], verbose: bool) -> Url {
+    if !extra_query.is_empty() {
+        let mut pairs = url.query_pairs_mut();
+        for (key, value) in extra_query {
+            pairs.append_pair(key, value);
+        }
+    }
+    if verbose {
+        eprintln!("[polaris] {url}");
+    }
+    url
+}
+
+/// Start a reqwest client builder with the configured TLS settings applied. Every
+/// client advertises `Accept-Encoding: gzip, br` and decodes compressed bodies
+/// transparently, JSON and `text/plain` source alike.
+pub(crate) fn client_builder(tls: &TlsConfig) -> reqwest::ClientBuilder {
+    reqwest::Client::builder()
+        .gzip(true)
+        .brotli(true)
+        .tls_certs_merge(tls.root_certs.iter().cloned())
+        .tls_danger_accept_invalid_certs(tls.insecure)
+}
+
+/// Clamp a requested page size to an endpoint's maximum, warning on stderr when it
+/// has to be reduced. Sending more than the server accepts gets silently capped
+/// responses, which would end pagination loops early.
+pub(crate) fn clamp_page_size(endpoint: &str, requested: u32, max: u32) -> u32 {
+    if requested > max {
+        eprintln!("warning: page size {requested} exceeds the {endpoint} maximum of {max}; using {max}");
+        max
+    } else {
+        requested.max(1)
+    }
+}
+
+/// Turn a response into its body text: non-2xx statuses become errors (404 as
+/// [`PolarisError::NotFound`], anything else as [`PolarisError::Api`]) and successful
+/// bodies are passed to the raw-response hook. Timeouts while reading the body surface
+/// as [`PolarisError::Http`], so `is_retryable` classifies them like send timeouts.
+pub(crate) async fn read_body(resp: reqwest::Response, raw: Option<&RawResponseHook>) -> Result<String> {
+    let body = check_status(resp).await?.text().await?;
+    if let Some(hook) = raw {
+        hook.call(&body);
+    }
+    Ok(body)
+}
+
+/// Map a non-2xx response to an error as [`read_body`] does, leaving the body of a
+/// successful one unread for callers that stream it.
+pub(crate) async fn check_status(resp: reqwest::Response) -> Result<reqwest::Response> {
+    let status = resp.status();
+    if status.is_success() {
+        return Ok(resp);
+    }
+    let code = status.as_u16();
+    let detail = error_detail(&resp.text().await.unwrap_or_default());
+    Err(if code == 404 {
+        PolarisError::NotFound(detail)
+    } else {
+        PolarisError::Api { status: code, detail }
+    })
+}
+
+/// [`expect_json`], [`read_body`], then [`parse_json`]. Every JSON endpoint in the
+/// crate goes through here.
+pub(crate) async fn check_response<T: serde::de::DeserializeOwned>(
+    resp: reqwest::Response,
+    raw: Option<&RawResponseHook>,
+) -> Result<T> {
+    expect_json(&resp)?;
+    parse_json(&read_body(resp, raw).await?)
+}
+
+/// Reject a successful response whose `Content-Type` isn't JSON (`application/json`,
+/// `application/vnd.api+json`, ...) with [`PolarisError::NotJson`], rather than letting
+/// an HTML login page fail later as a parse error. Responses without a `Content-Type`,
+/// and error statuses (reported by [`read_body`]), are let through.
+pub(crate) fn expect_json(resp: &reqwest::Response) -> Result<()> {
+    if !resp.status().is_success() {
+        return Ok(());
+    }
+    let Some(content_type) = resp
+        .headers()
+        .get(reqwest::header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+    else {
+        return Ok(());
+    };
+    let mime = content_type.split(';').next().unwrap_or_default().trim().to_ascii_lowercase();
+    if mime.is_empty() || mime.ends_with("json") {
+        return Ok(());
+    }
+    let content_type = if mime == "text/html" { "an HTML page".to_string() } else { format!("`{mime}`") };
+    Err(PolarisError::NotJson { content_type })
+}
+
+/// The human-readable part of an error body: the JSON:API `errors[].detail` (or
+/// `title`) entries, a top-level `detail`/`message`, or else the body as sent.
+fn error_detail(body: &str) -> String {
+    let Ok(json) = serde_json::from_str::<serde_json::Value>(body) else {
+        return body.to_string();
+    };
+    let text = |v: &serde_json::Value| {
+        ["detail", "title", "message"]
+            .iter()
+            .find_map(|k| v.get(k).and_then(|s| s.as_str()).filter(|s| !s.is_empty()))
+            .map(str::to_string)
+    };
+    let from_errors: Vec<String> = json
+        .get("errors")
+        .and_then(|e| e.as_array())
+        .map(|errors| errors.iter().filter_map(text).collect())
+        .unwrap_or_default();
+    if !from_errors.is_empty() {
+        return from_errors.join("; ");
+    }
+    text(&json).unwrap_or_else(|| body.to_string())
+}
+
+/// Parse a JSON response body. Errors name the field that failed
+/// (`data[3].attributes.finding-key`) and quote the JSON around it.
+pub(crate) fn parse_json<T: serde::de::DeserializeOwned>(body: &str) -> Result<T> {
+    let de = &mut serde_json::Deserializer::from_str(body);
+    serde_path_to_error::deserialize(de).map_err(|e| {
+        let path = e.path().to_string();
+        let inner = e.into_inner();
+        let at = snippet(body, inner.line(), inner.column());
+        // `.` is the document itself, `?` a position serde couldn't name (e.g. EOF).
+        if path == "." || path == "?" {
+            PolarisError::Deserialize(format!("failed to parse response: {inner}{at}"))
+        } else {
+            PolarisError::Deserialize(format!("failed to parse field `{path}`: {inner}{at}"))
+        }
+    })
+}
+
+/// Up to 40 characters either side of a 1-based line/column position, as ` near `...``.
+fn snippet(body: &str, line: usize, column: usize) -> String {
+    const CONTEXT: usize = 40;
+    let Some(text) = body.lines().nth(line.saturating_sub(1)) else {
+        return String::new();
+    };
+    let chars: Vec<char> = text.chars().collect();
+    let at = column.saturating_sub(1).min(chars.len());
+    let near: String = chars[at.saturating_sub(CONTEXT)..(at + CONTEXT).min(chars.len())].iter().collect();
+    if near.trim().is_empty() {
+        String::new()
+    } else {
+        format!(" near `{}`", near.trim())
+    }
+}
+
+/// Retry budgets for one request, per failure class, plus the optional budget
+/// shared by every request of a client.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct RetryPolicy {
+    /// Retries for 429/5xx responses and connection failures, with exponential backoff.
+    pub max_retries: u32,
+    /// Retries for requests that hit the client timeout, with a short fixed delay.
+    pub timeout_retries: u32,
+    pub budget: Option<std::sync::Arc<RetryBudget>>,
+}
+
+/// Time a client may spend retrying, summed over all its requests: backoff sleeps
+/// plus the retried attempts themselves.
+///
+/// A retry's backoff is reserved before it sleeps, in one atomic step that refuses
+/// when too little is left, so concurrent requests can't all see room for the same
+/// last seconds. The retried attempt is charged once it returns; an attempt already
+/// in flight when the budget runs out is let finish.
+#[derive(Debug)]
+pub(crate) struct RetryBudget {
+    limit: std::time::Duration,
+    /// Retry time left, in nanoseconds.
+    remaining: std::sync::atomic::AtomicU64,
+    warned: std::sync::atomic::AtomicBool,
+}
+
+impl RetryBudget {
+    pub(crate) fn new(limit: std::time::Duration) -> Self {
+        Self {
+            limit,
+            remaining: std::sync::atomic::AtomicU64::new(nanos(limit)),
+            warned: std::sync::atomic::AtomicBool::new(false),
+        }
+    }
+
+    /// Take `time` from the budget if that much is left. Warns on stderr the first
+    /// time it isn't.
+    fn try_reserve(&self, time: std::time::Duration) -> bool {
+        use std::sync::atomic::Ordering;
+
+        let reserved = self
+            .remaining
+            .fetch_update(Ordering::AcqRel, Ordering::Acquire, |left| left.checked_sub(nanos(time)))
+            .is_ok();
+        if !reserved && !self.warned.swap(true, Ordering::Relaxed) {
+            eprintln!(
+                "warning: retry budget of {}s used up; failing requests are no longer retried",
+                self.limit.as_secs()
+            );
+        }
+        reserved
+    }
+
+    /// Take `time` already spent, down to nothing left.
+    fn charge(&self, time: std::time::Duration) {
+        use std::sync::atomic::Ordering;
+
+        let _ = self
+            .remaining
+            .fetch_update(Ordering::AcqRel, Ordering::Acquire, |left| Some(left.saturating_sub(nanos(time))));
+    }
+}
+
+fn nanos(time: std::time::Duration) -> u64 {
+    u64::try_from(time.as_nanos()).unwrap_or(u64::MAX)
+}
+
+/// Send a request, retrying according to `policy`.
+///
+/// The two per-request budgets are independent: a request that times out once and
+/// then gets a 503 spends one of each. A retry also has to fit in the shared budget,
+/// if there is one; otherwise the last response or error is returned. Requests whose
+/// body cannot be cloned are sent once.
+pub(crate) async fn send_with_retry(
+    request: reqwest::RequestBuilder,
+    policy: &RetryPolicy,
+    throttle: &Throttle,
+    trace: Option<&crate::trace::TraceLog>,
+) -> Result<reqwest::Response> {
+    let mut status_retries = 0;
+    let mut timeout_retries = 0;
+    let mut retrying = false;
+    let in_budget = |delay| policy.budget.as_ref().is_none_or(|b| b.try_reserve(delay));
+    loop {
+        let started = tokio::time::Instant::now();
+        throttle.wait().await;
+        let Some(attempt) = request.try_clone() else {
+            let resp = crate::trace::send(request, trace).await?;
+            throttle.observe(resp.headers());
+            return Ok(resp);
+        };
+        let sent = crate::trace::send(attempt, trace).await;
+        if retrying && let Some(budget) = &policy.budget {
+            budget.charge(started.elapsed());
+        }
+        let delay = match sent {
+            Ok(resp) => {
+                throttle.observe(resp.headers());
+                let status = resp.status().as_u16();
+                let delay = retry_backoff(status_retries + 1);
+                if !crate::error::retryable_status(status)
+                    || status_retries >= policy.max_retries
+                    || !in_budget(delay)
+                {
+                    return Ok(resp);
+                }
+                status_retries += 1;
+                delay
+            }
+            Err(e) => {
+                let err = PolarisError::Http(e);
+                let timed_out = matches!(&err, PolarisError::Http(e) if e.is_timeout());
+                if timed_out && timeout_retries < policy.timeout_retries && in_budget(TIMEOUT_RETRY_DELAY) {
+                    timeout_retries += 1;
+                    TIMEOUT_RETRY_DELAY
+                } else if !timed_out
+                    && err.is_retryable()
+                    && status_retries < policy.max_retries
+                    && in_budget(retry_backoff(status_retries + 1))
+                {
+                    status_retries += 1;
+                    retry_backoff(status_retries)
+                } else {
+                    return Err(err);
+                }
+            }
+        };
+        retrying = true;
+        tokio::time::sleep(delay).await;
+    }
+}
+
+const TIMEOUT_RETRY_DELAY: std::time::Duration = std::time::Duration::from_millis(250);
+
+/// 500ms, 1s, 2s, ... capped at 8s.
+fn retry_backoff(attempt: u32) -> std::time::Duration {
+    std::time::Duration::from_millis(500 << attempt.saturating_sub(1).min(4))
+}
+
+/// Longest pause inserted between requests when the rate limit runs low.
+const MAX_THROTTLE_DELAY: std::time::Duration = std::time::Duration::from_secs(10);
+
+/// Adaptive delay driven by the gateway's rate-limit headers, shared by every request
+/// a client (and its clones) sends.
+#[derive(Debug, Default)]
+pub(crate) struct Throttle {
+    config: RateLimitConfig,
+    verbose: bool,
+    pause_until: std::sync::Mutex<Option<tokio::time::Instant>>,
+}
+
+impl Throttle {
+    pub(crate) fn new(config: RateLimitConfig, verbose: bool) -> Self {
+        Self {
+            config,
+            verbose,
+            pause_until: std::sync::Mutex::new(None),
+        }
+    }
+
+    async fn wait(&self) {
+        let until = self.pause_until.lock().ok().and_then(|p| *p);
+        if let Some(until) = until {
+            tokio::time::sleep_until(until).await;
+        }
+    }
+
+    /// Read the rate-limit headers off a response. Below `slow_below` remaining
+    /// requests, spread the rest evenly over the time left until the reset.
+    fn observe(&self, headers: &reqwest::header::HeaderMap) {
+        let header = |name: &str| {
+            headers
+                .get(name)
+                .and_then(|v| v.to_str().ok())
+                .and_then(|v| v.trim().parse::<u64>().ok())
+        };
+        let Some(remaining) = header(&self.config.remaining_header) else {
+            return;
+        };
+        let reset_in = header(&self.config.reset_header).map(reset_delay);
+        if self.verbose {
+            match reset_in {
+                Some(reset) => eprintln!("[polaris] rate limit: {remaining} remaining, resets in {}s", reset.as_secs()),
+                None => eprintln!("[polaris] rate limit: {remaining} remaining"),
+            }
+        }
+
+        let pause = (remaining < u64::from(self.config.slow_below)).then(|| {
+            let window = reset_in.unwrap_or(std::time::Duration::from_secs(1));
+            (window / (remaining as u32 + 1)).min(MAX_THROTTLE_DELAY)
+        });
+        if let Ok(mut until) = self.pause_until.lock() {
+            *until = pause.map(|p| tokio::time::Instant::now() + p);
+        }
+    }
+}
+
+/// Reset headers carry either seconds until the reset or a Unix timestamp.
+fn reset_delay(value: u64) -> std::time::Duration {
+    const EPOCH_CUTOFF: u64 = 1_000_000_000;
+    if value < EPOCH_CUTOFF {
+        return std::time::Duration::from_secs(value);
+    }
+    let now = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs());
+    std::time::Duration::from_secs(value.saturating_sub(now))
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used, clippy::expect_used)]
+mod tests {
+    use super::*;
+    use crate::test_server::{Reply, TestServer};
+
+    fn response(status: u16, content_type: &str, body: &str) -> reqwest::Response {
+        let resp = http::Response::builder()
+            .status(status)
+            .header("Content-Type", content_type)
+            .body(body.to_string())
+            .unwrap();
+        reqwest::Response::from(resp)
+    }
+
+    #[derive(Debug, serde::Deserialize)]
+    struct Named {
+        name: String,
+    }
+
+    #[test]
+    fn api_url_keeps_base_path_prefix() {
+        for base in ["https://host/polaris", "https://host/polaris/"] {
+            for path in ["api/query/v1/issues", "/api/query/v1/issues"] {
+                let url = api_url(base, path).unwrap();
+                assert_eq!(url.as_str(), "https://host/polaris/api/query/v1/issues", "{base} + {path}");
+            }
+        }
+        for base in ["https://host", "https://host/"] {
+            assert_eq!(api_url(base, "/api/x").unwrap().as_str(), "https://host/api/x");
+        }
+    }
+
+    #[test]
+    fn api_url_keeps_path_query() {
+        let url = api_url("https://host/polaris/", "api/query/v1/issues?project-id=p1&branch-id=b%201").unwrap();
+        assert_eq!(url.path(), "/polaris/api/query/v1/issues");
+        assert_eq!(url.query(), Some("project-id=p1&branch-id=b%201"));
+    }
+
+    #[test]
+    fn api_url_drops_base_query() {
+        // A query on the base URL is replaced by the API path's, never merged into it.
+        let url = api_url("https://host/polaris?tenant=a", "api/x?y=1").unwrap();
+        assert_eq!(url.as_str(), "https://host/polaris/api/x?y=1");
+    }
+
+    #[test]
+    fn api_url_rejects_invalid_base() {
+        let err = api_url("not a url", "api/x").unwrap_err();
+        assert!(err.to_string().contains("invalid base URL"), "{err}");
+    }
+
+    #[test]
+    fn api_url_rejects_other_origins() {
+        let base = "https://polaris.example.com/polaris";
+        for path in [
+            "https://attacker.example/x",
+            "http://polaris.example.com/api/x",
+            "https://polaris.example.com:8443/api/x",
+        ] {
+            let err = api_url(base, path).unwrap_err();
+            assert!(err.to_string().contains("points outside the base URL"), "{path}: {err}");
+        }
+        // Leading slashes are trimmed, so a scheme-relative path stays below the base.
+        let url = api_url(base, "//attacker.example/x").unwrap();
+        assert_eq!(url.as_str(), "https://polaris.example.com/polaris/attacker.example/x");
+        // Same origin spelled out in full is still fine.
+        let url = api_url(base, "https://polaris.example.com/polaris/api/x").unwrap();
+        assert_eq!(url.as_str(), "https://polaris.example.com/polaris/api/x");
+    }
+
+    #[tokio::test]
+    async fn check_response_parses_success() {
+        let named: Named = check_response(response(200, "application/vnd.api+json", r#"{"name":"p1"}"#), None)
+            .await
+            .unwrap();
+        assert_eq!(named.name, "p1");
+    }
+
+    #[tokio::test]
+    async fn check_response_maps_404_to_not_found() {
+        let body = r#"{"errors":[{"status":"404","detail":"Project not found"}]}"#;
+        let err = check_response::<Named>(response(404, "application/json", body), None).await.unwrap_err();
+        assert!(matches!(&err, PolarisError::NotFound(detail) if detail == "Project not found"), "{err:?}");
+    }
+
+    #[tokio::test]
+    async fn check_response_maps_429_to_retryable_api_error() {
+        let err = check_response::<Named>(response(429, "application/json", r#"{"message":"slow down"}"#), None)
+            .await
+            .unwrap_err();
+        assert!(matches!(&err, PolarisError::Api { status: 429, detail } if detail == "slow down"), "{err:?}");
+        assert!(err.is_retryable());
+    }
+
+    #[tokio::test]
+    async fn check_response_reports_malformed_json() {
+        let err = check_response::<Named>(response(200, "application/json", "not json"), None)
+            .await
+            .unwrap_err();
+        assert!(matches!(&err, PolarisError::Deserialize(msg) if msg.starts_with("failed to parse response")), "{err:?}");
+    }
+
+    #[tokio::test]
+    async fn check_response_rejects_html() {
+        let err = check_response::<Named>(response(200, "text/html; charset=utf-8", "<html></html>"), None)
+            .await
+            .unwrap_err();
+        assert!(matches!(&err, PolarisError::NotJson { content_type } if content_type == "an HTML page"), "{err:?}");
+    }
+
+    #[test]
+    fn error_detail_prefers_json_api_errors() {
+        let body = r#"{"errors":[{"detail":"bad filter"},{"title":"CONFLICT","detail":""}],"message":"ignored"}"#;
+        assert_eq!(error_detail(body), "bad filter; CONFLICT");
+        assert_eq!(error_detail(r#"{"errors":[],"detail":"top level"}"#), "top level");
+        assert_eq!(error_detail(r#"{"message":"gateway says no"}"#), "gateway says no");
+    }
+
+    #[test]
+    fn error_detail_falls_back_to_the_body() {
+        assert_eq!(error_detail("Bad Gateway"), "Bad Gateway");
+        assert_eq!(error_detail(r#"{"code":7}"#), r#"{"code":7}"#);
+        assert_eq!(error_detail(""), "");
+    }
+
+    #[test]
+    fn retryable_statuses() {
+        for status in [429, 500, 502, 503, 504, 599] {
+            assert!(crate::error::retryable_status(status), "{status}");
+        }
+        for status in [200, 400, 401, 403, 404, 409, 600] {
+            assert!(!crate::error::retryable_status(status), "{status}");
+        }
+    }
+
+    /// Send one GET to `server` under `policy`; returns the final status and how many
+    /// attempts reached the server.
+    async fn send_to(server: &TestServer, policy: &RetryPolicy, timeout: std::time::Duration) -> (Result<u16>, usize) {
+        let request = reqwest::Client::new().get(format!("{}/api/x", server.url)).timeout(timeout);
+        let result = send_with_retry(request, policy, &Throttle::default(), None).await;
+        (result.map(|r| r.status().as_u16()), server.requests().len())
+    }
+
+    const NO_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(30);
+
+    #[tokio::test]
+    async fn send_with_retry_stops_after_max_retries() {
+        let server = TestServer::start(|_| Reply::json(503, "{}")).await;
+        let none = RetryPolicy::default();
+        assert_eq!(send_to(&server, &none, NO_TIMEOUT).await.1, 1);
+
+        let server = TestServer::start(|_| Reply::json(503, "{}")).await;
+        let one = RetryPolicy {
+            max_retries: 1,
+            ..Default::default()
+        };
+        let (status, attempts) = send_to(&server, &one, NO_TIMEOUT).await;
+        assert_eq!((status.unwrap(), attempts), (503, 2));
+    }
+
+    #[tokio::test]
+    async fn send_with_retry_does_not_retry_client_errors() {
+        let server = TestServer::start(|_| Reply::json(404, "{}")).await;
+        let policy = RetryPolicy {
+            max_retries: 3,
+            ..Default::default()
+        };
+        let (status, attempts) = send_to(&server, &policy, NO_TIMEOUT).await;
+        assert_eq!((status.unwrap(), attempts), (404, 1));
+    }
+
+    #[tokio::test]
+    async fn send_with_retry_respects_the_shared_budget() {
+        let server = TestServer::start(|_| Reply::json(503, "{}")).await;
+        let policy = RetryPolicy {
+            max_retries: 3,
+            budget: Some(std::sync::Arc::new(RetryBudget::new(std::time::Duration::ZERO))),
+            ..Default::default()
+        };
+        let (status, attempts) = send_to(&server, &policy, NO_TIMEOUT).await;
+        assert_eq!((status.unwrap(), attempts), (503, 1), "an empty budget allows no retry");
+    }
+
+    #[test]
+    fn retry_budget_is_never_overspent_by_concurrent_reservations() {
+        let budget = RetryBudget::new(std::time::Duration::from_millis(100));
+        let granted = std::sync::atomic::AtomicU32::new(0);
+        std::thread::scope(|scope| {
+            for _ in 0..16 {
+                scope.spawn(|| {
+                    while budget.try_reserve(std::time::Duration::from_millis(7)) {
+                        granted.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+                    }
+                });
+            }
+        });
+        // 14 × 7ms fit in 100ms; a 15th would overspend.
+        assert_eq!(granted.into_inner(), 14);
+    }
+
+    #[test]
+    fn retry_budget_charges_down_to_zero() {
+        let budget = RetryBudget::new(std::time::Duration::from_secs(1));
+        budget.charge(std::time::Duration::from_secs(5));
+        assert!(!budget.try_reserve(std::time::Duration::from_nanos(1)));
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn concurrent_requests_share_the_retry_budget() {
+        let server = TestServer::start(|_| Reply::json(503, "{}")).await;
+        // Room for two 500ms first retries, not one per request.
+        let policy = RetryPolicy {
+            max_retries: 1,
+            budget: Some(std::sync::Arc::new(RetryBudget::new(std::time::Duration::from_millis(1200)))),
+            ..Default::default()
+        };
+        let tasks: Vec<_> = (0..8)
+            .map(|_| {
+                let (url, policy) = (format!("{}/api/x", server.url), policy.clone());
+                tokio::spawn(async move {
+                    let request = reqwest::Client::new().get(url);
+                    send_with_retry(request, &policy, &Throttle::default(), None).await
+                })
+            })
+            .collect();
+        for task in tasks {
+            assert_eq!(task.await.unwrap().unwrap().status().as_u16(), 503);
+        }
+        assert_eq!(server.requests().len(), 8 + 2);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn send_with_retry_counts_timeouts_separately() {
+        // The handler runs on its own worker thread, so blocking it stalls only the reply.
+        let server = TestServer::start(|_| {
+            std::thread::sleep(std::time::Duration::from_millis(300));
+            Reply::json(200, "{}")
+        })
+        .await;
+        let policy = RetryPolicy {
+            max_retries: 5,
+            timeout_retries: 1,
+            ..Default::default()
+        };
+        let (result, attempts) = send_to(&server, &policy, std::time::Duration::from_millis(100)).await;
+        assert!(matches!(&result, Err(PolarisError::Http(e)) if e.is_timeout()), "{result:?}");
+        assert_eq!(attempts, 2, "timeouts use timeout_retries, not max_retries");
+    }
+
+    // Only deserialized, never read.
+    #[allow(dead_code)]
+    #[derive(Debug, serde::Deserialize)]
+    struct Listing {
+        data: Vec<Item>,
+    }
+
+    #[allow(dead_code)]
+    #[derive(Debug, serde::Deserialize)]
+    struct Item {
+        attributes: ItemAttributes,
+    }
+
+    #[allow(dead_code)]
+    #[derive(Debug, serde::Deserialize)]
+    struct ItemAttributes {
+        #[serde(rename = "finding-key")]
+        finding_key: String,
+    }
+
+    #[test]
+    fn parse_json_names_the_failing_field_with_a_bounded_snippet() {
+        let padding = "x".repeat(200);
+        let body = format!(
+            r#"{{"data":[{{"attributes":{{"finding-key":"a","note":"{padding}"}}}},{{"attributes":{{"finding-key":42,"note":"{padding}"}}}}]}}"#
+        );
+        let PolarisError::Deserialize(msg) = parse_json::<Listing>(&body).unwrap_err() else {
+            panic!("expected a deserialize error");
+        };
+        assert!(msg.starts_with("failed to parse field `data[1].attributes.finding-key`: invalid type"), "{msg}");
+        let (_, near) = msg.split_once(" near `").unwrap();
+        let near = near.strip_suffix('`').unwrap();
+        assert!(near.contains(r#""finding-key":42"#), "{near}");
+        assert!(near.chars().count() <= 80, "snippet not bounded: {} chars", near.chars().count());
+    }
+
+    #[test]
+    fn parse_json_quotes_the_failing_line() {
+        let body = "{\n  \"id\": \"p1\",\n  \"name\": 5\n}";
+        let PolarisError::Deserialize(msg) = parse_json::<Named>(body).unwrap_err() else {
+            panic!("expected a deserialize error");
+        };
+        assert!(msg.contains("near `\"name\": 5`"), "{msg}");
+    }
+
+    #[test]
+    fn parse_json_reports_truncated_bodies_without_a_field() {
+        let PolarisError::Deserialize(msg) = parse_json::<Named>(r#"{"name":"p1""#).unwrap_err() else {
+            panic!("expected a deserialize error");
+        };
+        assert!(msg.starts_with("failed to parse response: EOF while parsing"), "{msg}");
+    }
+}
@@ -15,7 +15,15 @@ pub mod generated {
 }
 
 pub mod auth;
+mod cache;
 pub mod common;
 pub mod client;
 pub mod error;
+mod http;
+pub mod trace;
+pub mod web_url;
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used, clippy::expect_used)]
+mod test_server;
 
@@ -0,0 +1,196 @@
+//! A minimal HTTP/1.1 server for unit tests. Each connection carries one request,
+//! answered with whatever the handler returns for it.
+
+use std::sync::{Arc, Mutex};
+
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+/// A JWT whose `exp` is far in the future, as the login endpoint returns it.
+pub(crate) const TEST_JWT: &str = "x.eyJleHAiOjk5OTk5OTk5OTl9.y";
+
+/// A request as the handler sees it.
+#[derive(Debug, Clone)]
+pub(crate) struct Request {
+    pub method: String,
+    /// Path and query, e.g. `/api/query/v1/issues?project-id=p1`.
+    pub target: String,
+    pub headers: Vec<(String, String)>,
+}
+
+impl Request {
+    /// First value of header `name`, matched case-insensitively.
+    pub(crate) fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(n, _)| n.eq_ignore_ascii_case(name))
+            .map(|(_, v)| v.as_str())
+    }
+}
+
+/// What the server sends back.
+pub(crate) struct Reply {
+    pub status: u16,
+    pub headers: Vec<(String, String)>,
+    pub body: Vec<u8>,
+    /// Send the body with `Transfer-Encoding: chunked`, one write per chunk.
+    pub chunks: Option<Vec<Vec<u8>>>,
+}
+
+impl Reply {
+    pub(crate) fn new(status: u16, content_type: &str, body: impl Into<Vec<u8>>) -> Self {
+        Self {
+            status,
+            headers: vec![("Content-Type".into(), content_type.into())],
+            body: body.into(),
+            chunks: None,
+        }
+    }
+
+    /// A reply whose body is sent as `chunks`, each flushed on its own.
+    pub(crate) fn chunked(status: u16, content_type: &str, chunks: Vec<Vec<u8>>) -> Self {
+        Self {
+            chunks: Some(chunks),
+            ..Self::new(status, content_type, Vec::new())
+        }
+    }
+
+    pub(crate) fn json(status: u16, body: &str) -> Self {
+        Self::new(status, "application/vnd.api+json", body)
+    }
+
+    pub(crate) fn header(mut self, name: &str, value: &str) -> Self {
+        self.headers.push((name.into(), value.into()));
+        self
+    }
+}
+
+pub(crate) struct TestServer {
+    /// Base URL to point a client at, e.g. `http://127.0.0.1:40123`.
+    pub url: String,
+    requests: Arc<Mutex<Vec<Request>>>,
+    logins: Arc<std::sync::atomic::AtomicUsize>,
+}
+
+impl TestServer {
+    /// Serve until the test ends. Login requests (`api/auth/v2/authenticate`) are
+    /// answered with [`TEST_JWT`] before `handler` is asked.
+    pub(crate) async fn start(handler: impl Fn(&Request) -> Reply + Send + Sync + 'static) -> Self {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.expect("bind test server");
+        let url = format!("http://{}", listener.local_addr().expect("test server address"));
+        let requests = Arc::new(Mutex::new(Vec::new()));
+        let logins = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        let (handler, seen, logged_in) = (Arc::new(handler), requests.clone(), logins.clone());
+        tokio::spawn(async move {
+            while let Ok((mut stream, _)) = listener.accept().await {
+                let (handler, seen, logged_in) = (handler.clone(), seen.clone(), logged_in.clone());
+                tokio::spawn(async move {
+                    let Some(request) = read_request(&mut stream).await else {
+                        return;
+                    };
+                    let reply = if request.target.starts_with("/api/auth/v2/authenticate") {
+                        logged_in.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+                        Reply::json(200, &format!(r#"{{"jwt":"{TEST_JWT}"}}"#))
+                    } else {
+                        seen.lock().expect("requests lock").push(request.clone());
+                        handler(&request)
+                    };
+                    let _ = send(&mut stream, &reply).await;
+                    let _ = stream.shutdown().await;
+                });
+            }
+        });
+        Self { url, requests, logins }
+    }
+
+    /// How many login requests were answered.
+    pub(crate) fn logins(&self) -> usize {
+        self.logins.load(std::sync::atomic::Ordering::Relaxed)
+    }
+
+    /// Requests seen so far (logins excluded), as `METHOD target`.
+    pub(crate) fn requests(&self) -> Vec<String> {
+        self.requests
+            .lock()
+            .expect("requests lock")
+            .iter()
+            .map(|r| format!("{} {}", r.method, r.target))
+            .collect()
+    }
+}
+
+async fn read_request(stream: &mut tokio::net::TcpStream) -> Option<Request> {
+    let mut buf = Vec::new();
+    let header_end = loop {
+        let mut chunk = [0u8; 4096];
+        let n = stream.read(&mut chunk).await.ok()?;
+        if n == 0 {
+            return None;
+        }
+        buf.extend_from_slice(&chunk[..n]);
+        if let Some(at) = buf.windows(4).position(|w| w == b"\r\n\r\n") {
+            break at + 4;
+        }
+    };
+    let head = String::from_utf8_lossy(&buf[..header_end]).into_owned();
+    let mut lines = head.lines();
+    let mut start = lines.next()?.split(' ');
+    let (method, target) = (start.next()?.to_string(), start.next()?.to_string());
+    let headers: Vec<(String, String)> = lines
+        .filter_map(|l| l.split_once(':'))
+        .map(|(name, value)| (name.trim().to_string(), value.trim().to_string()))
+        .collect();
+    // Drain the body so the client isn't cut off mid-send.
+    let length = headers
+        .iter()
+        .find(|(name, _)| name.eq_ignore_ascii_case("content-length"))
+        .and_then(|(_, v)| v.parse::<usize>().ok())
+        .unwrap_or(0);
+    let mut read = buf.len() - header_end;
+    while read < length {
+        let mut chunk = [0u8; 4096];
+        match stream.read(&mut chunk).await {
+            Ok(0) | Err(_) => break,
+            Ok(n) => read += n,
+        }
+    }
+    Some(Request { method, target, headers })
+}
+
+async fn send(stream: &mut tokio::net::TcpStream, reply: &Reply) -> std::io::Result<()> {
+    let mut head = format!("HTTP/1.1 {} Test\r\n", reply.status);
+    for (name, value) in &reply.headers {
+        head.push_str(&format!("{name}: {value}\r\n"));
+    }
+    let Some(chunks) = &reply.chunks else {
+        head.push_str(&format!("Content-Length: {}\r\nConnection: close\r\n\r\n", reply.body.len()));
+        let mut bytes = head.into_bytes();
+        bytes.extend_from_slice(&reply.body);
+        return stream.write_all(&bytes).await;
+    };
+    head.push_str("Transfer-Encoding: chunked\r\nConnection: close\r\n\r\n");
+    stream.write_all(head.as_bytes()).await?;
+    for chunk in chunks {
+        stream.write_all(format!("{:x}\r\n", chunk.len()).as_bytes()).await?;
+        stream.write_all(chunk).await?;
+        stream.write_all(b"\r\n").await?;
+        stream.flush().await?;
+        // Give the client a chance to read each chunk before the next arrives.
+        tokio::time::sleep(std::time::Duration::from_millis(5)).await;
+    }
+    stream.write_all(b"0\r\n\r\n").await
+}
+
+/// A client for `server` that doesn't retry or wait, so failures show at once.
+pub(crate) fn test_client(server: &TestServer) -> crate::client::PolarisClient {
+    crate::client::PolarisClient::new(test_config(server))
+}
+
+pub(crate) fn test_config(server: &TestServer) -> crate::client::PolarisConfig {
+    crate::client::PolarisConfig {
+        base_url: server.url.clone(),
+        api_token: "test-token".into(),
+        max_retries: 0,
+        timeout_retries: 0,
+        ..Default::default()
+    }
+}
@@ -0,0 +1,265 @@
+//! A JSON-lines log of every HTTP exchange, for attaching to support tickets.
+//!
+//! Each line records one attempt (retries get their own line): time, method, URL,
+//! status, elapsed milliseconds, request and response headers and bodies, or the
+//! transport error. Credentials are scrubbed before anything is written: the
+//! `Authorization`, `Cookie` and `Set-Cookie` headers, the `accesstoken` form field
+//! of the login request, the `jwt` it returns, and token-like query parameters.
+//!
+//! While tracing, response bodies are read in full before they are handed on, so
+//! streamed downloads are buffered in memory.
+
+use std::io::Write;
+use std::sync::{Arc, Mutex};
+
+use reqwest::header::{AUTHORIZATION, CONTENT_TYPE, COOKIE, HeaderMap, SET_COOKIE};
+use serde::Serialize;
+
+use crate::error::{PolarisError, Result};
+
+const REDACTED: &str = "[redacted]";
+
+/// Query parameters and form fields whose values are never written.
+const SECRET_PARAMS: &[&str] = &["accesstoken", "access_token", "api_token", "token", "jwt"];
+
+/// An open trace file shared by a client and its clones; see [`PolarisConfig::with_trace`].
+///
+/// [`PolarisConfig::with_trace`]: crate::client::PolarisConfig::with_trace
+#[derive(Clone)]
+pub struct TraceLog(Arc<Mutex<std::fs::File>>);
+
+impl std::fmt::Debug for TraceLog {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("TraceLog")
+    }
+}
+
+impl TraceLog {
+    /// Create (or truncate) the trace file at `path`, readable only by the owner on Unix.
+    pub fn create(path: &std::path::Path) -> Result<Self> {
+        let mut options = std::fs::OpenOptions::new();
+        options.write(true).create(true).truncate(true);
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::OpenOptionsExt;
+            options.mode(0o600);
+        }
+        let file = options
+            .open(path)
+            .map_err(|e| PolarisError::Other(format!("cannot create trace file {}: {e}", path.display())))?;
+        Ok(Self(Arc::new(Mutex::new(file))))
+    }
+
+    /// Append one record. Write errors are ignored: tracing never fails a request.
+    fn write(&self, record: &Record<'_>) {
+        let Ok(mut line) = serde_json::to_vec(record) else {
+            return;
+        };
+        line.push(b'\n');
+        if let Ok(mut file) = self.0.lock() {
+            let _ = file.write_all(&line);
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct Record<'a> {
+    time: String,
+    method: &'a str,
+    url: String,
+    status: Option<u16>,
+    elapsed_ms: u64,
+    request_headers: serde_json::Map<String, serde_json::Value>,
+    request_body: Option<String>,
+    response_headers: Option<serde_json::Map<String, serde_json::Value>>,
+    response_body: Option<String>,
+    error: Option<String>,
+}
+
+/// Send `request`, recording the exchange in `trace` when there is one.
+pub(crate) async fn send(
+    request: reqwest::RequestBuilder,
+    trace: Option<&TraceLog>,
+) -> reqwest::Result<reqwest::Response> {
+    let Some(trace) = trace else {
+        return request.send().await;
+    };
+    let (client, request) = request.build_split();
+    let request = request?;
+    let method = request.method().to_string();
+    let url = redact_url(request.url());
+    let request_headers = headers_json(request.headers());
+    let request_body = request.body().and_then(|b| b.as_bytes()).map(|bytes| {
+        let is_form = request
+            .headers()
+            .get(CONTENT_TYPE)
+            .and_then(|v| v.to_str().ok())
+            .is_some_and(|ct| ct.starts_with("application/x-www-form-urlencoded"));
+        if is_form { redact_form(bytes) } else { String::from_utf8_lossy(bytes).into_owned() }
+    });
+
+    let start = std::time::Instant::now();
+    let record = |status, response_headers, response_body, error| Record {
+        time: chrono::Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Millis, true),
+        method: &method,
+        url: url.clone(),
+        status,
+        elapsed_ms: start.elapsed().as_millis() as u64,
+        request_headers: request_headers.clone(),
+        request_body: request_body.clone(),
+        response_headers,
+        response_body,
+        error,
+    };
+
+    let resp = match client.execute(request).await {
+        Ok(resp) => resp,
+        Err(e) => {
+            trace.write(&record(None, None, None, Some(e.to_string())));
+            return Err(e);
+        }
+    };
+    let status = resp.status();
+    let version = resp.version();
+    let headers = resp.headers().clone();
+    let body = match resp.bytes().await {
+        Ok(body) => body,
+        Err(e) => {
+            trace.write(&record(Some(status.as_u16()), Some(headers_json(&headers)), None, Some(e.to_string())));
+            return Err(e);
+        }
+    };
+    trace.write(&record(
+        Some(status.as_u16()),
+        Some(headers_json(&headers)),
+        Some(redact_body(&body)),
+        None,
+    ));
+
+    // Hand the caller an equivalent response over the buffered body.
+    let mut rebuilt = http::Response::new(body);
+    *rebuilt.status_mut() = status;
+    *rebuilt.version_mut() = version;
+    *rebuilt.headers_mut() = headers;
+    Ok(reqwest::Response::from(rebuilt))
+}
+
+fn headers_json(headers: &HeaderMap) -> serde_json::Map<String, serde_json::Value> {
+    headers
+        .iter()
+        .map(|(name, value)| {
+            let value = if [AUTHORIZATION, COOKIE, SET_COOKIE].contains(name) {
+                REDACTED.to_string()
+            } else {
+                String::from_utf8_lossy(value.as_bytes()).into_owned()
+            };
+            (name.to_string(), value.into())
+        })
+        .collect()
+}
+
+fn is_secret(name: &str) -> bool {
+    SECRET_PARAMS.iter().any(|p| p.eq_ignore_ascii_case(name))
+}
+
+fn redact_url(url: &url::Url) -> String {
+    if !url.query_pairs().any(|(k, _)| is_secret(&k)) {
+        return url.to_string();
+    }
+    let pairs: Vec<(String, String)> = url
+        .query_pairs()
+        .map(|(k, v)| {
+            let v = if is_secret(&k) { REDACTED.into() } else { v.into_owned() };
+            (k.into_owned(), v)
+        })
+        .collect();
+    let mut url = url.clone();
+    url.query_pairs_mut().clear().extend_pairs(pairs);
+    url.to_string()
+}
+
+fn redact_form(body: &[u8]) -> String {
+    url::form_urlencoded::parse(body)
+        .map(|(k, v)| {
+            let v = if is_secret(&k) { REDACTED.into() } else { urlencoding::encode(&v) };
+            format!("{}={v}", urlencoding::encode(&k))
+        })
+        .collect::<Vec<_>>()
+        .join("&")
+}
+
+/// The body as text, with top-level secret fields of a JSON object (the login
+/// response's `jwt`) replaced.
+fn redact_body(body: &[u8]) -> String {
+    if let Ok(serde_json::Value::Object(mut object)) = serde_json::from_slice::<serde_json::Value>(body)
+        && object.keys().any(|k| is_secret(k))
+    {
+        for (key, value) in object.iter_mut() {
+            if is_secret(key) {
+                *value = REDACTED.into();
+            }
+        }
+        return serde_json::Value::Object(object).to_string();
+    }
+    String::from_utf8_lossy(body).into_owned()
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used, clippy::expect_used)]
+mod tests {
+    use super::*;
+    use crate::test_server::{Reply, TEST_JWT, TestServer};
+
+    #[test]
+    fn credential_headers_are_redacted() {
+        let mut headers = HeaderMap::new();
+        headers.insert(AUTHORIZATION, format!("Bearer {TEST_JWT}").parse().unwrap());
+        headers.insert(COOKIE, "session=s3cret".parse().unwrap());
+        headers.insert(CONTENT_TYPE, "application/json".parse().unwrap());
+        let json = headers_json(&headers);
+        assert_eq!(json["authorization"], REDACTED);
+        assert_eq!(json["cookie"], REDACTED);
+        assert_eq!(json["content-type"], "application/json");
+    }
+
+    #[test]
+    fn login_form_token_is_redacted() {
+        assert_eq!(redact_form(b"accesstoken=abc%2Fdef&mode=x"), "accesstoken=[redacted]&mode=x");
+    }
+
+    #[test]
+    fn login_response_jwt_is_redacted() {
+        let body = format!(r#"{{"jwt":"{TEST_JWT}","expires":60}}"#);
+        let redacted: serde_json::Value = serde_json::from_str(&redact_body(body.as_bytes())).unwrap();
+        assert_eq!(redacted, serde_json::json!({ "jwt": REDACTED, "expires": 60 }));
+        assert_eq!(redact_body(b"not json"), "not json");
+    }
+
+    #[test]
+    fn token_query_parameters_are_redacted() {
+        let url = url::Url::parse("https://polaris.example.com/api?token=abc&page[limit]=5").unwrap();
+        let redacted = redact_url(&url);
+        assert!(!redacted.contains("abc"), "{redacted}");
+        assert!(redacted.contains("token=%5Bredacted%5D"), "{redacted}");
+    }
+
+    #[tokio::test]
+    async fn trace_file_holds_no_credentials() {
+        let server = TestServer::start(|_| Reply::json(200, r#"{"data":[]}"#)).await;
+        let path = std::env::temp_dir().join(format!("polaris-trace-{}.jsonl", std::process::id()));
+        let config = crate::test_server::test_config(&server).with_trace(TraceLog::create(&path).unwrap());
+        let client = crate::client::PolarisClient::new(config);
+        client.list_projects(None, None, 10, 0).await.unwrap();
+
+        let text = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert!(!text.contains("test-token"), "{text}");
+        assert!(!text.contains(TEST_JWT), "{text}");
+
+        let records: Vec<serde_json::Value> = text.lines().map(|l| serde_json::from_str(l).unwrap()).collect();
+        assert_eq!(records.len(), 2, "{text}");
+        assert_eq!(records[0]["request_body"], "accesstoken=[redacted]");
+        assert_eq!(records[0]["response_body"], format!(r#"{{"jwt":"{REDACTED}"}}"#));
+        assert_eq!(records[1]["status"], 200);
+    }
+}
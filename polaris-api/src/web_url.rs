@@ -0,0 +1,198 @@
+//! Links into the Polaris web UI, matching the ones the CLI prints.
+//!
+//! Like API URLs, these keep any path prefix on the base URL
+//! (`https://host/polaris/projects/...`).
+
+use url::Url;
+
+use crate::error::{PolarisError, Result};
+
+/// `<base>/projects/<project_id>`
+pub fn project_url(base_url: &str, project_id: &str) -> Result<Url> {
+    with_segments(base_url, &["projects", project_id])
+}
+
+/// `<base>/projects/<project_id>/branches/<branch_id>`
+pub fn branch_url(base_url: &str, project_id: &str, branch_id: &str) -> Result<Url> {
+    with_segments(base_url, &["projects", project_id, "branches", branch_id])
+}
+
+/// `<base>/projects/<project_id>/branches/<branch_id>[/revisions/<revision_id>]/issues/<issue_id>`.
+///
+/// The revision pins the link to the run that produced the issue; `path` (the issue's
+/// file path) lets the UI open the file tree at the right place. Both are optional.
+pub fn issue_url(
+    base_url: &str,
+    project_id: &str,
+    branch_id: &str,
+    revision_id: Option<&str>,
+    issue_id: &str,
+    path: Option<&str>,
+) -> Result<Url> {
+    let mut segments = vec!["projects", project_id, "branches", branch_id];
+    if let Some(revision_id) = revision_id {
+        segments.extend(["revisions", revision_id]);
+    }
+    segments.extend(["issues", issue_id]);
+    let mut url = with_segments(base_url, &segments)?;
+
+    // Built by hand rather than with `query_pairs_mut`, which would encode spaces as `+`.
+    let mut query = "pagingOffset=0".to_string();
+    if let Some(path) = path {
+        query.push_str(&format!("&path={}", urlencoding::encode(path)));
+    }
+    url.set_query(Some(&query));
+    Ok(url)
+}
+
+/// The IDs in a web UI issue link; see [`parse_issue_url`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IssueLink {
+    pub project_id: String,
+    pub branch_id: String,
+    pub revision_id: Option<String>,
+    pub issue_id: String,
+}
+
+/// Parse an issue link copied from the web UI, the inverse of [`issue_url`]. The link
+/// must be http(s) and point at the same host (and path prefix) as `base_url`; its
+/// query is ignored.
+pub fn parse_issue_url(base_url: &str, link: &str) -> Result<IssueLink> {
+    let base = with_segments(base_url, &[])?;
+    let url = Url::parse(link.trim()).map_err(|e| PolarisError::Other(format!("invalid issue URL `{link}`: {e}")))?;
+    if !matches!(url.scheme(), "http" | "https") {
+        return Err(PolarisError::Other(format!("issue URL `{link}` is not an http(s) link")));
+    }
+    if url.host_str() != base.host_str() || url.port_or_known_default() != base.port_or_known_default() {
+        return Err(PolarisError::Other(format!(
+            "issue URL is for {}, but the base URL is {}; pass --base-url for that instance",
+            url.host_str().unwrap_or("no host"),
+            base.host_str().unwrap_or("no host"),
+        )));
+    }
+
+    let segments = |u: &Url| -> Vec<String> {
+        u.path_segments()
+            .into_iter()
+            .flatten()
+            .filter(|s| !s.is_empty())
+            .map(|s| urlencoding::decode(s).map_or_else(|_| s.to_string(), |d| d.into_owned()))
+            .collect()
+    };
+    let prefix = segments(&base);
+    let path = segments(&url);
+    let not_issue = || {
+        PolarisError::Other(format!(
+            "not a Polaris issue URL: `{link}` (expected .../projects/<id>/branches/<id>/issues/<id>)"
+        ))
+    };
+    let rest = path.strip_prefix(prefix.as_slice()).ok_or_else(not_issue)?;
+    let ids: Vec<&str> = rest.iter().map(String::as_str).collect();
+    match ids.as_slice() {
+        ["projects", project, "branches", branch, "issues", issue] => Ok(IssueLink {
+            project_id: project.to_string(),
+            branch_id: branch.to_string(),
+            revision_id: None,
+            issue_id: issue.to_string(),
+        }),
+        ["projects", project, "branches", branch, "revisions", revision, "issues", issue] => Ok(IssueLink {
+            project_id: project.to_string(),
+            branch_id: branch.to_string(),
+            revision_id: Some(revision.to_string()),
+            issue_id: issue.to_string(),
+        }),
+        _ => Err(not_issue()),
+    }
+}
+
+/// The base URL with `segments` appended to its path, each percent-encoded.
+fn with_segments(base_url: &str, segments: &[&str]) -> Result<Url> {
+    let mut url = crate::http::api_url(base_url, "")?;
+    url.set_query(None);
+    url.set_fragment(None);
+    if let Ok(mut path) = url.path_segments_mut() {
+        path.pop_if_empty().extend(segments);
+    }
+    Ok(url)
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used, clippy::expect_used)]
+mod tests {
+    use super::*;
+
+    const BASE: &str = "https://polaris.example.com/polaris/";
+
+    #[test]
+    fn links_keep_the_base_path_prefix() {
+        assert_eq!(project_url(BASE, "p1").unwrap().as_str(), "https://polaris.example.com/polaris/projects/p1");
+        assert_eq!(
+            branch_url("https://polaris.example.com", "p1", "b1").unwrap().as_str(),
+            "https://polaris.example.com/projects/p1/branches/b1"
+        );
+    }
+
+    #[test]
+    fn issue_url_encodes_ids_and_path() {
+        let url = issue_url(BASE, "p 1", "b/1", Some("r1"), "i1", Some("src/my file.c")).unwrap();
+        assert_eq!(
+            url.as_str(),
+            "https://polaris.example.com/polaris/projects/p%201/branches/b%2F1/revisions/r1/issues/i1?pagingOffset=0&path=src%2Fmy%20file.c"
+        );
+        let url = issue_url(BASE, "p1", "b1", None, "i1", None).unwrap();
+        assert_eq!(url.as_str(), "https://polaris.example.com/polaris/projects/p1/branches/b1/issues/i1?pagingOffset=0");
+    }
+
+    #[test]
+    fn parse_issue_url_inverts_issue_url() {
+        for revision_id in [None, Some("r1")] {
+            let link = IssueLink {
+                project_id: "p 1".into(),
+                branch_id: "b/1".into(),
+                revision_id: revision_id.map(String::from),
+                issue_id: "i1".into(),
+            };
+            let url = issue_url(BASE, &link.project_id, &link.branch_id, revision_id, &link.issue_id, Some("a.c")).unwrap();
+            assert_eq!(parse_issue_url(BASE, url.as_str()).unwrap(), link);
+        }
+    }
+
+    #[test]
+    fn parse_issue_url_ignores_the_query_and_surrounding_whitespace() {
+        let link = parse_issue_url(BASE, " https://polaris.example.com/polaris/projects/p1/branches/b1/issues/i1?x=1#top\n").unwrap();
+        assert_eq!((link.project_id.as_str(), link.issue_id.as_str()), ("p1", "i1"));
+    }
+
+    #[test]
+    fn parse_issue_url_rejects_other_origins() {
+        for link in [
+            "https://other.example.com/polaris/projects/p1/branches/b1/issues/i1",
+            "https://polaris.example.com:8443/polaris/projects/p1/branches/b1/issues/i1",
+        ] {
+            let err = parse_issue_url(BASE, link).unwrap_err();
+            assert!(err.to_string().contains("pass --base-url"), "{link}: {err}");
+        }
+    }
+
+    #[test]
+    fn parse_issue_url_rejects_other_schemes() {
+        for link in ["ftp://polaris.example.com/polaris/projects/p1/branches/b1/issues/i1", "javascript:alert(1)"] {
+            let err = parse_issue_url(BASE, link).unwrap_err();
+            assert!(err.to_string().contains("not an http(s) link"), "{link}: {err}");
+        }
+    }
+
+    #[test]
+    fn parse_issue_url_rejects_links_without_every_id() {
+        for link in [
+            "https://polaris.example.com/polaris/projects/p1/branches/b1/issues/",
+            "https://polaris.example.com/polaris/projects/p1/branches/b1",
+            "https://polaris.example.com/polaris/projects/p1/issues/i1",
+            "https://polaris.example.com/projects/p1/branches/b1/issues/i1",
+        ] {
+            let err = parse_issue_url(BASE, link).unwrap_err();
+            assert!(err.to_string().starts_with("not a Polaris issue URL"), "{link}: {err}");
+        }
+        assert!(parse_issue_url(BASE, "not a url").unwrap_err().to_string().starts_with("invalid issue URL"));
+    }
+}
@@ -0,0 +1,192 @@
+//! `polaris doctor`: a pass/warn/fail report over the usual setup problems.
+
+use anyhow::Result;
+use chrono::Utc;
+use polaris_api::auth::JwtClaims;
+use serde::Serialize;
+
+use crate::{
+    BASE_URL_PLACEHOLDER, Cli, KEYRING_UNAVAILABLE_HINT, OutputFormat, TokenSources, emit, humanize_duration,
+    make_client, pad, resolve_token, tls_config, with_auth_hint,
+};
+
+/// Sessions closer than this to expiry are flagged, since long commands may outlive them.
+const SESSION_WARN_MINUTES: i64 = 10;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+enum Status {
+    Pass,
+    Warn,
+    Fail,
+    /// Not run because an earlier check it depends on failed.
+    Skip,
+}
+
+#[derive(Serialize)]
+struct Check {
+    check: &'static str,
+    status: Status,
+    message: String,
+}
+
+fn check(check: &'static str, status: Status, message: impl Into<String>) -> Check {
+    Check {
+        check,
+        status,
+        message: message.into(),
+    }
+}
+
+/// Run every check, print the report, and fail if any check failed.
+pub async fn run(cli: &Cli, fmt: &OutputFormat) -> Result<()> {
+    let mut checks = Vec::new();
+
+    let sources = TokenSources::detect(cli);
+    let token_ok = match (sources.active(), resolve_token(cli)) {
+        ("none", _) => {
+            checks.push(check(
+                "token",
+                Status::Fail,
+                "no API token found; run `polaris auth login` or set POLARIS_API_TOKEN",
+            ));
+            false
+        }
+        (source, Ok(_)) => {
+            checks.push(check("token", Status::Pass, format!("found via {source}")));
+            true
+        }
+        (source, Err(e)) => {
+            checks.push(check("token", Status::Fail, format!("token from {source} is unusable: {e:#}")));
+            false
+        }
+    };
+
+    checks.push(match &sources.keychain {
+        Ok(()) => check("keychain", Status::Pass, "OS keychain accessible"),
+        Err(e) => check(
+            "keychain",
+            Status::Warn,
+            format!("OS keychain unavailable ({e}); {KEYRING_UNAVAILABLE_HINT}"),
+        ),
+    });
+
+    let base_ok = if cli.base_url == BASE_URL_PLACEHOLDER {
+        checks.push(check(
+            "base url",
+            Status::Fail,
+            "no base URL configured; pass --base-url, set POLARIS_BASE_URL, or add base_url to config.toml",
+        ));
+        false
+    } else {
+        match polaris_api::client::probe_apis(&cli.base_url, &tls_config(cli)?).await {
+            Ok(probes) => {
+                let up = probes.iter().filter(|p| p.responded()).count();
+                let slowest = probes.iter().map(|p| p.latency_ms).max().unwrap_or(0);
+                if up == probes.len() {
+                    checks.push(check(
+                        "base url",
+                        Status::Pass,
+                        format!("{} reachable ({up} APIs, slowest {slowest} ms)", cli.base_url),
+                    ));
+                    true
+                } else if up > 0 {
+                    let down: Vec<&str> = probes.iter().filter(|p| !p.responded()).map(|p| p.api).collect();
+                    checks.push(check(
+                        "base url",
+                        Status::Warn,
+                        format!("{} reachable, but {} not responding; see `polaris version --check`", cli.base_url, down.join(", ")),
+                    ));
+                    true
+                } else {
+                    let cause = probes.iter().find_map(|p| p.error.as_deref()).unwrap_or("no API responded");
+                    checks.push(check(
+                        "base url",
+                        Status::Fail,
+                        format!(
+                            "{} unreachable ({cause}); check the URL, HTTPS_PROXY/NO_PROXY, or --ca-cert for a private CA",
+                            cli.base_url
+                        ),
+                    ));
+                    false
+                }
+            }
+            Err(e) => {
+                checks.push(check("base url", Status::Fail, format!("{e}")));
+                false
+            }
+        }
+    };
+
+    if token_ok && base_ok {
+        let client = make_client(cli)?;
+        match client.authenticate().await {
+            Ok(jwt) => {
+                checks.push(check("auth", Status::Pass, "token accepted"));
+                checks.push(session_check(&jwt));
+                checks.push(match client.list_projects(None, None, 1, 0).await {
+                    Ok(resp) if resp.data.is_empty() => check(
+                        "projects",
+                        Status::Warn,
+                        "no projects visible; check the token's permissions",
+                    ),
+                    Ok(resp) => {
+                        let total = resp.meta.and_then(|m| m.total).map_or_else(|| "some".to_string(), |t| t.to_string());
+                        check("projects", Status::Pass, format!("{total} projects visible"))
+                    }
+                    Err(e) => check("projects", Status::Fail, format!("listing projects failed: {e}")),
+                });
+            }
+            Err(e) => {
+                let err = with_auth_hint(e, "authentication failed");
+                checks.push(check("auth", Status::Fail, format!("{err:#}")));
+                for name in ["session", "projects"] {
+                    checks.push(check(name, Status::Skip, "needs a working login"));
+                }
+            }
+        }
+    } else {
+        for name in ["auth", "session", "projects"] {
+            checks.push(check(name, Status::Skip, "needs a token and a reachable base URL"));
+        }
+    }
+
+    match fmt {
+        OutputFormat::Pretty => {
+            for c in &checks {
+                let tag = match c.status {
+                    Status::Pass => "[pass]",
+                    Status::Warn => "[warn]",
+                    Status::Fail => "[FAIL]",
+                    Status::Skip => "[skip]",
+                };
+                outln!("{tag} {} {}", pad(c.check, 10), c.message);
+            }
+        }
+        _ => emit(&serde_json::to_value(&checks)?, fmt)?,
+    }
+
+    let failed = checks.iter().filter(|c| c.status == Status::Fail).count();
+    if failed > 0 {
+        anyhow::bail!("{failed} check(s) failed");
+    }
+    Ok(())
+}
+
+fn session_check(jwt: &str) -> Check {
+    let Some(exp) = JwtClaims::decode(jwt).ok().and_then(|c| c.expires_at()) else {
+        return check("session", Status::Warn, "JWT carries no expiry");
+    };
+    let left = exp - Utc::now();
+    if left <= chrono::Duration::zero() {
+        check("session", Status::Fail, "JWT already expired; check the system clock")
+    } else if left < chrono::Duration::minutes(SESSION_WARN_MINUTES) {
+        check(
+            "session",
+            Status::Warn,
+            format!("JWT expires in {}; long-running commands may need to re-authenticate", humanize_duration(left)),
+        )
+    } else {
+        check("session", Status::Pass, format!("JWT expires in {}", humanize_duration(left)))
+    }
+}
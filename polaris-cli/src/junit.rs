@@ -0,0 +1,90 @@
+use anyhow::Result;
+use clap::ValueEnum;
+use quick_xml::Writer;
+use quick_xml::events::{BytesDecl, BytesEnd, BytesStart, BytesText, Event};
+
+/// Severity levels, lowest first so they order naturally.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, ValueEnum)]
+pub enum Severity {
+    Info,
+    Low,
+    Medium,
+    High,
+    Critical,
+}
+
+impl Severity {
+    /// Map a severity taxon name to a level; unknown names rank lowest.
+    pub fn from_name(name: &str) -> Self {
+        match name.to_ascii_lowercase().as_str() {
+            "critical" => Severity::Critical,
+            "high" => Severity::High,
+            "medium" => Severity::Medium,
+            "low" => Severity::Low,
+            _ => Severity::Info,
+        }
+    }
+}
+
+/// A finding rendered as a JUnit test case.
+pub struct TestCase<'a> {
+    /// Set when listing several branches; prefixed to the test name.
+    pub branch: Option<&'a str>,
+    pub issue_key: &'a str,
+    pub finding_key: &'a str,
+    pub checker: &'a str,
+    pub severity: &'a str,
+    pub issue_type: &'a str,
+    pub path: String,
+}
+
+/// Render findings as a `<testsuite name="polaris">`. Findings at or above
+/// `fail_at` become failing test cases; the rest pass.
+pub fn render(cases: &[TestCase], fail_at: Severity) -> Result<String> {
+    let failing = |c: &TestCase| Severity::from_name(c.severity) >= fail_at;
+    let failures = cases.iter().filter(|c| failing(c)).count();
+
+    let mut writer = Writer::new_with_indent(Vec::new(), b' ', 2);
+    writer.write_event(Event::Decl(BytesDecl::new("1.0", Some("UTF-8"), None)))?;
+
+    let mut suite = BytesStart::new("testsuite");
+    suite.push_attribute(("name", "polaris"));
+    suite.push_attribute(("tests", cases.len().to_string().as_str()));
+    suite.push_attribute(("failures", failures.to_string().as_str()));
+    suite.push_attribute(("errors", "0"));
+    writer.write_event(Event::Start(suite))?;
+
+    for case in cases {
+        let mut testcase = BytesStart::new("testcase");
+        testcase.push_attribute(("classname", case.checker));
+        let name = match case.branch {
+            Some(branch) => format!("{branch}: {} [{}]", case.path, case.issue_key),
+            None => format!("{} [{}]", case.path, case.issue_key),
+        };
+        testcase.push_attribute(("name", name.as_str()));
+
+        if !failing(case) {
+            writer.write_event(Event::Empty(testcase))?;
+            continue;
+        }
+
+        writer.write_event(Event::Start(testcase))?;
+        let mut failure = BytesStart::new("failure");
+        failure.push_attribute((
+            "message",
+            format!("{} {} in {}", case.severity, case.checker, case.path).as_str(),
+        ));
+        failure.push_attribute(("type", case.severity));
+        writer.write_event(Event::Start(failure))?;
+        let body = format!(
+            "Severity: {}\nChecker: {}\nType: {}\nPath: {}\nIssue key: {}\nFinding key: {}",
+            case.severity, case.checker, case.issue_type, case.path, case.issue_key, case.finding_key,
+        );
+        writer.write_event(Event::Text(BytesText::new(&body)))?;
+        writer.write_event(Event::End(BytesEnd::new("failure")))?;
+        writer.write_event(Event::End(BytesEnd::new("testcase")))?;
+    }
+
+    writer.write_event(Event::End(BytesEnd::new("testsuite")))?;
+    Ok(String::from_utf8(writer.into_inner())?)
+}
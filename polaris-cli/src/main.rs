@@ -1,21 +1,59 @@
 #![warn(clippy::unwrap_used, clippy::expect_used)]
 
+mod junit;
+#[macro_use]
+mod pager;
+mod doctor;
+#[cfg(feature = "tui")]
+mod tui;
+mod views;
+
 use anyhow::{Context, Result};
-use clap::{Parser, Subcommand, ValueEnum};
-use polaris_api::client::{PolarisClient, PolarisConfig, TriageValues};
+use chrono::{DateTime, NaiveDate, NaiveDateTime, NaiveTime, Utc};
+use clap::{CommandFactory, FromArgMatches, Parser, Subcommand, ValueEnum};
+use std::io::IsTerminal;
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
+use pager::PagerMode;
+use polaris_api::auth::{JwtClaims, normalize_api_token};
+use polaris_api::client::{
+    DISMISSED_TRIAGE_STATUSES, Issue, IssueFilter, IssuesMeta, IssuesResponse, KNOWN_ISSUE_INCLUDES, OPEN_TRIAGE_STATUSES, PolarisClient,
+    PolarisConfig, RateLimitConfig, TRIAGE_BATCH_SIZE, TlsConfig, TriageCurrent, TriageOutcome, TriageOutcomeStatus,
+    TriageValues,
+};
+use polaris_api::error::PolarisError;
+use polaris_api::trace::TraceLog;
+use polaris_api::web_url;
+use views::{BranchView, IssueView, ProjectView};
 
 const KEYRING_SERVICE: &str = "polaris-cli";
 const KEYRING_USER: &str = "api-token";
 const BASE_URL_PLACEHOLDER: &str = "https://your-instance.polaris.blackduck.com";
+/// Default page size for list endpoints.
+const PAGE_SIZE: u32 = 25;
 
 #[derive(Default, serde::Deserialize)]
 struct Config {
     base_url: Option<String>,
+    #[serde(default)]
+    issues: IssuesConfig,
+}
+
+#[derive(Default, serde::Deserialize)]
+struct IssuesConfig {
+    /// Replaces the built-in `include[issue][]` list.
+    default_includes: Option<Vec<String>>,
+    /// Makes `issues` default to `--status open`.
+    #[serde(default)]
+    hide_dismissed: bool,
+}
+
+fn config_dir() -> Option<std::path::PathBuf> {
+    dirs::home_dir().map(|d| d.join(".config/polaris"))
 }
 
 fn load_config() -> Config {
-    dirs::home_dir()
-        .map(|d| d.join(".config/polaris/config.toml"))
+    config_dir()
+        .map(|d| d.join("config.toml"))
         .and_then(|p| std::fs::read_to_string(p).ok())
         .and_then(|s| toml::from_str(&s).ok())
         .unwrap_or_default()
@@ -25,35 +63,188 @@ fn load_config() -> Config {
 enum OutputFormat {
     /// Pretty terminal output (default)
     Pretty,
-    /// JSON output
+    /// Compact single-line JSON output
     Json,
     /// TOON format (token-efficient)
     Toon,
+    /// JUnit XML test report (issues command only)
+    Junit,
+}
+
+#[derive(Debug, Clone, ValueEnum)]
+enum ProjectSort {
+    /// Alphabetical by name
+    Name,
+    /// Most recently active first
+    Updated,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum IssueStatus {
+    /// All issues regardless of triage state
+    All,
+    /// Issues that are not dismissed
+    Open,
+    /// Dismissed issues only
+    Dismissed,
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum BranchStrategy {
+    /// The project's main branch, falling back to the most recently active one
+    Main,
+    /// The most recently active branch
+    Latest,
+    /// The first branch listed by the API
+    First,
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum PathFormat {
+    /// `path:line`
+    Plain,
+    /// `vscode://file/<abs path>:<line>`
+    Vscode,
+    /// `idea://open?file=<abs path>&line=<line>`
+    Idea,
 }
 
 #[derive(Parser)]
 #[command(name = "polaris", about = "BlackDuck Polaris CLI client")]
 struct Cli {
     /// Base URL for the Polaris instance
-    #[arg(long, env = "POLARIS_BASE_URL", default_value = "https://your-instance.polaris.blackduck.com")]
+    #[arg(long, env = "POLARIS_BASE_URL", default_value = BASE_URL_PLACEHOLDER, global = true)]
     base_url: String,
 
     /// API token for authentication
-    #[arg(long, env = "POLARIS_API_TOKEN")]
+    #[arg(long, env = "POLARIS_API_TOKEN", global = true)]
     api_token: Option<String>,
 
+    /// Read the API token from a file
+    #[arg(long, env = "POLARIS_API_TOKEN_FILE", global = true)]
+    api_token_file: Option<std::path::PathBuf>,
+
+    /// Refuse every command that changes server state (triage updates, `api post`)
+    #[arg(long, env = "POLARIS_READ_ONLY", global = true, value_parser = clap::builder::FalseyValueParser::new())]
+    read_only: bool,
+
+    /// Skip TLS certificate verification (self-signed test instances only)
+    #[arg(long, env = "POLARIS_INSECURE", global = true, value_parser = clap::builder::FalseyValueParser::new())]
+    insecure: bool,
+
+    /// Trust an additional root CA certificate (PEM) for TLS
+    #[arg(long, env = "POLARIS_CA_CERT", value_name = "PATH", global = true)]
+    ca_cert: Option<std::path::PathBuf>,
+
+    /// Trust every `.pem`/`.crt` root CA certificate in a directory for TLS
+    #[arg(long, env = "POLARIS_CA_CERT_DIR", value_name = "DIR", global = true)]
+    ca_cert_dir: Option<std::path::PathBuf>,
+
     /// Output format
-    #[arg(long, value_enum, default_value = "pretty", global = true)]
+    #[arg(long, value_enum, env = "POLARIS_FORMAT", default_value = "pretty", global = true)]
     format: OutputFormat,
 
     /// Shorthand for --format json
-    #[arg(long, global = true)]
+    #[arg(long, global = true, conflicts_with = "toon")]
     json: bool,
 
     /// Shorthand for --format toon
     #[arg(long, global = true)]
     toon: bool,
 
+    /// Page output through $PAGER (or `less -FRX`)
+    #[arg(long, value_enum, default_value = "auto", global = true)]
+    pager: PagerMode,
+
+    /// Append a raw `key=value` query parameter to every API request (repeatable).
+    /// Unsupported escape hatch for debugging or trying new API parameters; use at your own risk.
+    #[arg(long = "query", value_name = "KEY=VALUE", value_parser = parse_query_arg, global = true)]
+    query: Vec<(String, String)>,
+
+    /// Results per API page (capped at each endpoint's server maximum)
+    #[arg(long, default_value_t = PAGE_SIZE, value_parser = clap::value_parser!(u32).range(1..), global = true)]
+    page_size: u32,
+
+    /// Fetch only the first page of list results
+    #[arg(long, global = true)]
+    no_pagination: bool,
+
+    /// How to pick a branch when --branch-id is omitted
+    #[arg(long, value_enum, default_value = "main", global = true)]
+    branch_strategy: BranchStrategy,
+
+    /// Always pick the branch from a numbered list when --branch-id is omitted (terminal only)
+    #[arg(long, global = true, conflicts_with = "no_interactive")]
+    select_branch: bool,
+
+    /// Never prompt; resolve branches with --branch-strategy even on a terminal
+    #[arg(long, global = true)]
+    no_interactive: bool,
+
+    /// Reject issue keys, finding keys and issue IDs with unusual characters instead
+    /// of only warning about them
+    #[arg(long, global = true)]
+    strict_keys: bool,
+
+    /// Log request URLs to stderr
+    #[arg(short, long, global = true)]
+    verbose: bool,
+
+    /// Per-attempt request timeout in seconds
+    #[arg(long, value_name = "SECS", env = "POLARIS_TIMEOUT", default_value_t = 120, value_parser = clap::value_parser!(u64).range(1..), global = true)]
+    timeout: u64,
+
+    /// Retries for 429/5xx responses and connection failures
+    #[arg(long, value_name = "N", default_value_t = 2, global = true)]
+    max_retries: u32,
+
+    /// Retries for requests that time out (separate from --max-retries)
+    #[arg(long, value_name = "N", default_value_t = 1, global = true)]
+    timeout_retries: u32,
+
+    /// Total time the whole command may spend retrying, across all its requests (e.g. 60s, 5m)
+    #[arg(long, value_name = "DURATION", value_parser = parse_duration_arg, global = true)]
+    max_total_retry_time: Option<std::time::Duration>,
+
+    /// Response header carrying the requests left in the rate-limit window
+    #[arg(long, value_name = "NAME", default_value = "X-RateLimit-Remaining", global = true)]
+    rate_limit_remaining_header: String,
+
+    /// Response header carrying the rate-limit reset (seconds, or a Unix timestamp)
+    #[arg(long, value_name = "NAME", default_value = "X-RateLimit-Reset", global = true)]
+    rate_limit_reset_header: String,
+
+    /// Show timestamps in UTC instead of local time
+    #[arg(long, global = true)]
+    utc: bool,
+
+    /// Show timestamps as the raw ISO-8601 strings from the API
+    #[arg(long, global = true)]
+    raw_dates: bool,
+
+    /// Print the unmodified API response bodies instead of formatted output
+    #[arg(long, global = true)]
+    raw: bool,
+
+    /// Write every HTTP exchange (URLs, statuses, timings, bodies) to PATH as JSON
+    /// lines, with credentials redacted, e.g. to attach to a support ticket
+    #[arg(long, value_name = "PATH", global = true)]
+    trace_file: Option<std::path::PathBuf>,
+
+    /// Report layout for projects, branches and issues: a TOML or JSON file of
+    /// `[[columns]]` with `header` and `field`
+    #[arg(long, value_name = "PATH", global = true)]
+    fields_file: Option<std::path::PathBuf>,
+
+    /// Exit with status 4 when projects, branches, issues or runs returns nothing
+    #[arg(long, global = true)]
+    require_results: bool,
+
+    /// Always fetch project and branch listings in full instead of revalidating the
+    /// on-disk cache
+    #[arg(long, global = true)]
+    no_cache: bool,
+
     #[command(subcommand)]
     command: Commands,
 }
@@ -68,26 +259,123 @@ impl Cli {
             self.format.clone()
         }
     }
+
+    fn branch_options(&self) -> BranchOptions {
+        BranchOptions {
+            strategy: self.branch_strategy,
+            interactive: !self.no_interactive && std::io::stdin().is_terminal() && std::io::stderr().is_terminal(),
+            always_pick: self.select_branch,
+        }
+    }
+
+    fn date_style(&self) -> DateStyle {
+        DateStyle {
+            raw: self.raw_dates,
+            utc: self.utc,
+        }
+    }
+}
+
+/// How timestamps are rendered in pretty output. JSON and TOON always keep the raw strings.
+#[derive(Clone, Copy)]
+struct DateStyle {
+    raw: bool,
+    utc: bool,
+}
+
+impl DateStyle {
+    /// Render an API timestamp as `2025-03-04 14:03 (3 days ago)`. Values that don't
+    /// parse, and everything under `--raw-dates`, are returned unchanged.
+    fn show(self, ts: &str) -> String {
+        if self.raw {
+            return ts.to_string();
+        }
+        let Some(dt) = parse_api_timestamp(ts) else {
+            return ts.to_string();
+        };
+        let when = if self.utc {
+            dt.format("%Y-%m-%d %H:%M UTC").to_string()
+        } else {
+            dt.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M").to_string()
+        };
+        format!("{when} ({})", relative_age(Utc::now() - dt))
+    }
+
+    /// Rewrite every timestamp string inside a JSON value for display.
+    fn humanize_json(self, val: &mut serde_json::Value) {
+        match val {
+            serde_json::Value::String(s) if parse_api_timestamp(s).is_some() => *s = self.show(s),
+            serde_json::Value::Array(items) => items.iter_mut().for_each(|v| self.humanize_json(v)),
+            serde_json::Value::Object(map) => map.values_mut().for_each(|v| self.humanize_json(v)),
+            _ => {}
+        }
+    }
+}
+
+/// Parse an RFC 3339 timestamp, or one without an offset (taken as UTC).
+fn parse_api_timestamp(ts: &str) -> Option<DateTime<Utc>> {
+    if let Ok(dt) = DateTime::parse_from_rfc3339(ts) {
+        return Some(dt.with_timezone(&Utc));
+    }
+    NaiveDateTime::parse_from_str(ts, "%Y-%m-%dT%H:%M:%S%.f")
+        .ok()
+        .map(|dt| dt.and_utc())
+}
+
+/// `3 days ago`, `1 hour ago`, `just now`; future times read `in 2 hours`.
+fn relative_age(d: chrono::Duration) -> String {
+    let (future, d) = if d < chrono::Duration::zero() { (true, -d) } else { (false, d) };
+    let (n, unit) = if d.num_days() > 0 {
+        (d.num_days(), "day")
+    } else if d.num_hours() > 0 {
+        (d.num_hours(), "hour")
+    } else if d.num_minutes() > 0 {
+        (d.num_minutes(), "minute")
+    } else {
+        return "just now".to_string();
+    };
+    let plural = if n == 1 { "" } else { "s" };
+    if future {
+        format!("in {n} {unit}{plural}")
+    } else {
+        format!("{n} {unit}{plural} ago")
+    }
 }
 
 /// Emit a serde_json::Value in the requested format.
 fn emit(val: &serde_json::Value, fmt: &OutputFormat) -> Result<()> {
     match fmt {
         OutputFormat::Pretty => {
-            println!("{}", serde_json::to_string_pretty(val)?);
+            outln!("{}", serde_json::to_string_pretty(val)?);
         }
         OutputFormat::Json => {
-            println!("{}", serde_json::to_string_pretty(val)?);
+            outln!("{}", serde_json::to_string(val)?);
         }
         OutputFormat::Toon => {
-            let toon = toon_rs::encode_to_string(val, &toon_rs::Options::default())
+            let toon = toon_rs::encode_to_string(&sorted_keys(val), &toon_rs::Options::default())
                 .map_err(|e| anyhow::anyhow!("TOON encode error: {e}"))?;
-            println!("{toon}");
+            outln!("{toon}");
         }
+        OutputFormat::Junit => anyhow::bail!("--format junit is only supported by the `issues` command"),
     }
     Ok(())
 }
 
+/// Copy of `val` with object keys sorted at every level. serde_json keeps insertion
+/// order here (toon-rs enables `preserve_order`), so API responses would otherwise be
+/// encoded in whatever order the server sent them.
+fn sorted_keys(val: &serde_json::Value) -> serde_json::Value {
+    match val {
+        serde_json::Value::Object(map) => {
+            let mut entries: Vec<_> = map.iter().collect();
+            entries.sort_by(|a, b| a.0.cmp(b.0));
+            serde_json::Value::Object(entries.into_iter().map(|(k, v)| (k.clone(), sorted_keys(v))).collect())
+        }
+        serde_json::Value::Array(items) => serde_json::Value::Array(items.iter().map(sorted_keys).collect()),
+        other => other.clone(),
+    }
+}
+
 #[derive(Subcommand)]
 enum Commands {
     /// Manage authentication
@@ -101,6 +389,22 @@ enum Commands {
         /// Filter by project name
         #[arg(long)]
         name: Option<String>,
+
+        /// Filter by owner email (the `owner` project property; server-side, re-checked locally)
+        #[arg(long)]
+        owner: Option<String>,
+
+        /// Maximum number of projects to show
+        #[arg(long)]
+        limit: Option<usize>,
+
+        /// Sort order
+        #[arg(long, value_enum)]
+        sort_by: Option<ProjectSort>,
+
+        /// Only projects active since DATE (ISO-8601, or relative: 12h, 7d, 2w)
+        #[arg(long, value_parser = parse_date_arg)]
+        since: Option<DateTime<Utc>>,
     },
 
     /// List branches for a project
@@ -108,33 +412,156 @@ enum Commands {
         /// Project ID
         #[arg(long)]
         project_id: String,
+
+        /// Show only the project's main branch
+        #[arg(long)]
+        main_only: bool,
+    },
+
+    /// Print the ID of a project's main branch (for shell substitution)
+    MainBranch {
+        /// Project ID
+        #[arg(long)]
+        project_id: String,
     },
 
     /// List issues for a project
+    #[command(args_conflicts_with_subcommands = true, subcommand_negates_reqs = true)]
     Issues {
+        #[command(subcommand)]
+        action: Option<IssuesAction>,
+
+        /// Project ID
+        #[arg(long, required = true)]
+        project_id: Option<String>,
+
+        /// Branch ID
+        #[arg(long)]
+        branch_id: Option<String>,
+
+        /// Maximum number of issues to fetch
+        #[arg(long)]
+        limit: Option<usize>,
+
+        /// Filter by triage state [default: all, or open when config sets `hide_dismissed`]
+        #[arg(long, value_enum)]
+        status: Option<IssueStatus>,
+
+        /// Show dismissed issues even when config sets `hide_dismissed` (same as --status all)
+        #[arg(long, conflicts_with_all = ["status", "hide_dismissed"])]
+        include_dismissed: bool,
+
+        /// Hide dismissed issues (same as --status open)
+        #[arg(long, conflicts_with = "status")]
+        hide_dismissed: bool,
+
+        /// Case-insensitive text match on issue key, finding key or checker (client-side)
+        #[arg(long)]
+        search: Option<String>,
+
+        /// Only issues first detected on or after this date (YYYY-MM-DD, RFC 3339, or 30d/2w)
+        #[arg(long, value_parser = parse_date_arg)]
+        since: Option<DateTime<Utc>>,
+
+        /// Only issues first detected on or before this date (YYYY-MM-DD, RFC 3339, or 30d/2w)
+        #[arg(long, value_parser = parse_date_arg)]
+        until: Option<DateTime<Utc>>,
+
+        /// Continue an earlier listing from the cursor it printed (servers with cursor pagination)
+        #[arg(long, value_name = "CURSOR")]
+        after: Option<String>,
+
+        /// List issues from every branch of the project, labelled with their branch
+        #[arg(long, conflicts_with_all = ["branch_id", "after"])]
+        all_branches: bool,
+
+        /// Branches to query at once with --all-branches
+        #[arg(long, default_value_t = 4, value_parser = clap::value_parser!(u32).range(1..))]
+        concurrency: u32,
+
+        /// With --format junit, findings at or above this severity are reported as failures
+        /// (in the report only; the exit status is unaffected)
+        #[arg(long, value_enum, default_value = "medium")]
+        fail_severity: junit::Severity,
+
+        /// One row per finding key, with its issue count and highest severity
+        #[arg(long)]
+        group_by_finding: bool,
+    },
+
+    /// List analysis runs for a project
+    #[command(args_conflicts_with_subcommands = true, subcommand_negates_reqs = true)]
+    Runs {
+        #[command(subcommand)]
+        action: Option<RunsAction>,
+
+        /// Project ID
+        #[arg(long, required = true)]
+        project_id: Option<String>,
+
+        /// Only list runs for this revision
+        #[arg(long)]
+        revision_id: Option<String>,
+    },
+
+    /// Print the source code of a file from an analysis run
+    Source {
+        /// Run ID
+        #[arg(long)]
+        run_id: String,
+
+        /// File path as reported in the issue's location
+        #[arg(long)]
+        path: String,
+
+        /// Stream the file to this path instead of printing it (no size limit)
+        #[arg(long, short, value_name = "FILE")]
+        output: Option<std::path::PathBuf>,
+    },
+
+    /// Browse and triage issues interactively
+    #[cfg(feature = "tui")]
+    Tui {
         /// Project ID
         #[arg(long)]
         project_id: String,
 
-        /// Branch ID
+        /// Branch ID (auto-resolves main branch if omitted)
         #[arg(long)]
         branch_id: Option<String>,
     },
 
-    /// Show full details for a single issue
+    /// Show full details for one or more issues
     #[command(name = "issue")]
     IssueShow {
-        /// Issue ID
-        #[arg(long)]
-        issue_id: String,
+        /// Issue ID (repeatable or comma-separated to show several)
+        #[arg(long, required_unless_present = "url", value_delimiter = ',')]
+        issue_id: Vec<String>,
 
         /// Project ID (needed to resolve main branch)
-        #[arg(long)]
-        project_id: String,
+        #[arg(long, required_unless_present = "url")]
+        project_id: Option<String>,
 
         /// Branch ID (auto-resolves main branch if omitted)
         #[arg(long)]
         branch_id: Option<String>,
+
+        /// Issue link copied from the web UI, instead of the ID flags
+        #[arg(long, conflicts_with_all = ["issue_id", "project_id", "branch_id"])]
+        url: Option<String>,
+
+        /// Render the path as an editor reference with the main event's line,
+        /// resolved against the current directory
+        #[arg(long, value_enum)]
+        path_format: Option<PathFormat>,
+
+        /// Leave source snippets out of the event summary
+        #[arg(long)]
+        no_source: bool,
+
+        /// Issues to fetch at once when several are given
+        #[arg(long, default_value_t = 4, value_parser = clap::value_parser!(u32).range(1..))]
+        concurrency: u32,
     },
 
     /// Show event tree with source code for a finding
@@ -154,6 +581,18 @@ enum Commands {
         /// Max depth of nested events
         #[arg(long)]
         max_depth: Option<u32>,
+
+        /// Print one call-path line per finding instead of the full tree
+        #[arg(long)]
+        summary: bool,
+
+        /// Print only event descriptions and file:line locations, without source snippets
+        #[arg(long, conflicts_with = "summary")]
+        no_source: bool,
+
+        /// Language for event descriptions, as an Accept-Language value (default en)
+        #[arg(long)]
+        lang: Option<String>,
     },
 
     /// Triage operations
@@ -219,17 +658,167 @@ enum Commands {
         metric: Option<String>,
     },
 
+    /// Check the token, keychain, base URL, login and project access, with fixes for failures
+    Doctor,
+
+    /// Show the CLI version, optionally checking that the Polaris instance is reachable
+    Version {
+        /// Probe the configured base URL (no token needed)
+        #[arg(long)]
+        check: bool,
+    },
+
     /// Discovery endpoints (filter-keys, group-bys)
     Discovery {
         /// Type of discovery data: filter-keys or group-bys
         #[arg(long, rename_all = "kebab-case")]
         r#type: String,
     },
+
+    /// Call any API path with authentication handled (advanced, unsupported escape hatch)
+    Api {
+        #[command(subcommand)]
+        action: ApiAction,
+    },
+
+    /// List the severity or issue-type taxa a project's issues use
+    Taxa {
+        /// Project ID
+        #[arg(long)]
+        project_id: String,
+
+        /// Branch ID (auto-resolves main branch if omitted)
+        #[arg(long)]
+        branch_id: Option<String>,
+
+        /// Which taxonomy to list
+        #[arg(long, value_enum, default_value = "severity")]
+        kind: TaxonKind,
+    },
+
+    /// Export a branch's issues joined with their current triage
+    Export {
+        /// Project ID
+        #[arg(long)]
+        project_id: String,
+
+        /// Branch ID (auto-resolves main branch if omitted)
+        #[arg(long)]
+        branch_id: Option<String>,
+
+        /// Triage batch requests to run at once
+        #[arg(long, default_value_t = 8, value_parser = clap::value_parser!(u32).range(1..))]
+        concurrency: u32,
+    },
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum TaxonKind {
+    Severity,
+    IssueType,
+}
+
+impl TaxonKind {
+    /// Issue relationship pointing at the taxon, and the included resource type.
+    fn relationship(self) -> (&'static str, &'static str) {
+        match self {
+            TaxonKind::Severity => ("/severity/data/id", "taxon"),
+            TaxonKind::IssueType => ("/issue-type/data/id", "issue-type"),
+        }
+    }
+}
+
+#[derive(Subcommand)]
+enum IssuesAction {
+    /// Show full details for several issues, resolving the branch once
+    Details {
+        /// Issue IDs (repeatable or comma-separated)
+        #[arg(long = "issue-id", required = true, value_delimiter = ',')]
+        issue_ids: Vec<String>,
+
+        /// Project ID (needed to resolve main branch)
+        #[arg(long)]
+        project_id: String,
+
+        /// Branch ID (auto-resolves main branch if omitted)
+        #[arg(long)]
+        branch_id: Option<String>,
+
+        /// Issues to fetch at once
+        #[arg(long, default_value_t = 4, value_parser = clap::value_parser!(u32).range(1..))]
+        concurrency: u32,
+    },
+
+    /// Print the number of matching issues without fetching them
+    Count {
+        /// Project ID
+        #[arg(long)]
+        project_id: String,
+
+        /// Branch ID (auto-resolves main branch if omitted)
+        #[arg(long)]
+        branch_id: Option<String>,
+
+        /// Filter by triage state
+        #[arg(long, value_enum, default_value = "all")]
+        status: IssueStatus,
+
+        /// Also break the branch's issues down by severity (from roll-up counts; ignores --status)
+        #[arg(long)]
+        by_severity: bool,
+    },
+
+    /// Write every issue's full detail to its own JSON file, named by issue key
+    Dump {
+        /// Project ID
+        #[arg(long)]
+        project_id: String,
+
+        /// Branch ID (auto-resolves main branch if omitted)
+        #[arg(long)]
+        branch_id: Option<String>,
+
+        /// Directory to write into (created if missing; existing files are overwritten)
+        #[arg(long, value_name = "DIR")]
+        output_dir: std::path::PathBuf,
+
+        /// Also fetch and store each issue's main event tree
+        #[arg(long)]
+        events: bool,
+
+        /// Detail fetches to run at once
+        #[arg(long, default_value_t = 4, value_parser = clap::value_parser!(u32).range(1..))]
+        concurrency: u32,
+    },
+}
+
+#[derive(Subcommand)]
+enum RunsAction {
+    /// Poll a run until it completes or fails (exit 0 on completion, 1 on failure,
+    /// 5 on timeout)
+    Wait {
+        /// Project ID
+        #[arg(long)]
+        project_id: String,
+
+        /// Run ID
+        #[arg(long)]
+        run_id: String,
+
+        /// Seconds to wait before giving up
+        #[arg(long, default_value_t = 600)]
+        wait_timeout: u64,
+
+        /// Seconds between status checks
+        #[arg(long, default_value_t = 30, value_parser = clap::value_parser!(u64).range(1..))]
+        interval: u64,
+    },
 }
 
 #[derive(Subcommand)]
 enum TriageAction {
     /// Get current triage status for an issue
+    #[command(group = clap::ArgGroup::new("issue").required(true).args(["issue_key", "finding_key"]))]
     Get {
         /// Project ID
         #[arg(long)]
@@ -237,7 +826,15 @@ enum TriageAction {
 
         /// Issue key
         #[arg(long)]
-        issue_key: String,
+        issue_key: Option<String>,
+
+        /// Finding key (resolved to its issue key first)
+        #[arg(long)]
+        finding_key: Option<String>,
+
+        /// Branch used to resolve --finding-key (auto-resolves main branch if omitted)
+        #[arg(long)]
+        branch_id: Option<String>,
     },
 
     /// Update triage for one or more issues
@@ -261,6 +858,12 @@ enum TriageAction {
         /// Comment text
         #[arg(long)]
         comment: Option<String>,
+
+        /// If the request fails with a timeout, dropped connection or 5xx, or the response
+        /// doesn't say which issues were triaged, re-read the current triage to find out
+        /// whether the update landed
+        #[arg(long)]
+        verify: bool,
     },
 
     /// Get triage history for an issue
@@ -273,9 +876,38 @@ enum TriageAction {
         #[arg(long)]
         issue_key: String,
 
-        /// Maximum results
+        /// Maximum results (with --follow: how many existing items to show first)
         #[arg(long, default_value = "10")]
         limit: u32,
+
+        /// Keep polling and print new history items as they appear, until Ctrl-C
+        #[arg(long)]
+        follow: bool,
+
+        /// Seconds between polls with --follow
+        #[arg(long, default_value_t = 30, value_name = "SECS", requires = "follow",
+              value_parser = clap::value_parser!(u64).range(1..))]
+        interval: u64,
+    },
+}
+
+/// Raw API calls. Paths are relative to the base URL (`api/common/v0/projects`);
+/// `--query KEY=VALUE` adds query parameters.
+#[derive(Subcommand)]
+enum ApiAction {
+    /// GET a path and print the JSON response
+    Get {
+        /// API path, e.g. api/query/v1/discovery/filter-keys
+        path: String,
+    },
+    /// POST a JSON body to a path and print the response (sent once, never retried)
+    Post {
+        /// API path, e.g. api/triage-command/v1/triage-issues
+        path: String,
+
+        /// File holding the JSON request body (`-` reads stdin)
+        #[arg(long, value_name = "FILE")]
+        body: std::path::PathBuf,
     },
 }
 
@@ -286,13 +918,19 @@ enum AuthCommands {
         /// API token (will prompt if not provided)
         #[arg(long)]
         token: Option<String>,
+
+        /// If the OS keychain is unavailable, store the token in ~/.config/polaris/token (mode 0600)
+        #[arg(long)]
+        allow_file_token_store: bool,
     },
-    /// Remove API token from OS keychain
+    /// Remove API token from OS keychain (and the file store, if used)
     Logout,
     /// Show authentication status
     Status,
     /// Authenticate and display JWT (for debugging)
     Jwt,
+    /// Delete cached project and branch listings
+    ClearCache,
 }
 
 fn keyring_entry() -> Result<keyring::Entry, keyring::Error> {
@@ -303,117 +941,655 @@ fn token_from_keyring() -> Option<String> {
     keyring_entry().ok().and_then(|e| e.get_password().ok())
 }
 
-fn resolve_token(cli: &Cli) -> Result<String> {
-    cli.api_token
-        .clone()
-        .or_else(|| std::env::var("POLARIS_API_TOKEN").ok())
-        .or_else(token_from_keyring)
-        .context("API token required: use `polaris auth login`, set POLARIS_API_TOKEN, or pass --api-token")
+/// True when the error means there is no usable keychain at all (e.g. no secret
+/// service on a headless host), as opposed to the keychain simply holding no token.
+fn keyring_unavailable(err: &keyring::Error) -> bool {
+    matches!(
+        err,
+        keyring::Error::PlatformFailure(_) | keyring::Error::NoStorageAccess(_)
+    )
 }
 
-fn make_client(cli: &Cli) -> Result<PolarisClient> {
-    let api_token = resolve_token(cli)?;
-    let config = PolarisConfig {
-        base_url: cli.base_url.clone(),
-        api_token,
-    };
+/// Whether the OS keychain can be used, with the reason when it can't.
+fn keyring_status() -> Result<(), keyring::Error> {
+    match keyring_entry().and_then(|e| e.get_password()) {
+        Err(e) if keyring_unavailable(&e) => Err(e),
+        _ => Ok(()),
+    }
+}
+
+const KEYRING_UNAVAILABLE_HINT: &str = "set POLARIS_API_TOKEN, pass --api-token-file, or run `polaris auth login --allow-file-token-store`";
+
+/// Fallback token store for hosts without a keychain, written only on explicit opt-in.
+fn token_store_path() -> Option<std::path::PathBuf> {
+    config_dir().map(|d| d.join("token"))
+}
+
+/// Cached project and branch listings, revalidated with ETags on each use.
+fn response_cache_dir() -> Option<std::path::PathBuf> {
+    dirs::cache_dir().map(|d| d.join("polaris/responses"))
+}
+
+fn read_token_file(path: &std::path::Path) -> Result<String> {
+    let token = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read token file {}", path.display()))?;
+    Ok(token.trim().to_string())
+}
+
+fn token_from_store() -> Option<String> {
+    token_store_path()
+        .and_then(|p| read_token_file(&p).ok())
+        .filter(|t| !t.is_empty())
+}
+
+fn write_token_store(path: &std::path::Path, token: &str) -> Result<()> {
+    use std::io::Write;
+
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)
+            .with_context(|| format!("Failed to create {}", dir.display()))?;
+    }
+    let mut opts = std::fs::OpenOptions::new();
+    opts.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::{OpenOptionsExt, PermissionsExt};
+        opts.mode(0o600);
+        // `mode` only applies on creation; tighten a pre-existing file too.
+        if path.exists() {
+            std::fs::set_permissions(path, std::fs::Permissions::from_mode(0o600))?;
+        }
+    }
+    let mut file = opts
+        .open(path)
+        .with_context(|| format!("Failed to open {}", path.display()))?;
+    file.write_all(token.as_bytes())?;
+    Ok(())
+}
+
+/// Which token sources are configured, as reported by `auth status` and `doctor`.
+struct TokenSources {
+    has_arg: bool,
+    has_token_file: bool,
+    has_env: bool,
+    keychain: Result<(), keyring::Error>,
+    has_keychain: bool,
+    has_store: bool,
+}
+
+impl TokenSources {
+    fn detect(cli: &Cli) -> Self {
+        let keychain = keyring_status();
+        Self {
+            has_arg: cli.api_token.is_some(),
+            has_token_file: cli.api_token_file.is_some(),
+            has_env: std::env::var("POLARIS_API_TOKEN").is_ok(),
+            has_keychain: keychain.is_ok() && token_from_keyring().is_some(),
+            keychain,
+            has_store: token_from_store().is_some(),
+        }
+    }
+
+    /// The source `resolve_token` will use, or `"none"`.
+    fn active(&self) -> &'static str {
+        if self.has_arg {
+            "--api-token flag"
+        } else if self.has_token_file {
+            "--api-token-file"
+        } else if self.has_env {
+            "POLARIS_API_TOKEN env var"
+        } else if self.has_keychain {
+            "OS keychain"
+        } else if self.has_store {
+            "token file store"
+        } else {
+            "none"
+        }
+    }
+}
+
+fn resolve_token(cli: &Cli) -> Result<String> {
+    let token = if let Some(token) = cli.api_token.clone() {
+        token
+    } else if let Some(ref path) = cli.api_token_file {
+        read_token_file(path)?
+    } else {
+        std::env::var("POLARIS_API_TOKEN")
+            .ok()
+            .or_else(token_from_keyring)
+            .or_else(token_from_store)
+            .context("API token required: use `polaris auth login`, set POLARIS_API_TOKEN, or pass --api-token / --api-token-file")?
+    };
+    Ok(normalize_api_token(&token)?)
+}
+
+/// Attach guidance to an auth failure: a rejected token and an unavailable auth
+/// service call for very different fixes.
+fn with_auth_hint(err: PolarisError, context: &str) -> anyhow::Error {
+    let hint = match &err {
+        PolarisError::AuthFailed {
+            status: Some(400 | 401 | 403),
+            ..
+        } => Some("your token appears invalid or expired; create a new one in the Polaris web UI"),
+        PolarisError::AuthFailed {
+            status: Some(500..=599),
+            ..
+        } => Some("the auth service is temporarily unavailable; try again later"),
+        _ => None,
+    };
+    match hint {
+        Some(hint) => anyhow::Error::new(err).context(format!("{context}: {hint}")),
+        None => anyhow::Error::new(err).context(context.to_string()),
+    }
+}
+
+/// Roll-up group-by for the built-in severity taxonomy. Group-bys are discovery values
+/// (`discovery --type group-bys`), not plain field names.
+const SEVERITY_GROUP_BY: &str = "[issue][taxonomy][id][011dfe05-00e5-4d8c-8746-a81fe44a120b]";
+
+/// Left-align `s` in a column `width` terminal cells wide. `format!("{:<N}")` counts
+/// chars, so wide (CJK, emoji) or combining characters would shift later columns.
+fn pad(s: &str, width: usize) -> String {
+    let fill = width.saturating_sub(UnicodeWidthStr::width(s));
+    format!("{s}{}", " ".repeat(fill))
+}
+
+/// The longest prefix of `s` at most `max` terminal cells wide, cut on a char
+/// boundary. A wide character that would straddle the limit is left out.
+fn truncate_width(s: &str, max: usize) -> &str {
+    let mut width = 0;
+    for (i, c) in s.char_indices() {
+        width += UnicodeWidthChar::width(c).unwrap_or(0);
+        if width > max {
+            return &s[..i];
+        }
+    }
+    s
+}
+
+/// Server-side triage statuses for a `--status` choice (empty means no filter).
+fn status_filter(status: IssueStatus) -> &'static [&'static str] {
+    match status {
+        IssueStatus::All => &[],
+        IssueStatus::Open => OPEN_TRIAGE_STATUSES,
+        IssueStatus::Dismissed => DISMISSED_TRIAGE_STATUSES,
+    }
+}
+
+/// `(name, count)` rows from a roll-up-counts response, in server order.
+fn roll_up_rows(resp: &serde_json::Value) -> Vec<(String, Option<u64>)> {
+    let Some(data) = resp.get("data").and_then(|v| v.as_array()) else {
+        return Vec::new();
+    };
+    data.iter()
+        .map(|item| {
+            let name = item
+                .pointer("/attributes/name")
+                .or_else(|| item.pointer("/attributes/value"))
+                .and_then(|v| v.as_str())
+                .unwrap_or("-");
+            let count = item
+                .pointer("/attributes/count")
+                .or_else(|| item.pointer("/attributes/value"))
+                .and_then(|v| v.as_u64());
+            (name.to_string(), count)
+        })
+        .collect()
+}
+
+/// What a command changes on the server, if anything. `--read-only` refuses these up
+/// front; the client refuses them again (see `PolarisConfig::read_only`), so a command
+/// missing here still can't write.
+fn mutation(command: &Commands) -> Option<&'static str> {
+    match command {
+        Commands::Triage { action: TriageAction::Update { .. } } => Some("triage update"),
+        Commands::Api { action: ApiAction::Post { .. } } => Some("api post"),
+        _ => None,
+    }
+}
+
+/// Longest key accepted; real issue and finding keys are far shorter.
+const MAX_KEY_LEN: usize = 256;
+
+/// Trim an issue key, finding key or issue ID (`what`) and check it before it is sent.
+/// Empty keys, keys with whitespace or control characters, and overlong ones are
+/// errors. Characters outside letters, digits and `-_.:` are unusual rather than
+/// invalid, so they only warn unless `strict` (`--strict-keys`) is set.
+fn validate_issue_key(what: &str, key: &str, strict: bool) -> Result<String> {
+    let key = key.trim();
+    let invalid = |why: &str| anyhow::anyhow!("`{key}` doesn't look like a valid {what}: {why}");
+    if key.is_empty() {
+        anyhow::bail!("empty {what}");
+    }
+    if key.chars().any(|c| c.is_whitespace() || c.is_control()) {
+        return Err(invalid("it contains whitespace (is it more than one key? separate them with commas)"));
+    }
+    if key.chars().count() > MAX_KEY_LEN {
+        return Err(invalid(&format!("it is longer than {MAX_KEY_LEN} characters")));
+    }
+    if let Some(c) = key.chars().find(|c| !(c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.' | ':'))) {
+        let why = format!("unexpected character `{c}`");
+        if strict {
+            return Err(invalid(&why));
+        }
+        eprintln!("warning: `{key}` doesn't look like a valid {what} ({why}); sending it anyway");
+    }
+    Ok(key.to_string())
+}
+
+/// Run [`validate_issue_key`] over every key argument of the command, in place, so
+/// malformed keys fail before any request is made.
+fn validate_command_keys(command: &mut Commands, strict: bool) -> Result<()> {
+    let check = |what: &str, key: &mut String| -> Result<()> {
+        *key = validate_issue_key(what, key, strict)?;
+        Ok(())
+    };
+    match command {
+        Commands::IssueShow { issue_id, .. } => issue_id.iter_mut().try_for_each(|id| check("issue ID", id))?,
+        Commands::Issues {
+            action: Some(IssuesAction::Details { issue_ids, .. }),
+            ..
+        } => issue_ids.iter_mut().try_for_each(|id| check("issue ID", id))?,
+        Commands::Events { finding_key, .. } => check("finding key", finding_key)?,
+        Commands::Triage { action } => match action {
+            TriageAction::Get {
+                issue_key, finding_key, ..
+            } => {
+                if let Some(key) = issue_key {
+                    check("issue key", key)?;
+                }
+                if let Some(key) = finding_key {
+                    check("finding key", key)?;
+                }
+            }
+            TriageAction::Update { issue_keys, .. } => issue_keys.iter_mut().try_for_each(|k| check("issue key", k))?,
+            TriageAction::History { issue_key, .. } => check("issue key", issue_key)?,
+        },
+        _ => {}
+    }
+    Ok(())
+}
+
+/// Report a 404 as "<what> not found: <id>" instead of the raw API error.
+fn or_not_found(err: PolarisError, what: &str, id: &str, context: &str) -> anyhow::Error {
+    match err {
+        PolarisError::NotFound(_) => anyhow::anyhow!("{what} not found: {id}"),
+        err => anyhow::Error::new(err).context(context.to_string()),
+    }
+}
+
+/// Describe who a JWT belongs to and when it expires, e.g. ` (user: a@b.com, session expires in 1h)`.
+fn session_summary(jwt: &str) -> String {
+    let Ok(claims) = JwtClaims::decode(jwt) else {
+        return String::new();
+    };
+    let mut parts = Vec::new();
+    if let Some(who) = claims.identity() {
+        parts.push(format!("user: {who}"));
+    }
+    if let Some(exp) = claims.expires_at() {
+        let left = exp - Utc::now();
+        if left > chrono::Duration::zero() {
+            parts.push(format!("session expires in {}", humanize_duration(left)));
+        } else {
+            parts.push("session already expired".to_string());
+        }
+    }
+    if parts.is_empty() {
+        String::new()
+    } else {
+        format!(" ({})", parts.join(", "))
+    }
+}
+
+/// Render a duration in its largest whole unit: `3d`, `1h`, `12m`, `40s`.
+fn humanize_duration(d: chrono::Duration) -> String {
+    if d.num_days() > 0 {
+        format!("{}d", d.num_days())
+    } else if d.num_hours() > 0 {
+        format!("{}h", d.num_hours())
+    } else if d.num_minutes() > 0 {
+        format!("{}m", d.num_minutes())
+    } else {
+        format!("{}s", d.num_seconds().max(0))
+    }
+}
+
+fn make_client(cli: &Cli) -> Result<PolarisClient> {
+    let api_token = resolve_token(cli)?;
+    let config = PolarisConfig {
+        base_url: cli.base_url.clone(),
+        api_token,
+        extra_query: cli.query.clone(),
+        verbose: cli.verbose,
+        tls: tls_config(cli)?,
+        rate_limit: RateLimitConfig {
+            remaining_header: cli.rate_limit_remaining_header.clone(),
+            reset_header: cli.rate_limit_reset_header.clone(),
+            ..Default::default()
+        },
+        ..Default::default()
+    }
+    .with_timeout(std::time::Duration::from_secs(cli.timeout))
+    .with_max_retries(cli.max_retries)
+    .with_timeout_retries(cli.timeout_retries);
+    let config = match cli.max_total_retry_time {
+        Some(budget) => config.with_max_total_retry_time(budget),
+        None => config,
+    };
+    let mut config = if cli.raw {
+        config.with_raw_responses(pager::write_raw)
+    } else {
+        config
+    };
+    if let Some(dir) = response_cache_dir().filter(|_| !cli.no_cache) {
+        config = config.with_cache_dir(dir);
+    }
+    config.trace = trace_log(cli)?;
+    config.read_only = cli.read_only;
+    if let Some(includes) = load_config().issues.default_includes {
+        for unknown in includes.iter().filter(|i| !KNOWN_ISSUE_INCLUDES.contains(&i.as_str())) {
+            eprintln!("warning: unknown include `{unknown}` in config [issues] default_includes");
+        }
+        config.issue_includes = includes;
+    }
     Ok(PolarisClient::new(config))
 }
 
+fn trace_log(cli: &Cli) -> Result<Option<TraceLog>> {
+    Ok(cli.trace_file.as_deref().map(TraceLog::create).transpose()?)
+}
+
+fn tls_config(cli: &Cli) -> Result<TlsConfig> {
+    let mut tls = TlsConfig {
+        insecure: cli.insecure,
+        ..Default::default()
+    };
+    if let Some(ref path) = cli.ca_cert {
+        tls.add_ca_cert_file(path)?;
+    }
+    if let Some(ref dir) = cli.ca_cert_dir
+        && tls.add_ca_cert_dir(dir)? == 0
+    {
+        eprintln!("warning: no usable CA certificates found in {}", dir.display());
+    }
+    if tls.insecure {
+        eprintln!("WARNING: TLS certificate verification is disabled (--insecure); connections can be intercepted.");
+    }
+    Ok(tls)
+}
+
+/// Parse the command line. `--json`/`--toon` conflict in clap itself, but `--format`
+/// can't be declared as conflicting with them there: clap treats a `POLARIS_FORMAT`
+/// value as given, and the shorthands are documented to override it. So only a
+/// `--format` typed on the command line is rejected alongside them.
+fn parse_cli() -> Cli {
+    try_parse_cli(std::env::args_os()).unwrap_or_else(|e| e.exit())
+}
+
+fn try_parse_cli<I, T>(args: I) -> Result<Cli, clap::Error>
+where
+    I: IntoIterator<Item = T>,
+    T: Into<std::ffi::OsString> + Clone,
+{
+    let matches = Cli::command().try_get_matches_from(args)?;
+    let cli = Cli::from_arg_matches(&matches)?;
+    let typed_format = matches.value_source("format") == Some(clap::parser::ValueSource::CommandLine);
+    if typed_format && (cli.json || cli.toon) {
+        let flag = if cli.json { "--json" } else { "--toon" };
+        return Err(Cli::command().error(
+            clap::error::ErrorKind::ArgumentConflict,
+            format!("the argument '--format <FORMAT>' cannot be used with '{flag}'"),
+        ));
+    }
+    Ok(cli)
+}
+
+/// Ends the run with a specific exit code and no error message, for outcomes scripts
+/// test with the exit status (e.g. `auth status` with no token configured).
+#[derive(Debug)]
+struct Exit(u8);
+
+impl std::fmt::Display for Exit {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "exit status {}", self.0)
+    }
+}
+
+impl std::error::Error for Exit {}
+
+/// `auth status` exit code when no token source is configured.
+const EXIT_NOT_LOGGED_IN: u8 = 3;
+/// List command exit code with `--require-results` when nothing matched.
+const EXIT_NO_RESULTS: u8 = 4;
+/// `runs wait` exit code when the run is still going at the deadline.
+const EXIT_TIMED_OUT: u8 = 5;
+
+/// With `--require-results`, turn an empty listing (already printed) into exit status 4.
+fn require_results(required: bool, count: usize) -> Result<()> {
+    if required && count == 0 {
+        eprintln!("error: no results (--require-results)");
+        return Err(Exit(EXIT_NO_RESULTS).into());
+    }
+    Ok(())
+}
+
 #[tokio::main]
-async fn main() -> Result<()> {
-    let mut cli = Cli::parse();
+async fn main() -> Result<std::process::ExitCode> {
+    let result = run().await;
+    pager::finish();
+    match result {
+        Ok(()) => Ok(std::process::ExitCode::SUCCESS),
+        // Output was cut short by its reader (`| head`, or a quit pager), which isn't
+        // a failure of the command.
+        Err(e) if pager::is_broken_pipe(&e) => Ok(std::process::ExitCode::SUCCESS),
+        Err(e) => match e.downcast_ref::<Exit>() {
+            Some(&Exit(code)) => Ok(std::process::ExitCode::from(code)),
+            None => Err(e),
+        },
+    }
+}
+
+async fn run() -> Result<()> {
+    let mut cli = parse_cli();
     if cli.base_url == BASE_URL_PLACEHOLDER {
         let config = load_config();
         if let Some(url) = config.base_url {
             cli.base_url = url;
         }
     }
+    validate_command_keys(&mut cli.command, cli.strict_keys)?;
     let fmt = cli.output_format();
+    let dates = cli.date_style();
+    let branch_opts = cli.branch_options();
+    let template = match (&cli.fields_file, &cli.command) {
+        (None, _) => None,
+        (Some(path), Commands::Projects { .. }) => Some(views::Template::load(path, ProjectView::FIELDS)?),
+        (Some(path), Commands::Branches { .. }) => Some(views::Template::load(path, BranchView::FIELDS)?),
+        (Some(path), Commands::Issues { action: None, .. }) => Some(views::Template::load(path, IssueView::FIELDS)?),
+        (Some(_), _) => anyhow::bail!("--fields-file is only supported by `projects`, `branches` and `issues`"),
+    };
+    if let Commands::Issues { action: None, group_by_finding: true, .. } = cli.command {
+        if template.is_some() {
+            anyhow::bail!("--group-by-finding can't be combined with --fields-file");
+        }
+        if matches!(fmt, OutputFormat::Junit) {
+            anyhow::bail!("--group-by-finding can't be combined with --format junit");
+        }
+    }
+    if cli.require_results
+        && !matches!(
+            cli.command,
+            Commands::Projects { .. } | Commands::Branches { .. } | Commands::Issues { action: None, .. } | Commands::Runs { action: None, .. }
+        )
+    {
+        anyhow::bail!("--require-results is only supported by `projects`, `branches`, `issues` and `runs`");
+    }
+    if cli.read_only
+        && let Some(what) = mutation(&cli.command)
+    {
+        return Err(PolarisError::ReadOnly(what.to_string()).into());
+    }
+    // A pager would hold followed output back until it exits.
+    let following = matches!(
+        cli.command,
+        Commands::Triage { action: TriageAction::History { follow: true, .. } }
+    );
+    pager::init(if following { PagerMode::Never } else { cli.pager }, matches!(fmt, OutputFormat::Pretty));
 
     // Auth subcommands that don't need a client
     if let Commands::Auth { ref action } = cli.command {
         match action {
-            AuthCommands::Login { token } => {
+            AuthCommands::Login {
+                token,
+                allow_file_token_store,
+            } => {
                 let token = match token {
                     Some(t) => t.clone(),
                     None => {
                         eprint!("Enter API token: ");
                         let mut buf = String::new();
                         std::io::stdin().read_line(&mut buf)?;
-                        buf.trim().to_string()
+                        buf
                     }
                 };
-                if token.is_empty() {
-                    anyhow::bail!("Token cannot be empty");
-                }
+                let token = normalize_api_token(&token)?;
                 // Verify the token works before storing
                 let config = PolarisConfig {
                     base_url: cli.base_url.clone(),
                     api_token: token.clone(),
+                    tls: tls_config(&cli)?,
+                    trace: trace_log(&cli)?,
+                    ..Default::default()
                 };
                 let test_client = PolarisClient::new(config);
-                test_client.authenticate().await.context("Token verification failed — not stored")?;
+                let jwt = test_client
+                    .authenticate()
+                    .await
+                    .map_err(|e| with_auth_hint(e, "Token verification failed — not stored"))?;
 
-                let entry = keyring_entry().context("Failed to access OS keychain")?;
-                entry.set_password(&token).context("Failed to store token in keychain")?;
-                eprintln!("✓ Token verified and stored in OS keychain");
+                match keyring_entry().and_then(|e| e.set_password(&token)) {
+                    Ok(()) => {
+                        eprintln!("✓ Token verified{} and stored in OS keychain", session_summary(&jwt));
+                    }
+                    Err(e) if keyring_unavailable(&e) => {
+                        if !allow_file_token_store {
+                            anyhow::bail!("OS keychain is unavailable ({e}); token not stored. Instead, {KEYRING_UNAVAILABLE_HINT}");
+                        }
+                        let path = token_store_path().context("Could not determine home directory")?;
+                        write_token_store(&path, &token)?;
+                        eprintln!(
+                            "✓ Token verified{} and stored in {} (OS keychain unavailable)",
+                            session_summary(&jwt),
+                            path.display()
+                        );
+                    }
+                    Err(e) => return Err(e).context("Failed to store token in keychain"),
+                }
                 return Ok(());
             }
             AuthCommands::Logout => {
-                match keyring_entry() {
-                    Ok(entry) => match entry.delete_credential() {
-                        Ok(()) => eprintln!("✓ Token removed from OS keychain"),
-                        Err(keyring::Error::NoEntry) => eprintln!("No token stored in keychain"),
-                        Err(e) => anyhow::bail!("Failed to remove token: {e}"),
-                    },
-                    Err(e) => anyhow::bail!("Failed to access OS keychain: {e}"),
+                if let Some(path) = token_store_path().filter(|p| p.exists()) {
+                    std::fs::remove_file(&path)
+                        .with_context(|| format!("Failed to remove {}", path.display()))?;
+                    eprintln!("✓ Token removed from {}", path.display());
+                }
+                match keyring_entry().and_then(|e| e.delete_credential()) {
+                    Ok(()) => eprintln!("✓ Token removed from OS keychain"),
+                    Err(keyring::Error::NoEntry) => eprintln!("No token stored in keychain"),
+                    Err(e) if keyring_unavailable(&e) => eprintln!("OS keychain is unavailable ({e})"),
+                    Err(e) => anyhow::bail!("Failed to remove token: {e}"),
+                }
+                return Ok(());
+            }
+            AuthCommands::ClearCache => {
+                match response_cache_dir().filter(|d| d.exists()) {
+                    Some(dir) => {
+                        std::fs::remove_dir_all(&dir)
+                            .with_context(|| format!("Failed to remove {}", dir.display()))?;
+                        eprintln!("✓ Cache cleared ({})", dir.display());
+                    }
+                    None => eprintln!("No cached responses"),
                 }
                 return Ok(());
             }
             AuthCommands::Status => {
-                let has_arg = cli.api_token.is_some();
-                let has_env = std::env::var("POLARIS_API_TOKEN").is_ok();
-                let has_keychain = token_from_keyring().is_some();
-                let source = if has_arg {
-                    "--api-token flag"
-                } else if has_env {
-                    "POLARIS_API_TOKEN env var"
-                } else if has_keychain {
-                    "OS keychain"
-                } else {
-                    "none"
-                };
+                let sources = TokenSources::detect(&cli);
+                let source = sources.active();
+                let TokenSources {
+                    has_arg,
+                    has_token_file,
+                    has_env,
+                    keychain,
+                    has_keychain,
+                    has_store,
+                } = sources;
                 match fmt {
                     OutputFormat::Pretty => {
-                        println!("Token source:  {source}");
-                        println!("  --api-token: {}", if has_arg { "set" } else { "not set" });
-                        println!("  env var:     {}", if has_env { "set" } else { "not set" });
-                        println!("  keychain:    {}", if has_keychain { "stored" } else { "empty" });
+                        outln!("Token source:  {source}");
+                        outln!("  --api-token: {}", if has_arg { "set" } else { "not set" });
+                        outln!("  token file:  {}", if has_token_file { "set" } else { "not set" });
+                        outln!("  env var:     {}", if has_env { "set" } else { "not set" });
+                        outln!(
+                            "  keychain:    {}",
+                            match keychain {
+                                Err(_) => "unavailable",
+                                Ok(()) if has_keychain => "stored",
+                                Ok(()) => "empty",
+                            }
+                        );
+                        if has_store {
+                            outln!("  file store:  stored");
+                        }
                     }
                     _ => emit(&serde_json::json!({
                         "active_source": source,
                         "api_token_flag": has_arg,
+                        "api_token_file": has_token_file,
                         "env_var": has_env,
                         "keychain": has_keychain,
+                        "keychain_available": keychain.is_ok(),
+                        "file_store": has_store,
                     }), &fmt)?,
                 }
+                if let Err(e) = keychain
+                    && source == "none"
+                {
+                    eprintln!("OS keychain is unavailable ({e}); {KEYRING_UNAVAILABLE_HINT}.");
+                }
+                if source == "none" {
+                    return Err(Exit(EXIT_NOT_LOGGED_IN).into());
+                }
                 return Ok(());
             }
             AuthCommands::Jwt => {} // handled below with client
         }
     }
 
+    if let Commands::Doctor = cli.command {
+        return doctor::run(&cli, &fmt).await;
+    }
+
+    if let Commands::Version { check } = cli.command {
+        return print_version(&cli.base_url, &tls_config(&cli)?, check, &fmt).await;
+    }
+
     let client = make_client(&cli)?;
+    if cli.raw && !matches!(cli.command, Commands::Auth { .. }) {
+        pager::mute();
+    }
 
     match cli.command {
         Commands::Auth { action } => {
             // Only Jwt reaches here
             match action {
                 AuthCommands::Jwt => {
-                    let jwt = client.authenticate().await.context("Authentication failed")?;
+                    let jwt = client
+                        .authenticate()
+                        .await
+                        .map_err(|e| with_auth_hint(e, "Authentication failed"))?;
                     match fmt {
-                        OutputFormat::Pretty => println!("{jwt}"),
+                        OutputFormat::Pretty => outln!("{jwt}"),
                         _ => emit(&serde_json::json!({ "jwt": jwt }), &fmt)?,
                     }
                 }
@@ -421,190 +1597,752 @@ async fn main() -> Result<()> {
             }
         }
 
-        Commands::Projects { name } => {
-            let resp = client
-                .list_all_projects(name.as_deref(), 25)
-                .await
-                .context("Failed to list projects")?;
+        Commands::Projects {
+            name,
+            owner,
+            limit,
+            sort_by,
+            since,
+        } => {
+            let mut resp = if cli.no_pagination {
+                client
+                    .list_projects(name.as_deref(), owner.as_deref(), cli.page_size, 0)
+                    .await
+            } else {
+                client
+                    .list_all_projects(name.as_deref(), owner.as_deref(), cli.page_size)
+                    .await
+            }
+            .context("Failed to list projects")?;
+            let page = (resp.data.len(), resp.meta.as_ref().and_then(|m| m.total));
+
+            // The server honors the property filter, but instances without an `owner`
+            // property would otherwise return everything; re-check locally.
+            if let Some(ref owner) = owner {
+                resp.data.retain(|p| {
+                    p.attributes
+                        .owner()
+                        .is_some_and(|o| o.eq_ignore_ascii_case(owner))
+                });
+            }
+            if let Some(since) = since {
+                resp.data
+                    .retain(|p| p.attributes.last_activity().is_some_and(|d| d >= since));
+            }
+            match sort_by {
+                Some(ProjectSort::Name) => resp
+                    .data
+                    .sort_by_key(|p| p.attributes.name.to_lowercase()),
+                Some(ProjectSort::Updated) => resp
+                    .data
+                    .sort_by_key(|p| std::cmp::Reverse(p.attributes.last_activity())),
+                None => {}
+            }
+            if let Some(limit) = limit {
+                resp.data.truncate(limit);
+            }
 
             match fmt {
+                _ if let Some(template) = &template => {
+                    let items: Vec<ProjectView> = resp.data.iter().map(ProjectView::from).collect();
+                    template.render(&items, &fmt)?;
+                }
                 OutputFormat::Pretty => {
                     if resp.data.is_empty() {
-                        println!("No projects found.");
-                        return Ok(());
+                        outln!("No projects found.");
+                        return require_results(cli.require_results, 0);
                     }
-                    println!("{} projects found.\n", resp.data.len());
-                    println!("{:<40} {:<40} DESCRIPTION", "ID", "NAME");
-                    println!("{}", "-".repeat(100));
+                    outln!("{} projects found.\n", resp.data.len());
+                    outln!("{:<40} {:<40} {:<25} DESCRIPTION", "ID", "NAME", "UPDATED");
+                    outln!("{}", "-".repeat(125));
                     for p in &resp.data {
-                        println!(
-                            "{:<40} {:<40} {}",
-                            p.id,
-                            p.attributes.name,
+                        outln!(
+                            "{} {} {} {}",
+                            pad(&p.id, 40),
+                            pad(&p.attributes.name, 40),
+                            pad(
+                                p.attributes
+                                    .date_modified
+                                    .as_deref()
+                                    .or(p.attributes.date_created.as_deref())
+                                    .unwrap_or("-"),
+                                25,
+                            ),
                             p.attributes.description.as_deref().unwrap_or("-")
                         );
                     }
                 }
                 _ => {
-                    let items: Vec<serde_json::Value> = resp
-                        .data
-                        .iter()
-                        .map(|p| {
-                            serde_json::json!({
-                                "id": p.id,
-                                "name": p.attributes.name,
-                                "description": p.attributes.description,
-                            })
-                        })
-                        .collect();
-                    emit(&serde_json::json!(items), &fmt)?;
+                    let items: Vec<ProjectView> = resp.data.iter().map(ProjectView::from).collect();
+                    emit(&serde_json::to_value(items)?, &fmt)?;
                 }
             }
+            first_page_note(cli.no_pagination, page, &fmt)?;
+            require_results(cli.require_results, resp.data.len())?;
         }
 
-        Commands::Branches { project_id } => {
-            let resp = client
-                .list_all_branches(&project_id, 25)
-                .await
-                .context("Failed to list branches")?;
+        Commands::Branches { project_id, main_only } => {
+            let mut resp = if cli.no_pagination && !main_only {
+                client.list_branches(&project_id, cli.page_size, 0).await
+            } else {
+                client.list_all_branches(&project_id, cli.page_size).await
+            }
+            .map_err(|e| or_not_found(e, "project", &project_id, "Failed to list branches"))?;
+            if main_only {
+                let main = main_branch(&resp.data)
+                    .cloned()
+                    .with_context(|| format!("Project {project_id} has no main branch"))?;
+                resp.data = vec![main];
+                resp.meta = None;
+            }
 
             match fmt {
+                _ if let Some(template) = &template => {
+                    let items: Vec<BranchView> = resp.data.iter().map(BranchView::from).collect();
+                    template.render(&items, &fmt)?;
+                }
                 OutputFormat::Pretty => {
                     if resp.data.is_empty() {
-                        println!("No branches found.");
-                        return Ok(());
+                        outln!("No branches found.");
+                        return require_results(cli.require_results, 0);
                     }
-                    println!("{} branches found.\n", resp.data.len());
-                    println!("{:<40} {:<30} MAIN", "ID", "NAME");
-                    println!("{}", "-".repeat(80));
+                    outln!("{} branches found.\n", resp.data.len());
+                    outln!("{:<40} {:<30} MAIN", "ID", "NAME");
+                    outln!("{}", "-".repeat(80));
                     for b in &resp.data {
-                        println!(
-                            "{:<40} {:<30} {}",
-                            b.id,
-                            b.attributes.name,
-                            if b.attributes.main_for_project.unwrap_or(false) { "✓" } else { "" }
+                        outln!(
+                            "{} {} {}",
+                            pad(&b.id, 40),
+                            pad(&b.attributes.name, 30),
+                            if is_main(b) { "✓" } else { "" }
                         );
                     }
                 }
                 _ => {
-                    let items: Vec<serde_json::Value> = resp
-                        .data
-                        .iter()
-                        .map(|b| {
-                            serde_json::json!({
-                                "id": b.id,
-                                "name": b.attributes.name,
-                                "main": b.attributes.main_for_project.unwrap_or(false),
-                            })
-                        })
-                        .collect();
-                    emit(&serde_json::json!(items), &fmt)?;
+                    let items: Vec<BranchView> = resp.data.iter().map(BranchView::from).collect();
+                    emit(&serde_json::to_value(items)?, &fmt)?;
                 }
             }
+            first_page_note(cli.no_pagination, (resp.data.len(), resp.meta.as_ref().and_then(|m| m.total)), &fmt)?;
+            require_results(cli.require_results, resp.data.len())?;
+        }
+
+        Commands::MainBranch { project_id } => {
+            let branches = client
+                .list_all_branches(&project_id, cli.page_size)
+                .await
+                .map_err(|e| or_not_found(e, "project", &project_id, "Failed to list branches"))?;
+            let main = main_branch(&branches.data)
+                .with_context(|| format!("Project {project_id} has no main branch"))?;
+            match fmt {
+                OutputFormat::Pretty => outln!("{}", main.id),
+                _ => emit(&serde_json::json!({ "id": main.id, "name": main.attributes.name }), &fmt)?,
+            }
         }
 
         Commands::Issues {
-            project_id,
-            branch_id,
+            action: Some(IssuesAction::Details {
+                issue_ids,
+                project_id,
+                branch_id,
+                concurrency,
+            }),
+            ..
+        } => {
+            let branch_id = resolve_branch(&client, &project_id, branch_id, branch_opts).await?;
+            show_issue_details(
+                &client,
+                &issue_ids,
+                &project_id,
+                &branch_id,
+                concurrency as usize,
+                &cli.base_url,
+                dates,
+                None,
+                true,
+                &fmt,
+            )
+            .await?;
+        }
+
+        Commands::Issues {
+            action:
+                Some(IssuesAction::Dump {
+                    project_id,
+                    branch_id,
+                    output_dir,
+                    events,
+                    concurrency,
+                }),
+            ..
         } => {
-            let branch_id = resolve_branch(&client, &project_id, branch_id).await?;
+            use futures::StreamExt;
 
+            let branch_id = resolve_branch(&client, &project_id, branch_id, branch_opts).await?;
             let resp = client
-                .list_all_issues(&project_id, Some(&branch_id), None, 25)
+                .list_all_issues(&project_id, Some(&branch_id), None, cli.page_size)
                 .await
-                .context("Failed to list issues")?;
+                .map_err(|e| or_not_found(e, "project/branch", &format!("{project_id}/{branch_id}"), "Failed to list issues"))?;
+            std::fs::create_dir_all(&output_dir)
+                .with_context(|| format!("Failed to create {}", output_dir.display()))?;
+
+            let progress = Progress::new("issues", resp.data.len());
+            let mut details: Vec<_> = futures::stream::iter(resp.data.iter().enumerate())
+                .map(|(i, issue)| {
+                    let (client, project_id, branch_id) = (&client, &project_id, &branch_id);
+                    async move { (i, fetch_issue_detail(client, &issue.id, project_id, branch_id, events).await) }
+                })
+                .buffer_unordered(concurrency as usize)
+                .inspect(|_| progress.tick())
+                .collect()
+                .await;
+            progress.finish();
+            details.sort_by_key(|(i, _)| *i);
+
+            // Written in listing order, so a key that sanitizes to a name already taken
+            // gets the same suffix on every run.
+            let mut used = std::collections::HashSet::new();
+            let mut written = 0;
+            let mut failed = 0;
+            for (issue, (_, result)) in resp.data.iter().zip(details) {
+                let key = &issue.attributes.issue_key;
+                let detail = match result {
+                    Ok(detail) => detail,
+                    Err(e) => {
+                        eprintln!("warning: skipping issue {key}: {e:#}");
+                        failed += 1;
+                        continue;
+                    }
+                };
+                let mut record = serde_json::json!({ "issue": detail.issue });
+                match detail.events {
+                    Some(Ok(events)) => record["events"] = events,
+                    Some(Err(e)) => record["events_error"] = e.to_string().into(),
+                    None => {}
+                }
+                let mut name = safe_file_stem(key);
+                if !used.insert(name.clone()) {
+                    name = format!("{name}-{}", safe_file_stem(&issue.id));
+                    used.insert(name.clone());
+                }
+                let path = output_dir.join(format!("{name}.json"));
+                std::fs::write(&path, serde_json::to_string_pretty(&record)? + "\n")
+                    .with_context(|| format!("Failed to write {}", path.display()))?;
+                written += 1;
+            }
 
             match fmt {
+                OutputFormat::Pretty => {
+                    eprintln!("Wrote {written} issue file(s) to {}", output_dir.display());
+                }
+                _ => emit(
+                    &serde_json::json!({ "dir": output_dir, "written": written, "failed": failed }),
+                    &fmt,
+                )?,
+            }
+            if failed > 0 {
+                anyhow::bail!("{failed} issue(s) could not be fetched");
+            }
+        }
+
+        Commands::Issues {
+            action:
+                Some(IssuesAction::Count {
+                    project_id,
+                    branch_id,
+                    status,
+                    by_severity,
+                }),
+            ..
+        } => {
+            let branch_id = resolve_branch(&client, &project_id, branch_id, branch_opts).await?;
+            let filter = IssueFilter {
+                triage_statuses: status_filter(status).iter().map(|s| s.to_string()).collect(),
+                ..Default::default()
+            };
+            let total = match client.count_issues(&project_id, Some(&branch_id), &filter).await {
+                // Same fallback as `issues`: without triage data everything counts as open.
+                Err(PolarisError::Api { status: 400, .. }) if status != IssueStatus::All => {
+                    eprintln!("warning: triage data unavailable; treating all issues as open");
+                    if status == IssueStatus::Dismissed {
+                        Ok(Some(0))
+                    } else {
+                        client.count_issues(&project_id, Some(&branch_id), &IssueFilter::default()).await
+                    }
+                }
+                total => total,
+            }
+            .map_err(|e| or_not_found(e, "project/branch", &format!("{project_id}/{branch_id}"), "Failed to count issues"))?
+            .context("the server did not report a total for this query")?;
+
+            let severities = if by_severity {
+                let resp = client
+                    .get_roll_up_counts(&project_id, Some(&branch_id), Some(SEVERITY_GROUP_BY))
+                    .await
+                    .context("Failed to get roll-up counts")?;
+                Some(roll_up_rows(&resp))
+            } else {
+                None
+            };
+
+            match fmt {
+                OutputFormat::Pretty => {
+                    outln!("{total}");
+                    for (name, count) in severities.iter().flatten() {
+                        outln!("  {name:<12} {}", count.map_or("-".to_string(), |c| c.to_string()));
+                    }
+                }
+                _ => {
+                    let mut out = serde_json::json!({ "total": total });
+                    if let Some(rows) = severities {
+                        let map: serde_json::Map<String, serde_json::Value> =
+                            rows.into_iter().map(|(name, count)| (name, serde_json::json!(count))).collect();
+                        out["by_severity"] = serde_json::Value::Object(map);
+                    }
+                    emit(&out, &fmt)?;
+                }
+            }
+        }
+
+        Commands::Issues {
+            action: None,
+            project_id,
+            branch_id,
+            limit,
+            status,
+            include_dismissed,
+            hide_dismissed,
+            search,
+            since,
+            until,
+            after,
+            all_branches,
+            concurrency,
+            fail_severity,
+            group_by_finding,
+        } => {
+            let project_id = project_id.context("--project-id is required")?;
+            let status = match (status, include_dismissed, hide_dismissed) {
+                (Some(status), ..) => status,
+                (None, true, _) => IssueStatus::All,
+                (None, _, true) => IssueStatus::Open,
+                (None, false, false) if load_config().issues.hide_dismissed => IssueStatus::Open,
+                (None, false, false) => IssueStatus::All,
+            };
+            // The issue-query API has no substring filter, so --search matches client-side
+            // and --limit then applies to the matches rather than the fetch. Date bounds are
+            // sent to the server but re-checked here too, in case it ignores them.
+            let client_filtered = search.is_some() || since.is_some() || until.is_some();
+            let fetch_limit = if client_filtered { None } else { limit };
+            let page_size = fetch_limit.map_or(cli.page_size, |l| l.clamp(1, cli.page_size as usize) as u32);
+            let filter = IssueFilter {
+                triage_statuses: status_filter(status).iter().map(|s| s.to_string()).collect(),
+                first_detected_since: since,
+                first_detected_until: until,
+                ..Default::default()
+            };
+            let fetch = |filter: IssueFilter, branch_id: String| {
+                let (client, project_id, after) = (&client, &project_id, &after);
+                async move {
+                    let branch_id = Some(branch_id.as_str());
+                    match (after, cli.no_pagination) {
+                        (None, true) => {
+                            client
+                                .query_issues(project_id, branch_id, None, &filter, page_size, 0)
+                                .await
+                        }
+                        (None, false) => {
+                            client
+                                .list_issues_up_to(project_id, branch_id, None, &filter, page_size, fetch_limit)
+                                .await
+                        }
+                        (Some(cursor), true) => {
+                            client
+                                .query_issues_after(project_id, branch_id, None, &filter, page_size, cursor)
+                                .await
+                        }
+                        (Some(cursor), false) => {
+                            client
+                                .resume_issues(project_id, branch_id, None, &filter, page_size, fetch_limit, cursor)
+                                .await
+                        }
+                    }
+                }
+            };
+            // One branch's issues, with fallbacks for filters the server rejects and the
+            // client-side filters applied.
+            let fetch_branch = |branch_id: String| {
+                let (fetch, mut filter, project_id, search) = (&fetch, filter.clone(), &project_id, &search);
+                async move {
+                    let mut resp = fetch(filter.clone(), branch_id.clone()).await;
+                    // Instances that don't index first-detected-on reject the date filter; the
+                    // client-side check below then does all the work.
+                    if matches!(resp, Err(PolarisError::Api { status: 400, .. })) && (since.is_some() || until.is_some()) {
+                        eprintln!("note: date filter not supported by the server; filtering client-side");
+                        filter.first_detected_since = None;
+                        filter.first_detected_until = None;
+                        resp = fetch(filter, branch_id.clone()).await;
+                    }
+                    let mut resp = match resp {
+                        // Instances without triage data reject the triage-status filter.
+                        Err(PolarisError::Api { status: 400, .. }) if status != IssueStatus::All => {
+                            eprintln!("warning: triage data unavailable; treating all issues as open");
+                            let mut resp = fetch(IssueFilter::default(), branch_id.clone()).await;
+                            if let (IssueStatus::Dismissed, Ok(resp)) = (status, &mut resp) {
+                                resp.data.clear();
+                                resp.meta = None;
+                                resp.links = None;
+                            }
+                            resp
+                        }
+                        resp => resp,
+                    }
+                    .map_err(|e| or_not_found(e, "project/branch", &format!("{project_id}/{branch_id}"), "Failed to list issues"))?;
+
+                    if since.is_some() || until.is_some() {
+                        // Issues without a first-detected date can't be shown to fall in range.
+                        resp.data.retain(|issue| {
+                            issue.attributes.first_detected().is_some_and(|detected| {
+                                since.is_none_or(|since| detected >= since) && until.is_none_or(|until| detected <= until)
+                            })
+                        });
+                    }
+                    if let Some(text) = search {
+                        let needle = text.to_lowercase();
+                        resp.data.retain(|issue| {
+                            let attrs = &issue.attributes;
+                            [Some(&attrs.issue_key), Some(&attrs.finding_key), attrs.sub_tool.as_ref()]
+                                .into_iter()
+                                .flatten()
+                                .any(|field| field.to_lowercase().contains(&needle))
+                        });
+                    }
+                    anyhow::Ok(resp)
+                }
+            };
+
+            // With --all-branches, `branches[i]` names the branch of `resp.data[i]`.
+            let (mut resp, mut branches) = if all_branches {
+                use futures::StreamExt;
+
+                let all = client
+                    .list_all_branches(&project_id, cli.page_size)
+                    .await
+                    .map_err(|e| or_not_found(e, "project", &project_id, "Failed to list branches"))?;
+                let progress = Progress::new("branches", all.data.len());
+                let mut results: Vec<_> = futures::stream::iter(all.data)
+                    .map(|branch| {
+                        let fetch_branch = &fetch_branch;
+                        async move { (branch.attributes.name, fetch_branch(branch.id).await) }
+                    })
+                    .buffer_unordered(concurrency as usize)
+                    .inspect(|_| progress.tick())
+                    .collect()
+                    .await;
+                progress.finish();
+                results.sort_by(|a, b| a.0.cmp(&b.0));
+                let (resp, names) = merge_branch_issues(results);
+                (resp, Some(names))
+            } else {
+                let branch_id = resolve_branch(&client, &project_id, branch_id, branch_opts).await?;
+                (fetch_branch(branch_id).await?, None)
+            };
+            if let (true, Some(limit)) = (client_filtered || all_branches, limit) {
+                // A cursor past dropped matches would skip them on `--after`.
+                if resp.data.len() > limit {
+                    resp.links = None;
+                }
+                resp.data.truncate(limit);
+                if let Some(branches) = branches.as_mut() {
+                    branches.truncate(limit);
+                }
+            }
+            let branch_of = |i: usize| branches.as_ref().and_then(|b| b.get(i)).map(String::as_str);
+
+            if group_by_finding {
+                let included_map = build_included_map(&resp.included);
+                let items: Vec<IssueView> = resp
+                    .data
+                    .iter()
+                    .enumerate()
+                    .map(|(i, issue)| IssueView::new(issue, &included_map, branch_of(i)))
+                    .collect();
+                let groups = views::group_by_finding(items);
+                match fmt {
+                    OutputFormat::Pretty => {
+                        if groups.is_empty() {
+                            outln!("No issues found.");
+                        } else {
+                            outln!("{} findings across {} issues.\n", groups.len(), resp.data.len());
+                            outln!("{:<64} {:>6} {:<12} CHECKER", "FINDING-KEY", "COUNT", "MAX-SEVERITY");
+                            outln!("{}", "-".repeat(110));
+                            for group in &groups {
+                                let checker = group.issues[0].checker.unwrap_or("-");
+                                outln!(
+                                    "{} {:>6} {} {}",
+                                    pad(group.finding_key, 64),
+                                    group.count,
+                                    pad(group.max_severity, 12),
+                                    checker,
+                                );
+                            }
+                        }
+                    }
+                    _ => emit(&serde_json::to_value(&groups)?, &fmt)?,
+                }
+                first_page_note(cli.no_pagination, (resp.data.len(), resp.meta.as_ref().and_then(|m| m.total)), &fmt)?;
+                if let Some(cursor) = resp.next_cursor() {
+                    eprintln!("(continue with --after {cursor})");
+                }
+                return require_results(cli.require_results, resp.data.len());
+            }
+
+            match fmt {
+                _ if let Some(template) = &template => {
+                    let included_map = build_included_map(&resp.included);
+                    let items: Vec<IssueView> = resp
+                        .data
+                        .iter()
+                        .enumerate()
+                        .map(|(i, issue)| IssueView::new(issue, &included_map, branch_of(i)))
+                        .collect();
+                    template.render(&items, &fmt)?;
+                }
                 OutputFormat::Pretty => {
                     if resp.data.is_empty() {
-                        println!("No issues found.");
-                        return Ok(());
+                        outln!("No issues found.");
+                        return require_results(cli.require_results, 0);
+                    }
+                    let fetched = resp.data.len();
+                    match resp.meta.as_ref().and_then(|m| m.total) {
+                        Some(total) if total != fetched as u64 => {
+                            outln!("{fetched} of {total} issues.\n");
+                        }
+                        _ => outln!("{fetched} issues found.\n"),
                     }
-                    println!("{} issues found.\n", resp.data.len());
 
                     let included_map = build_included_map(&resp.included);
 
-                    println!(
-                        "{:<12} {:<64} {:<20} {:<10} TYPE",
-                        "ID (short)", "ISSUE-KEY", "CHECKER", "SEVERITY",
+                    let branch_header = if branches.is_some() { format!("{:<24} ", "BRANCH") } else { String::new() };
+                    outln!(
+                        "{branch_header}{:<12} {:<64} {:<16} {:<20} {:<10} TYPE",
+                        "ID (short)", "ISSUE-KEY", "TOOL", "CHECKER", "SEVERITY",
                     );
-                    println!("{}", "-".repeat(130));
+                    outln!("{}", "-".repeat(147 + branch_header.len()));
 
-                    for issue in &resp.data {
-                        let short_id = &issue.id[..issue.id.len().min(10)];
+                    for (i, issue) in resp.data.iter().enumerate() {
+                        let short_id = truncate_width(&issue.id, 10);
                         let severity = resolve_included(&issue.relationships, "/severity/data/id", "taxon", &included_map);
                         let issue_type = resolve_included(&issue.relationships, "/issue-type/data/id", "issue-type", &included_map);
-
-                        println!(
-                            "{:<12} {:<64} {:<20} {:<10} {}",
-                            short_id,
-                            issue.attributes.issue_key,
-                            issue.attributes.sub_tool.as_deref().unwrap_or("-"),
-                            severity,
+                        let tool = resolve_included(&issue.relationships, "/tool-domain-service/data/id", "tool-domain-service", &included_map);
+                        let branch = branch_of(i).map(|b| format!("{} ", pad(b, 24))).unwrap_or_default();
+
+                        outln!(
+                            "{branch}{} {} {} {} {} {}",
+                            pad(short_id, 12),
+                            pad(&issue.attributes.issue_key, 64),
+                            pad(tool, 16),
+                            pad(issue.attributes.sub_tool.as_deref().unwrap_or("-"), 20),
+                            pad(severity, 10),
                             issue_type,
                         );
                     }
                 }
+                OutputFormat::Junit => {
+                    let included_map = build_included_map(&resp.included);
+                    let cases: Vec<junit::TestCase> = resp
+                        .data
+                        .iter()
+                        .enumerate()
+                        .map(|(i, issue)| junit::TestCase {
+                            branch: branch_of(i),
+                            issue_key: &issue.attributes.issue_key,
+                            finding_key: &issue.attributes.finding_key,
+                            checker: issue.attributes.sub_tool.as_deref().unwrap_or("-"),
+                            severity: resolve_included(&issue.relationships, "/severity/data/id", "taxon", &included_map),
+                            issue_type: resolve_included(&issue.relationships, "/issue-type/data/id", "issue-type", &included_map),
+                            path: resolve_path(issue.relationships.as_ref(), &included_map),
+                        })
+                        .collect();
+                    outln!("{}", junit::render(&cases, fail_severity)?);
+                }
                 _ => {
                     let included_map = build_included_map(&resp.included);
+                    let items: Vec<IssueView> = resp
+                        .data
+                        .iter()
+                        .enumerate()
+                        .map(|(i, issue)| IssueView::new(issue, &included_map, branch_of(i)))
+                        .collect();
+                    emit(&serde_json::to_value(items)?, &fmt)?;
+                }
+            }
+            first_page_note(cli.no_pagination, (resp.data.len(), resp.meta.as_ref().and_then(|m| m.total)), &fmt)?;
+            if let Some(cursor) = resp.next_cursor() {
+                eprintln!("(continue with --after {cursor})");
+            }
+            require_results(cli.require_results, resp.data.len())?;
+        }
+
+        Commands::Runs {
+            action:
+                Some(RunsAction::Wait {
+                    project_id,
+                    run_id,
+                    wait_timeout,
+                    interval,
+                }),
+            ..
+        } => {
+            let timeout = std::time::Duration::from_secs(wait_timeout);
+            let interval = std::time::Duration::from_secs(interval);
+            wait_for_run(&client, &project_id, &run_id, timeout, interval, &fmt, dates).await?;
+        }
+
+        Commands::Runs {
+            action: None,
+            project_id,
+            revision_id,
+        } => {
+            let project_id = project_id.context("--project-id is required")?;
+            let resp = client
+                .list_runs(&project_id, revision_id.as_deref(), cli.page_size, 0)
+                .await
+                .map_err(|e| or_not_found(e, "project", &project_id, "Failed to list runs"))?;
+
+            match fmt {
+                OutputFormat::Pretty => {
+                    if resp.data.is_empty() {
+                        outln!("No runs found.");
+                        return require_results(cli.require_results, 0);
+                    }
+                    let show = |d: &Option<String>| d.as_deref().map(|d| dates.show(d)).unwrap_or_else(|| "-".into());
+                    outln!("{} runs found.\n", resp.data.len());
+                    outln!("{:<38} {:<12} {:<34} COMPLETED", "ID", "STATUS", "CREATED");
+                    outln!("{}", "-".repeat(120));
+                    for r in &resp.data {
+                        outln!(
+                            "{} {} {} {}",
+                            pad(&r.id, 38),
+                            pad(r.attributes.status.as_deref().unwrap_or("-"), 12),
+                            pad(&show(&r.attributes.date_created), 34),
+                            show(&r.attributes.date_completed),
+                        );
+                    }
+                }
+                _ => {
                     let items: Vec<serde_json::Value> = resp
                         .data
                         .iter()
-                        .map(|issue| {
-                            let severity = resolve_included(&issue.relationships, "/severity/data/id", "taxon", &included_map);
-                            let issue_type = resolve_included(&issue.relationships, "/issue-type/data/id", "issue-type", &included_map);
+                        .map(|r| {
                             serde_json::json!({
-                                "id": issue.id,
-                                "issue_key": issue.attributes.issue_key,
-                                "finding_key": issue.attributes.finding_key,
-                                "checker": issue.attributes.sub_tool,
-                                "severity": severity,
-                                "type": issue_type,
+                                "id": r.id,
+                                "status": r.attributes.status,
+                                "date_created": r.attributes.date_created,
+                                "date_completed": r.attributes.date_completed,
                             })
                         })
                         .collect();
                     emit(&serde_json::json!(items), &fmt)?;
                 }
             }
+            first_page_note(true, (resp.data.len(), resp.meta.as_ref().and_then(|m| m.total)), &fmt)?;
+            require_results(cli.require_results, resp.data.len())?;
+        }
+
+        Commands::Source { run_id, path, output } => {
+            let not_found = |e| or_not_found(e, "source file", &path, "Failed to fetch source code");
+            if let Some(output) = output {
+                let mut file = tokio::fs::File::create(&output)
+                    .await
+                    .with_context(|| format!("Failed to create {}", output.display()))?;
+                let bytes = match client.get_source_code_to_writer(&run_id, &path, &mut file).await {
+                    Ok(bytes) => bytes,
+                    Err(e) => {
+                        // Don't leave a truncated file behind.
+                        let _ = tokio::fs::remove_file(&output).await;
+                        return Err(not_found(e));
+                    }
+                };
+                eprintln!("Wrote {bytes} bytes to {}", output.display());
+                return Ok(());
+            }
+            let source = client.get_source_code(&run_id, &path).await.map_err(not_found)?;
+            match fmt {
+                OutputFormat::Pretty => outln!("{}", source.trim_end_matches('\n')),
+                _ => emit(&serde_json::json!({ "run_id": run_id, "path": path, "source": source }), &fmt)?,
+            }
+        }
+
+        #[cfg(feature = "tui")]
+        Commands::Tui { project_id, branch_id } => {
+            if cli.raw {
+                anyhow::bail!("--raw is not supported by `tui`");
+            }
+            let branch_id = resolve_branch(&client, &project_id, branch_id, branch_opts).await?;
+            tui::run(tui::Session {
+                client: &client,
+                project_id: &project_id,
+                branch_id: &branch_id,
+                base_url: &cli.base_url,
+                page_size: cli.page_size,
+                dates,
+            })
+            .await?;
         }
 
         Commands::IssueShow {
             issue_id,
             project_id,
             branch_id,
+            url,
+            path_format,
+            no_source,
+            concurrency,
         } => {
-            let branch_id = resolve_branch(&client, &project_id, branch_id).await?;
+            let (issue_ids, project_id, branch_id) = match (url, project_id) {
+                (Some(url), _) => {
+                    let link = web_url::parse_issue_url(&cli.base_url, &url)?;
+                    (vec![link.issue_id], link.project_id, link.branch_id)
+                }
+                (None, Some(project_id)) if !issue_id.is_empty() => {
+                    let branch_id = resolve_branch(&client, &project_id, branch_id, branch_opts).await?;
+                    (issue_id, project_id, branch_id)
+                }
+                _ => anyhow::bail!("--issue-id and --project-id are required without --url"),
+            };
+            let [issue_id] = issue_ids.as_slice() else {
+                return show_issue_details(
+                    &client,
+                    &issue_ids,
+                    &project_id,
+                    &branch_id,
+                    concurrency as usize,
+                    &cli.base_url,
+                    dates,
+                    path_format,
+                    !no_source,
+                    &fmt,
+                )
+                .await;
+            };
 
-            let val: serde_json::Value = client
-                .get_issue(&issue_id, &project_id, &branch_id)
-                .await
-                .context("Failed to get issue")?;
+            let with_events = matches!(fmt, OutputFormat::Pretty);
+            let detail = fetch_issue_detail(&client, issue_id, &project_id, &branch_id, with_events).await?;
 
             match fmt {
                 OutputFormat::Pretty => {
-                    print_issue_detail(&val, &cli.base_url, &project_id, &branch_id);
-
-                    // Also fetch and show main event if we have finding-key and run-id
-                    let data = val.get("data").unwrap_or(&val);
-                    let finding_key = data
-                        .pointer("/attributes/finding-key")
-                        .and_then(|v| v.as_str());
-                    let run_id = data
-                        .pointer("/relationships/latest-observed-on-run/data/id")
-                        .and_then(|v| v.as_str());
-
-                    if let (Some(fk), Some(rid)) = (finding_key, run_id) {
-                        match client.get_events_with_source(fk, rid, None, Some(1)).await {
-                            Ok(events) => {
-                                print_events_summary(&events);
-                            }
-                            Err(e) => {
-                                eprintln!("\n(Could not fetch events: {e})");
-                            }
-                        }
-                    }
+                    print_issue_with_events(
+                        &detail,
+                        &cli.base_url,
+                        &project_id,
+                        &branch_id,
+                        dates,
+                        path_format,
+                        !no_source,
+                    )?;
                 }
-                _ => emit(&val, &fmt)?,
+                _ => emit(&detail.issue, &fmt)?,
             }
         }
 
@@ -613,17 +2351,37 @@ async fn main() -> Result<()> {
             run_id,
             occurrence,
             max_depth,
+            summary,
+            no_source,
+            lang,
         } => {
             let events = client
-                .get_events_with_source(&finding_key, &run_id, occurrence, max_depth)
+                .get_events_with_source(&finding_key, &run_id, occurrence, max_depth, lang.as_deref())
                 .await
-                .context("Failed to get events")?;
+                .map_err(|e| events_error(e, &finding_key, &run_id))?;
 
             match fmt {
+                OutputFormat::Pretty if summary => print_event_paths(&events)?,
                 OutputFormat::Pretty => {
-                    print_event_tree(&events);
+                    print_event_tree(&events, !no_source)?;
+                }
+                _ if summary => {
+                    let items: Vec<serde_json::Value> = event_trees(&events)
+                        .iter()
+                        .map(|tree| {
+                            serde_json::json!({
+                                "finding_key": event_str(tree, "finding-key"),
+                                "language": event_str(tree, "language"),
+                                "path": event_path(tree),
+                            })
+                        })
+                        .collect();
+                    emit(&serde_json::json!(items), &fmt)?;
                 }
-                _ => emit(&events, &fmt)?,
+                _ => emit(&normalized_events(&events), &fmt)?,
+            }
+            if let Some(hint) = empty_events_hint(&events, &finding_key, &run_id, occurrence) {
+                eprintln!("note: {hint}");
             }
         }
 
@@ -632,7 +2390,7 @@ async fn main() -> Result<()> {
             branch_id,
             group_by,
         } => {
-            let branch_id = resolve_branch(&client, &project_id, branch_id).await?;
+            let branch_id = resolve_branch(&client, &project_id, branch_id, branch_opts).await?;
 
             let resp = client
                 .get_roll_up_counts(
@@ -645,29 +2403,20 @@ async fn main() -> Result<()> {
 
             match fmt {
                 OutputFormat::Pretty => {
-                    if let Some(data) = resp.get("data").and_then(|v| v.as_array()) {
-                        if data.is_empty() {
-                            println!("No counts found.");
+                    if resp.get("data").and_then(|v| v.as_array()).is_some() {
+                        let rows = roll_up_rows(&resp);
+                        if rows.is_empty() {
+                            outln!("No counts found.");
                         } else {
-                            println!("{:<40} COUNT", "GROUP");
-                            println!("{}", "-".repeat(50));
-                            for item in data {
-                                let name = item
-                                    .pointer("/attributes/name")
-                                    .or_else(|| item.pointer("/attributes/value"))
-                                    .and_then(|v| v.as_str())
-                                    .unwrap_or("-");
-                                let count = item
-                                    .pointer("/attributes/count")
-                                    .or_else(|| item.pointer("/attributes/value"))
-                                    .and_then(|v| v.as_u64())
-                                    .map(|n| n.to_string())
-                                    .unwrap_or_else(|| "-".to_string());
-                                println!("{:<40} {}", name, count);
+                            outln!("{:<40} COUNT", "GROUP");
+                            outln!("{}", "-".repeat(50));
+                            for (name, count) in rows {
+                                let count = count.map(|n| n.to_string()).unwrap_or_else(|| "-".to_string());
+                                outln!("{} {}", pad(&name, 40), count);
                             }
                         }
                     } else {
-                        println!("{}", serde_json::to_string_pretty(&resp)?);
+                        outln!("{}", serde_json::to_string_pretty(&resp)?);
                     }
                 }
                 _ => emit(&resp, &fmt)?,
@@ -682,7 +2431,7 @@ async fn main() -> Result<()> {
             start_date,
             end_date,
         } => {
-            let branch_id = resolve_branch(&client, &project_id, branch_id).await?;
+            let branch_id = resolve_branch(&client, &project_id, branch_id, branch_opts).await?;
 
             let resp = client
                 .get_issues_over_time(
@@ -700,28 +2449,28 @@ async fn main() -> Result<()> {
                 OutputFormat::Pretty => {
                     if let Some(data) = resp.get("data").and_then(|v| v.as_array()) {
                         if data.is_empty() {
-                            println!("No trend data found.");
+                            outln!("No trend data found.");
                         } else {
                             for series in data {
                                 let name = series
                                     .get("name")
                                     .and_then(|v| v.as_str())
                                     .unwrap_or("-");
-                                println!("Series: {name}");
+                                outln!("Series: {name}");
                                 if let Some(points) = series.get("data").and_then(|v| v.as_array()) {
                                     for point in points {
                                         if let Some(arr) = point.as_array() {
                                             let date = arr.first().and_then(|v| v.as_str()).unwrap_or("-");
                                             let count = arr.get(1).and_then(|v| v.as_u64()).map(|n| n.to_string()).unwrap_or_else(|| "-".to_string());
-                                            println!("  {date}: {count}");
+                                            outln!("  {date}: {count}");
                                         }
                                     }
                                 }
-                                println!();
+                                outln!();
                             }
                         }
                     } else {
-                        println!("{}", serde_json::to_string_pretty(&resp)?);
+                        outln!("{}", serde_json::to_string_pretty(&resp)?);
                     }
                 }
                 _ => emit(&resp, &fmt)?,
@@ -733,7 +2482,7 @@ async fn main() -> Result<()> {
             branch_id,
             metric,
         } => {
-            let branch_id = resolve_branch(&client, &project_id, branch_id).await?;
+            let branch_id = resolve_branch(&client, &project_id, branch_id, branch_opts).await?;
 
             let api_metric = metric.as_deref().map(|m| match m {
                 "outstanding" => "average-for-outstanding-issues",
@@ -750,7 +2499,7 @@ async fn main() -> Result<()> {
                 OutputFormat::Pretty => {
                     if let Some(data) = resp.get("data").and_then(|v| v.as_array()) {
                         if data.is_empty() {
-                            println!("No age data found.");
+                            outln!("No age data found.");
                         } else {
                             for item in data {
                                 let age = item
@@ -762,17 +2511,35 @@ async fn main() -> Result<()> {
                                     .pointer("/attributes/name")
                                     .and_then(|v| v.as_str())
                                     .unwrap_or("-");
-                                println!("Average age ({taxon}): {age}");
+                                outln!("Average age ({taxon}): {age}");
                             }
                         }
                     } else {
-                        println!("{}", serde_json::to_string_pretty(&resp)?);
+                        outln!("{}", serde_json::to_string_pretty(&resp)?);
                     }
                 }
                 _ => emit(&resp, &fmt)?,
             }
         }
 
+        Commands::Version { .. } | Commands::Doctor => unreachable!(),
+
+        Commands::Api { action } => {
+            let resp = match action {
+                ApiAction::Get { path } => client.get_raw(&path).await.with_context(|| format!("GET {path} failed"))?,
+                ApiAction::Post { path, body } => {
+                    let text = if body.as_os_str() == "-" {
+                        std::io::read_to_string(std::io::stdin()).context("Failed to read body from stdin")?
+                    } else {
+                        std::fs::read_to_string(&body).with_context(|| format!("Failed to read {}", body.display()))?
+                    };
+                    let json: serde_json::Value = serde_json::from_str(&text).context("request body is not valid JSON")?;
+                    client.post_raw(&path, &json).await.with_context(|| format!("POST {path} failed"))?
+                }
+            };
+            emit(&resp, &fmt)?;
+        }
+
         Commands::Discovery { r#type } => {
             let resp = match r#type.as_str() {
                 "filter-keys" => client
@@ -790,17 +2557,129 @@ async fn main() -> Result<()> {
 
             match fmt {
                 OutputFormat::Pretty => {
-                    println!("{}", serde_json::to_string_pretty(&resp)?);
+                    outln!("{}", serde_json::to_string_pretty(&resp)?);
                 }
                 _ => emit(&resp, &fmt)?,
             }
         }
 
+        // The query API has no taxonomy listing, so collect the taxa the branch's
+        // issues reference through their included resources.
+        Commands::Taxa {
+            project_id,
+            branch_id,
+            kind,
+        } => {
+            let branch_id = resolve_branch(&client, &project_id, branch_id, branch_opts).await?;
+            let resp = client
+                .list_all_issues(&project_id, Some(&branch_id), None, cli.page_size)
+                .await
+                .map_err(|e| or_not_found(e, "project/branch", &format!("{project_id}/{branch_id}"), "Failed to list issues"))?;
+
+            let (rel_path, type_prefix) = kind.relationship();
+            let included_map = build_included_map(&resp.included);
+            let mut taxa: std::collections::BTreeMap<&str, (&str, usize)> = std::collections::BTreeMap::new();
+            for issue in &resp.data {
+                let Some(id) = issue.relationships.as_ref().and_then(|r| r.pointer(rel_path)).and_then(|v| v.as_str()) else {
+                    continue;
+                };
+                let name = resolve_included(&issue.relationships, rel_path, type_prefix, &included_map);
+                taxa.entry(id).or_insert((name, 0)).1 += 1;
+            }
+
+            match fmt {
+                OutputFormat::Pretty => {
+                    if taxa.is_empty() {
+                        outln!("No taxa found.");
+                        return Ok(());
+                    }
+                    outln!("{:<40} {:<30} ISSUES", "ID", "NAME");
+                    outln!("{}", "-".repeat(80));
+                    for (id, (name, count)) in &taxa {
+                        outln!("{} {} {count}", pad(id, 40), pad(name, 30));
+                    }
+                }
+                _ => {
+                    let items: Vec<serde_json::Value> = taxa
+                        .iter()
+                        .map(|(id, (name, count))| serde_json::json!({ "id": id, "name": name, "issues": count }))
+                        .collect();
+                    emit(&serde_json::json!(items), &fmt)?;
+                }
+            }
+        }
+
+        Commands::Export {
+            project_id,
+            branch_id,
+            concurrency,
+        } => {
+            let branch_id = resolve_branch(&client, &project_id, branch_id, branch_opts).await?;
+            let resp = client
+                .list_all_issues(&project_id, Some(&branch_id), None, cli.page_size)
+                .await
+                .map_err(|e| or_not_found(e, "project/branch", &format!("{project_id}/{branch_id}"), "Failed to list issues"))?;
+
+            let included_map = build_included_map(&resp.included);
+            let (rows, errors) = export_records(&resp.data, &included_map, concurrency as usize, |keys| {
+                let (client, project_id) = (&client, &project_id);
+                async move { client.get_triage_batch(project_id, &keys).await }
+            })
+            .await;
+
+            match fmt {
+                OutputFormat::Pretty => {
+                    if rows.is_empty() {
+                        outln!("No issues found.");
+                        return Ok(());
+                    }
+                    outln!("{:<64} {:<20} {:<10} DISMISSAL", "ISSUE-KEY", "CHECKER", "SEVERITY");
+                    outln!("{}", "-".repeat(110));
+                    for row in &rows {
+                        let field = |ptr: &str| row.pointer(ptr).and_then(|v| v.as_str()).unwrap_or("-").to_string();
+                        outln!(
+                            "{} {} {} {}",
+                            pad(&field("/issue_key"), 64),
+                            pad(&field("/checker"), 20),
+                            pad(&field("/severity"), 10),
+                            field("/triage/dismissal_status"),
+                        );
+                    }
+                }
+                _ => emit(&serde_json::json!({ "issues": rows, "errors": errors }), &fmt)?,
+            }
+            if !errors.is_empty() {
+                eprintln!("warning: triage lookup failed for {} of {} issues", errors.len(), rows.len());
+                if matches!(fmt, OutputFormat::Pretty) {
+                    for err in &errors {
+                        eprintln!("  {}: {}", err["issue_key"].as_str().unwrap_or("-"), err["error"].as_str().unwrap_or("-"));
+                    }
+                }
+            }
+        }
+
         Commands::Triage { action } => match action {
             TriageAction::Get {
                 project_id,
                 issue_key,
+                finding_key,
+                branch_id,
             } => {
+                let issue_key = match (issue_key, finding_key) {
+                    (Some(key), _) => key,
+                    (None, Some(fk)) => {
+                        let branch_id =
+                            resolve_branch(&client, &project_id, branch_id, branch_opts).await?;
+                        client
+                            .find_issue_by_finding_key(&project_id, Some(&branch_id), &fk)
+                            .await
+                            .context("Failed to resolve finding key")?
+                            .attributes
+                            .issue_key
+                    }
+                    (None, None) => anyhow::bail!("One of --issue-key or --finding-key is required"),
+                };
+
                 let resp = client
                     .get_triage(&project_id, &issue_key)
                     .await
@@ -809,20 +2688,20 @@ async fn main() -> Result<()> {
                 match fmt {
                     OutputFormat::Pretty => {
                         if resp.data.is_empty() {
-                            println!("No triage data found.");
+                            outln!("No triage data found.");
                             return Ok(());
                         }
                         for tc in &resp.data {
-                            println!("Issue key:        {}", tc.attributes.issue_key);
-                            println!("Project ID:       {}", tc.attributes.project_id);
-                            println!(
+                            outln!("Issue key:        {}", tc.attributes.issue_key);
+                            outln!("Project ID:       {}", tc.attributes.project_id);
+                            outln!(
                                 "Dismissal status: {}",
                                 tc.attributes.dismissal_status.as_deref().unwrap_or("N/A")
                             );
                             if !tc.attributes.triage_current_values.is_empty() {
-                                println!("Triage values:");
+                                outln!("Triage values:");
                                 for val in &tc.attributes.triage_current_values {
-                                    println!("  {}", serde_json::to_string_pretty(val).unwrap_or_default());
+                                    outln!("  {}", serde_json::to_string_pretty(val).unwrap_or_default());
                                 }
                             }
                         }
@@ -840,6 +2719,7 @@ async fn main() -> Result<()> {
                 dismiss,
                 owner,
                 comment,
+                verify,
             } => {
                 if dismiss.is_none() && owner.is_none() && comment.is_none() {
                     anyhow::bail!("At least one of --dismiss, --owner, or --comment is required");
@@ -852,57 +2732,798 @@ async fn main() -> Result<()> {
                     commentary: comment,
                 };
 
-                let resp = client
-                    .update_triage(&project_id, &keys, &values)
-                    .await
-                    .context("Failed to update triage")?;
+                let (mut outcomes, dismissal_status) = match client.update_triage(&project_id, &keys, &values).await {
+                    Ok(resp) => {
+                        let outcomes = resp.outcomes(&keys);
+                        (outcomes, resp.data.and_then(|d| d.attributes).and_then(|a| a.dismissal_status))
+                    }
+                    // The POST isn't retried, so a transient failure may or may not have
+                    // reached the server; the current triage tells which.
+                    Err(e) if verify && e.is_retryable() => {
+                        eprintln!("warning: triage request failed ({e}); checking whether it was applied");
+                        (client.verify_triage(&project_id, &keys, &values).await, None)
+                    }
+                    Err(e) if e.status() == Some(409) && values.dismiss.is_some() => {
+                        return Err(anyhow::Error::new(e).context(
+                            "Failed to update triage (a dismissal can't change while a dismissal request is pending)",
+                        ));
+                    }
+                    Err(e) => return Err(anyhow::Error::new(e).context("Failed to update triage")),
+                };
+                let unknown: Vec<&str> = outcomes
+                    .iter()
+                    .filter(|o| o.status == TriageOutcomeStatus::Unknown)
+                    .map(|o| o.issue_key.as_str())
+                    .collect();
+                if verify && !unknown.is_empty() {
+                    let checked = client.verify_triage(&project_id, &unknown, &values).await;
+                    let mut checked: std::collections::HashMap<String, TriageOutcome> =
+                        checked.into_iter().map(|o| (o.issue_key.clone(), o)).collect();
+                    for o in &mut outcomes {
+                        if let Some(c) = checked.remove(&o.issue_key) {
+                            *o = c;
+                        }
+                    }
+                }
+                let count = |status| outcomes.iter().filter(|o| o.status == status).count();
+                let (applied, failed, unknown) = (
+                    count(TriageOutcomeStatus::Applied),
+                    count(TriageOutcomeStatus::Failed),
+                    count(TriageOutcomeStatus::Unknown),
+                );
+
+                match fmt {
+                    OutputFormat::Pretty => {
+                        outln!("{:<64} {:<8} REASON", "ISSUE-KEY", "STATUS");
+                        outln!("{}", "-".repeat(100));
+                        for o in &outcomes {
+                            outln!(
+                                "{} {:<8} {}",
+                                pad(&o.issue_key, 64),
+                                o.status.as_str(),
+                                o.reason.as_deref().unwrap_or("-"),
+                            );
+                        }
+                        outln!("\n{applied} of {} issues triaged.", outcomes.len());
+                    }
+                    _ => emit(
+                        &serde_json::json!({
+                            "results": outcomes,
+                            "succeeded": applied,
+                            "failed": failed,
+                            "unknown": unknown,
+                            "dismissal_status": dismissal_status,
+                        }),
+                        &fmt,
+                    )?,
+                }
+
+                if failed > 0 {
+                    if values.dismiss.is_some() {
+                        eprintln!("note: an issue's dismissal can't change while a dismissal request for it is pending");
+                    }
+                    anyhow::bail!("Triage failed for {failed} of {} issues", outcomes.len());
+                }
+                if unknown > 0 {
+                    anyhow::bail!(
+                        "Could not confirm triage for {unknown} of {} issues; check them with `polaris triage get`",
+                        outcomes.len()
+                    );
+                }
+            }
+
+            TriageAction::History {
+                project_id,
+                issue_key,
+                limit,
+                follow: true,
+                interval,
+            } => {
+                follow_triage_history(
+                    &client,
+                    &project_id,
+                    &issue_key,
+                    limit as usize,
+                    std::time::Duration::from_secs(interval),
+                    &fmt,
+                    dates,
+                )
+                .await?;
+            }
+
+            TriageAction::History {
+                project_id,
+                issue_key,
+                limit,
+                ..
+            } => {
+                let resp = client
+                    .get_triage_history(&project_id, &issue_key, limit, 0)
+                    .await
+                    .context("Failed to get triage history")?;
+
+                match fmt {
+                    OutputFormat::Pretty => {
+                        let mut resp = resp;
+                        dates.humanize_json(&mut resp);
+                        emit(&resp, &fmt)?;
+                    }
+                    _ => emit(&resp, &fmt)?,
+                }
+            }
+        },
+    }
+
+    Ok(())
+}
+
+// ── Helpers ──
+
+/// Every triage history item of an issue, oldest first.
+async fn triage_history_items(
+    client: &PolarisClient,
+    project_id: &str,
+    issue_key: &str,
+) -> Result<Vec<serde_json::Value>> {
+    const PAGE: u32 = 100;
+    let mut items = Vec::new();
+    loop {
+        let resp = client
+            .get_triage_history(project_id, issue_key, PAGE, items.len() as u32)
+            .await
+            .context("Failed to get triage history")?;
+        let page = resp.get("data").and_then(|d| d.as_array()).cloned().unwrap_or_default();
+        let total = resp.pointer("/meta/total").and_then(|t| t.as_u64());
+        let fetched = page.len();
+        items.extend(page);
+        if fetched < PAGE as usize || total.is_some_and(|t| items.len() as u64 >= t) {
+            break;
+        }
+    }
+    let timestamp = |item: &serde_json::Value| {
+        item.pointer("/attributes/timestamp").and_then(|t| t.as_str()).and_then(parse_api_timestamp)
+    };
+    items.sort_by_key(timestamp);
+    Ok(items)
+}
+
+/// `triage history --follow`: print the last `limit` items, then poll every `interval`
+/// and print items not seen before (by ID) until Ctrl-C. Polls that find nothing new
+/// print nothing; a failed poll is reported and retried at the next interval.
+async fn follow_triage_history(
+    client: &PolarisClient,
+    project_id: &str,
+    issue_key: &str,
+    limit: usize,
+    interval: std::time::Duration,
+    fmt: &OutputFormat,
+    dates: DateStyle,
+) -> Result<()> {
+    let key = |item: &serde_json::Value| match item.get("id").and_then(|v| v.as_str()) {
+        Some(id) => id.to_string(),
+        None => item.to_string(),
+    };
+    let print = |item: &serde_json::Value| -> Result<()> {
+        if !matches!(fmt, OutputFormat::Pretty) {
+            return emit(item, fmt);
+        }
+        let attr = |name: &str| item.pointer(&format!("/attributes/{name}"));
+        let when = attr("timestamp").and_then(|v| v.as_str()).map_or_else(|| "-".into(), |ts| dates.show(ts));
+        let values = attr("triage-history-values").map(|v| v.to_string()).unwrap_or_default();
+        outln!(
+            "{}  {}  {}{values}",
+            when,
+            pad(attr("author").and_then(|v| v.as_str()).unwrap_or("-"), 20),
+            attr("dismissal-status").and_then(|v| v.as_str()).map(|s| format!("{s}  ")).unwrap_or_default(),
+        );
+        Ok(())
+    };
+
+    let items = triage_history_items(client, project_id, issue_key).await?;
+    let mut seen: std::collections::HashSet<String> = items.iter().map(key).collect();
+    for item in &items[items.len().saturating_sub(limit)..] {
+        print(item)?;
+    }
+    if std::io::stderr().is_terminal() {
+        eprintln!("Following triage history of {issue_key} every {}s (Ctrl-C to stop)", interval.as_secs());
+    }
+
+    loop {
+        let items = tokio::select! {
+            _ = tokio::signal::ctrl_c() => return Ok(()),
+            items = async {
+                tokio::time::sleep(interval).await;
+                triage_history_items(client, project_id, issue_key).await
+            } => items,
+        };
+        match items {
+            Ok(items) => {
+                for item in items {
+                    if seen.insert(key(&item)) {
+                        print(&item)?;
+                    }
+                }
+            }
+            Err(e) => eprintln!("warning: {e:#}; retrying in {}s", interval.as_secs()),
+        }
+    }
+}
+
+/// Poll a run every `interval` until its status is terminal or `timeout` passes.
+/// Status changes are reported on stderr; the final run record goes to stdout.
+async fn wait_for_run(
+    client: &PolarisClient,
+    project_id: &str,
+    run_id: &str,
+    timeout: std::time::Duration,
+    interval: std::time::Duration,
+    fmt: &OutputFormat,
+    dates: DateStyle,
+) -> Result<()> {
+    let start = std::time::Instant::now();
+    let mut last_status: Option<String> = None;
+    let run = loop {
+        let run = match client.get_run(run_id).await {
+            Ok(resp) => resp.data,
+            // A missing run won't appear by waiting.
+            Err(PolarisError::NotFound(_)) => anyhow::bail!("run not found: {run_id}"),
+            Err(e) => {
+                eprintln!("warning: {:#}; retrying in {}s", anyhow::Error::new(e), interval.as_secs());
+                if start.elapsed() + interval > timeout {
+                    break None;
+                }
+                tokio::time::sleep(interval).await;
+                continue;
+            }
+        };
+        if last_status.is_none()
+            && let Some(owner) = run.project_id()
+            && owner != project_id
+        {
+            anyhow::bail!("run {run_id} belongs to project {owner}, not {project_id}");
+        }
+
+        let status = run.attributes.status.clone().unwrap_or_else(|| "-".into());
+        if last_status.as_ref() != Some(&status) {
+            eprintln!("[{:>4}s] run {run_id}: {status}", start.elapsed().as_secs());
+            last_status = Some(status);
+        }
+        if run.attributes.outcome().is_some() {
+            break Some(run);
+        }
+        if start.elapsed() + interval > timeout {
+            break None;
+        }
+        tokio::select! {
+            _ = tokio::signal::ctrl_c() => anyhow::bail!("interrupted while waiting for run {run_id}"),
+            _ = tokio::time::sleep(interval) => {}
+        }
+    };
+
+    let Some(run) = run else {
+        eprintln!(
+            "error: run {run_id} still {} after {}s",
+            last_status.as_deref().unwrap_or("unknown"),
+            timeout.as_secs()
+        );
+        return Err(Exit(EXIT_TIMED_OUT).into());
+    };
+    let status = run.attributes.status.as_deref().unwrap_or("-");
+    match fmt {
+        OutputFormat::Pretty => {
+            let completed = run.attributes.date_completed.as_deref().map(|d| dates.show(d));
+            outln!("Run {} {status}{}", run.id, completed.map(|d| format!(" at {d}")).unwrap_or_default());
+        }
+        _ => emit(
+            &serde_json::json!({
+                "id": run.id,
+                "status": run.attributes.status,
+                "date_created": run.attributes.date_created,
+                "date_completed": run.attributes.date_completed,
+            }),
+            fmt,
+        )?,
+    }
+    if run.attributes.outcome() == Some(false) {
+        anyhow::bail!("run {run_id} finished with status {status}");
+    }
+    Ok(())
+}
+
+async fn print_version(base_url: &str, tls: &TlsConfig, check: bool, fmt: &OutputFormat) -> Result<()> {
+    let version = env!("CARGO_PKG_VERSION");
+    if !check {
+        match fmt {
+            OutputFormat::Pretty => outln!("polaris {version}"),
+            _ => emit(&serde_json::json!({ "version": version }), fmt)?,
+        }
+        return Ok(());
+    }
+
+    let probes = polaris_api::client::probe_apis(base_url, tls).await?;
+    let reachable = probes.iter().any(|p| p.responded());
+    match fmt {
+        OutputFormat::Pretty => {
+            outln!("polaris {version}");
+            outln!("Base URL: {base_url}\n");
+            outln!("{:<20} {:<8} {:>8}  NOTE", "API", "STATUS", "LATENCY");
+            outln!("{}", "-".repeat(60));
+            for p in &probes {
+                outln!(
+                    "{:<20} {:<8} {:>5} ms  {}",
+                    p.api,
+                    p.status.map(|s| s.to_string()).unwrap_or_else(|| "-".into()),
+                    p.latency_ms,
+                    match (&p.error, p.responded()) {
+                        (Some(e), _) => e.as_str(),
+                        (None, true) => "ok",
+                        (None, false) => "not responding",
+                    }
+                );
+            }
+            outln!();
+            outln!("Instance {}", if reachable { "reachable" } else { "unreachable" });
+        }
+        _ => emit(&serde_json::json!({
+            "version": version,
+            "base_url": base_url,
+            "reachable": reachable,
+            "apis": probes,
+        }), fmt)?,
+    }
+    if !reachable {
+        anyhow::bail!("Polaris instance at {base_url} is not reachable");
+    }
+    Ok(())
+}
+
+/// Parse a `--query` argument of the form `key=value`.
+fn parse_query_arg(s: &str) -> std::result::Result<(String, String), String> {
+    match s.split_once('=') {
+        Some((key, value)) if !key.is_empty() => Ok((key.to_string(), value.to_string())),
+        _ => Err(format!("expected KEY=VALUE, got `{s}`")),
+    }
+}
+
+/// Parse a date argument: an ISO-8601 date or timestamp, or a relative age such as `7d`.
+fn parse_date_arg(s: &str) -> std::result::Result<DateTime<Utc>, String> {
+    let s = s.trim();
+    if let Some(d) = polaris_api::common::parse_timestamp(s) {
+        return Ok(d);
+    }
+    if let Ok(d) = NaiveDateTime::parse_from_str(s, "%Y-%m-%dT%H:%M:%S") {
+        return Ok(d.and_utc());
+    }
+    if let Ok(d) = NaiveDate::parse_from_str(s, "%Y-%m-%d") {
+        return Ok(d.and_time(NaiveTime::MIN).and_utc());
+    }
+
+    let invalid = || format!("invalid date `{s}`: expected YYYY-MM-DD, an RFC 3339 timestamp, or a relative age like 12h/7d/2w");
+    let split = s.find(|c: char| !c.is_ascii_digit()).ok_or_else(invalid)?;
+    let (num, unit) = s.split_at(split);
+    let n: i64 = num.parse().map_err(|_| invalid())?;
+    let age = match unit {
+        "h" => chrono::Duration::hours(n),
+        "d" => chrono::Duration::days(n),
+        "w" => chrono::Duration::weeks(n),
+        _ => return Err(invalid()),
+    };
+    Ok(Utc::now() - age)
+}
+
+/// Parse a duration given as seconds (`90`) or with a unit (`90s`, `5m`, `1h`).
+fn parse_duration_arg(s: &str) -> std::result::Result<std::time::Duration, String> {
+    let s = s.trim();
+    let invalid = || format!("invalid duration `{s}`: expected seconds or a number with s/m/h, like 60s or 5m");
+    let split = s.find(|c: char| !c.is_ascii_digit()).unwrap_or(s.len());
+    let (num, unit) = s.split_at(split);
+    let n: u64 = num.parse().map_err(|_| invalid())?;
+    let secs = match unit {
+        "" | "s" => n,
+        "m" => n * 60,
+        "h" => n * 3600,
+        _ => return Err(invalid()),
+    };
+    Ok(std::time::Duration::from_secs(secs))
+}
+
+/// With `--no-pagination`, point out that more results exist beyond the first page.
+/// `page` is the number of results fetched and the server-reported total.
+fn first_page_note(no_pagination: bool, page: (usize, Option<u64>), fmt: &OutputFormat) -> Result<()> {
+    let (fetched, total) = page;
+    let more = total.map_or(0, |t| t.saturating_sub(fetched as u64));
+    if !no_pagination || more == 0 {
+        return Ok(());
+    }
+    match fmt {
+        OutputFormat::Pretty => outln!("\n(first page only; {more} more available)"),
+        _ => eprintln!("(first page only; {more} more available)"),
+    }
+    Ok(())
+}
+
+/// How `resolve_branch` picks a branch when `--branch-id` is omitted.
+#[derive(Clone, Copy)]
+struct BranchOptions {
+    strategy: BranchStrategy,
+    /// stdin and stderr are a terminal and `--no-interactive` wasn't given.
+    interactive: bool,
+    /// `--select-branch`: prompt even when the strategy has an unambiguous answer.
+    always_pick: bool,
+}
+
+async fn resolve_branch(
+    client: &PolarisClient,
+    project_id: &str,
+    branch_id: Option<String>,
+    opts: BranchOptions,
+) -> Result<String> {
+    if let Some(id) = branch_id {
+        return Ok(id);
+    }
+
+    let branches = client
+        .list_all_branches(project_id, PAGE_SIZE)
+        .await
+        .map_err(|e| or_not_found(e, "project", project_id, "Failed to list branches to resolve a branch"))?;
+    let mains = branches.data.iter().filter(|b| is_main(b)).count();
+    // No main branch, or several, is ambiguous under the default strategy.
+    let ambiguous = matches!(opts.strategy, BranchStrategy::Main) && mains != 1;
+    if opts.interactive && (opts.always_pick || ambiguous) && branches.data.len() > 1 {
+        return pick_branch(&branches.data);
+    }
+
+    let latest = || {
+        branches
+            .data
+            .iter()
+            .max_by_key(|b| b.attributes.last_activity())
+    };
+    let chosen = match opts.strategy {
+        BranchStrategy::Main => {
+            if let Some(main) = main_branch(&branches.data) {
+                return Ok(main.id.clone());
+            }
+            latest().inspect(|b| {
+                eprintln!("No main branch found; using most recently active branch {} ({})", b.attributes.name, b.id);
+            })
+        }
+        BranchStrategy::Latest => latest(),
+        BranchStrategy::First => branches.data.first(),
+    };
+    let branch = chosen.context("Project has no branches; specify --branch-id explicitly")?;
+    if !matches!(opts.strategy, BranchStrategy::Main) {
+        eprintln!("Using branch {} ({})", branch.attributes.name, branch.id);
+    }
+    Ok(branch.id.clone())
+}
+
+fn is_main(branch: &polaris_api::common::Branch) -> bool {
+    branch.attributes.main_for_project.unwrap_or(false)
+}
+
+/// The branch flagged as the project's main branch (the first, if several are).
+fn main_branch(branches: &[polaris_api::common::Branch]) -> Option<&polaris_api::common::Branch> {
+    branches.iter().find(|b| is_main(b))
+}
+
+/// Ask on stderr/stdin which branch to use.
+fn pick_branch(branches: &[polaris_api::common::Branch]) -> Result<String> {
+    eprintln!("Select a branch (or pass --branch-id):");
+    for (i, b) in branches.iter().enumerate() {
+        let main = if is_main(b) { " [main]" } else { "" };
+        eprintln!("  {:>3}) {} ({}){main}", i + 1, b.attributes.name, b.id);
+    }
+    loop {
+        eprint!("Branch [1-{}]: ", branches.len());
+        let mut buf = String::new();
+        if std::io::stdin().read_line(&mut buf)? == 0 {
+            anyhow::bail!("No branch selected; specify --branch-id explicitly");
+        }
+        match buf.trim().parse::<usize>() {
+            Ok(n) if (1..=branches.len()).contains(&n) => return Ok(branches[n - 1].id.clone()),
+            _ => eprintln!("Enter a number between 1 and {}", branches.len()),
+        }
+    }
+}
+
+/// `label done/total` counter on stderr, redrawn in place. Silent when stderr isn't
+/// a terminal, so logs and CI output stay clean.
+struct Progress {
+    label: &'static str,
+    total: usize,
+    done: std::cell::Cell<usize>,
+    enabled: bool,
+}
+
+impl Progress {
+    fn new(label: &'static str, total: usize) -> Self {
+        Self {
+            label,
+            total,
+            done: std::cell::Cell::new(0),
+            enabled: total > 0 && std::io::stderr().is_terminal(),
+        }
+    }
+
+    fn tick(&self) {
+        self.done.set(self.done.get() + 1);
+        if self.enabled {
+            eprint!("\r{} {}/{}", self.label, self.done.get(), self.total);
+        }
+    }
+
+    fn finish(&self) {
+        if self.enabled {
+            eprintln!();
+        }
+    }
+}
+
+/// `export` records: each issue joined with its current triage. `fetch` reads the
+/// triage of up to [`TRIAGE_BATCH_SIZE`] keys, one result per key, and `concurrency`
+/// fetches run at once. An issue whose triage couldn't be read gets a null `triage`
+/// and an entry in the returned errors rather than failing the export.
+async fn export_records<'a, F, Fut>(
+    issues: &'a [Issue],
+    included_map: &std::collections::HashMap<String, &serde_json::Value>,
+    concurrency: usize,
+    fetch: F,
+) -> (Vec<serde_json::Value>, Vec<serde_json::Value>)
+where
+    F: Fn(Vec<&'a str>) -> Fut,
+    Fut: std::future::Future<Output = Vec<polaris_api::error::Result<Option<TriageCurrent>>>>,
+{
+    use futures::StreamExt;
+
+    let keys: Vec<&str> = issues.iter().map(|i| i.attributes.issue_key.as_str()).collect();
+    let progress = Progress::new("triage", issues.len());
+    let mut batches: Vec<_> = futures::stream::iter(keys.chunks(TRIAGE_BATCH_SIZE).enumerate())
+        .map(|(i, chunk)| {
+            let fetch = &fetch;
+            async move { (i, fetch(chunk.to_vec()).await) }
+        })
+        .buffer_unordered(concurrency)
+        .inspect(|(_, results)| results.iter().for_each(|_| progress.tick()))
+        .collect()
+        .await;
+    progress.finish();
+    batches.sort_by_key(|(i, _)| *i);
+
+    let mut rows = Vec::with_capacity(issues.len());
+    let mut errors = Vec::new();
+    let results = batches.into_iter().flat_map(|(_, results)| results);
+    for (issue, result) in issues.iter().zip(results) {
+        let attrs = &issue.attributes;
+        let current = match result {
+            Ok(t) => t.map(|tc| {
+                serde_json::json!({
+                    "dismissal_status": tc.attributes.dismissal_status,
+                    "values": tc.attributes.triage_current_values,
+                })
+            }),
+            Err(e) => {
+                errors.push(serde_json::json!({ "issue_key": attrs.issue_key, "error": e.to_string() }));
+                None
+            }
+        };
+        rows.push(serde_json::json!({
+            "id": issue.id,
+            "issue_key": attrs.issue_key,
+            "finding_key": attrs.finding_key,
+            "checker": attrs.sub_tool,
+            "severity": resolve_included(&issue.relationships, "/severity/data/id", "taxon", included_map),
+            "issue_type": resolve_included(&issue.relationships, "/issue-type/data/id", "issue-type", included_map),
+            "first_detected_on": attrs.first_detected_on,
+            "triage": current,
+        }));
+    }
+    (rows, errors)
+}
 
-                match fmt {
-                    OutputFormat::Pretty => println!("Triage updated successfully."),
-                    _ => emit(&resp, &fmt)?,
-                }
+/// Combine per-branch issue listings (in display order) into one response, plus the
+/// branch name of each issue. An issue is kept once per branch by finding key; branches
+/// that failed are reported on stderr and skipped. The total is the sum of the
+/// branches' totals, when every branch reported one.
+fn merge_branch_issues(results: Vec<(String, Result<IssuesResponse>)>) -> (IssuesResponse, Vec<String>) {
+    let mut merged = IssuesResponse {
+        data: Vec::new(),
+        included: Vec::new(),
+        meta: None,
+        links: None,
+    };
+    let mut names = Vec::new();
+    let mut seen = std::collections::HashSet::new();
+    let mut total = Some(0);
+    for (name, result) in results {
+        let resp = match result {
+            Ok(resp) => resp,
+            Err(e) => {
+                eprintln!("warning: skipping branch {name}: {e:#}");
+                total = None;
+                continue;
+            }
+        };
+        total = total.zip(resp.meta.and_then(|m| m.total)).map(|(a, b)| a + b);
+        for issue in resp.data {
+            if seen.insert((name.clone(), issue.attributes.finding_key.clone())) {
+                names.push(name.clone());
+                merged.data.push(issue);
             }
+        }
+        merged.included.extend(resp.included);
+    }
+    merged.meta = total.map(|total| IssuesMeta {
+        total: Some(total),
+        offset: Some(0),
+        limit: Some(merged.data.len() as u64),
+    });
+    (merged, names)
+}
 
-            TriageAction::History {
-                project_id,
-                issue_key,
-                limit,
-            } => {
-                let resp = client
-                    .get_triage_history(&project_id, &issue_key, limit, 0)
-                    .await
-                    .context("Failed to get triage history")?;
+/// `key` reduced to characters safe in a file name on every platform; anything else
+/// becomes `_`, and names that would be hidden or special get a leading `_`.
+fn safe_file_stem(key: &str) -> String {
+    let stem: String = key
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.') { c } else { '_' })
+        .collect();
+    if stem.is_empty() || stem.starts_with('.') {
+        format!("_{stem}")
+    } else {
+        stem
+    }
+}
 
-                emit(&resp, &fmt)?;
-            }
-        },
+/// An issue's detail, plus its event summary when fetched for pretty output.
+struct IssueDetail {
+    issue: serde_json::Value,
+    events: Option<polaris_api::error::Result<serde_json::Value>>,
+}
+
+async fn fetch_issue_detail(
+    client: &PolarisClient,
+    issue_id: &str,
+    project_id: &str,
+    branch_id: &str,
+    with_events: bool,
+) -> Result<IssueDetail> {
+    let issue: serde_json::Value = client
+        .get_issue(issue_id, project_id, branch_id)
+        .await
+        .map_err(|e| or_not_found(e, "issue", issue_id, &format!("Failed to get issue {issue_id}")))?;
+
+    // Also fetch the main event if we have finding-key and run-id
+    let mut events = None;
+    if with_events {
+        let data = issue.get("data").unwrap_or(&issue);
+        let finding_key = data
+            .pointer("/attributes/finding-key")
+            .and_then(|v| v.as_str());
+        let run_id = data
+            .pointer("/relationships/latest-observed-on-run/data/id")
+            .and_then(|v| v.as_str());
+        if let (Some(fk), Some(rid)) = (finding_key, run_id) {
+            events = Some(client.get_events_with_source(fk, rid, None, Some(1), None).await);
+        }
     }
 
-    Ok(())
+    Ok(IssueDetail { issue, events })
 }
 
-// ── Helpers ──
+/// Fetch several issues, `concurrency` at a time, in the order given. An issue that
+/// can't be fetched is listed in the returned errors instead of failing the rest.
+async fn fetch_issue_details(
+    client: &PolarisClient,
+    issue_ids: &[String],
+    project_id: &str,
+    branch_id: &str,
+    with_events: bool,
+    concurrency: usize,
+) -> (Vec<IssueDetail>, Vec<serde_json::Value>) {
+    use futures::StreamExt;
+
+    let progress = Progress::new("issues", issue_ids.len());
+    let mut results: Vec<_> = futures::stream::iter(issue_ids.iter().enumerate())
+        .map(|(i, issue_id)| async move {
+            (i, fetch_issue_detail(client, issue_id, project_id, branch_id, with_events).await)
+        })
+        .buffer_unordered(concurrency)
+        .inspect(|_| progress.tick())
+        .collect()
+        .await;
+    progress.finish();
+    results.sort_by_key(|(i, _)| *i);
+
+    let mut details = Vec::new();
+    let mut errors = Vec::new();
+    for (i, result) in results {
+        match result {
+            Ok(detail) => details.push(detail),
+            Err(e) => errors.push(serde_json::json!({ "issue_id": issue_ids[i], "error": format!("{e:#}") })),
+        }
+    }
+    (details, errors)
+}
 
-async fn resolve_branch(
+/// Fail when any of `total` issues couldn't be fetched, listing them on stderr for
+/// pretty output (structured output carries them under `errors`).
+fn report_issue_errors(errors: &[serde_json::Value], total: usize, fmt: &OutputFormat) -> Result<()> {
+    if errors.is_empty() {
+        return Ok(());
+    }
+    if matches!(fmt, OutputFormat::Pretty) {
+        eprintln!("\nFailed to fetch {} of {total} issues:", errors.len());
+        for err in errors {
+            eprintln!("  {}: {}", err["issue_id"].as_str().unwrap_or("-"), err["error"].as_str().unwrap_or("-"));
+        }
+    }
+    anyhow::bail!("{} of {total} issues could not be fetched", errors.len())
+}
+
+/// Fetch several issues and print them in the order given: `issues details`, and
+/// `issue` with more than one `--issue-id`. Pretty output rules the issues apart;
+/// structured output is `{"issues": [...], "errors": [...]}`.
+#[allow(clippy::too_many_arguments)]
+async fn show_issue_details(
     client: &PolarisClient,
+    issue_ids: &[String],
     project_id: &str,
-    branch_id: Option<String>,
-) -> Result<String> {
-    match branch_id {
-        Some(id) => Ok(id),
-        None => {
-            let branches = client
-                .list_all_branches(project_id, 25)
-                .await
-                .context("Failed to list branches to find main branch")?;
-            branches
-                .data
-                .iter()
-                .find(|b| b.attributes.main_for_project.unwrap_or(false))
-                .map(|b| b.id.clone())
-                .context("No main branch found; specify --branch-id explicitly")
+    branch_id: &str,
+    concurrency: usize,
+    base_url: &str,
+    dates: DateStyle,
+    path_format: Option<PathFormat>,
+    source: bool,
+    fmt: &OutputFormat,
+) -> Result<()> {
+    let with_events = matches!(fmt, OutputFormat::Pretty);
+    let (details, errors) = fetch_issue_details(client, issue_ids, project_id, branch_id, with_events, concurrency).await;
+
+    match fmt {
+        OutputFormat::Pretty => {
+            for (i, detail) in details.iter().enumerate() {
+                if i > 0 {
+                    outln!("\n{}", "═".repeat(80));
+                }
+                print_issue_with_events(detail, base_url, project_id, branch_id, dates, path_format, source)?;
+            }
+        }
+        _ => {
+            let issues: Vec<&serde_json::Value> = details.iter().map(|d| &d.issue).collect();
+            emit(&serde_json::json!({ "issues": issues, "errors": errors }), fmt)?;
         }
     }
+    report_issue_errors(&errors, issue_ids.len(), fmt)
+}
+
+fn print_issue_with_events(
+    detail: &IssueDetail,
+    base_url: &str,
+    project_id: &str,
+    branch_id: &str,
+    dates: DateStyle,
+    path_format: Option<PathFormat>,
+    source: bool,
+) -> Result<()> {
+    let link = path_format.map(|format| PathLink {
+        format,
+        line: detail
+            .events
+            .as_ref()
+            .and_then(|e| e.as_ref().ok())
+            .and_then(|e| event_trees(e).first())
+            .and_then(|tree| event_field(tree, "main-event-line-number"))
+            .and_then(|v| v.as_u64()),
+        hyperlink: hyperlinks_supported(),
+    });
+    print_issue_detail(&detail.issue, base_url, project_id, branch_id, dates, link.as_ref())?;
+    match &detail.events {
+        Some(Ok(events)) => print_events_summary(events, source)?,
+        Some(Err(e)) => eprintln!("\n(Could not fetch events: {e})"),
+        None => {}
+    }
+    Ok(())
 }
 
 fn build_included_map(included: &[serde_json::Value]) -> std::collections::HashMap<String, &serde_json::Value> {
@@ -934,7 +3555,102 @@ fn resolve_included<'a>(
         .unwrap_or("-")
 }
 
-fn print_issue_detail(val: &serde_json::Value, base_url: &str, project_id: &str, branch_id: &str) {
+/// How `issue --path-format` renders the issue path.
+struct PathLink {
+    format: PathFormat,
+    /// Line of the main event, when the event tree was fetched.
+    line: Option<u64>,
+    /// Wrap the reference in an OSC 8 hyperlink.
+    hyperlink: bool,
+}
+
+impl PathLink {
+    fn render(&self, path: &str) -> String {
+        let located = match self.line {
+            Some(line) => format!("{path}:{line}"),
+            None => path.to_string(),
+        };
+        let abs = std::env::current_dir().map_or_else(|_| std::path::PathBuf::from(path), |cwd| cwd.join(path));
+        let abs = abs.to_string_lossy();
+        let target = match self.format {
+            PathFormat::Plain => format!("file://{abs}"),
+            PathFormat::Vscode => match self.line {
+                Some(line) => format!("vscode://file/{abs}:{line}"),
+                None => format!("vscode://file/{abs}"),
+            },
+            PathFormat::Idea => match self.line {
+                Some(line) => format!("idea://open?file={}&line={line}", urlencoding::encode(&abs)),
+                None => format!("idea://open?file={}", urlencoding::encode(&abs)),
+            },
+        };
+        match (self.hyperlink, self.format) {
+            (true, _) => format!("\x1b]8;;{target}\x1b\\{located}\x1b]8;;\x1b\\"),
+            (false, PathFormat::Plain) => located,
+            (false, _) => target,
+        }
+    }
+}
+
+/// Best-effort check for a terminal that renders OSC 8 hyperlinks. Unknown terminals
+/// get the plain text, since unsupported escapes would show up as junk.
+fn hyperlinks_supported() -> bool {
+    if let Ok(force) = std::env::var("FORCE_HYPERLINK") {
+        return force != "0";
+    }
+    if !std::io::stdout().is_terminal() || std::env::var_os("CI").is_some() {
+        return false;
+    }
+    let env = |name: &str| std::env::var(name).unwrap_or_default();
+    matches!(env("TERM_PROGRAM").as_str(), "iTerm.app" | "WezTerm" | "vscode" | "ghostty")
+        || std::env::var_os("WT_SESSION").is_some()
+        || std::env::var_os("KONSOLE_VERSION").is_some()
+        || env("VTE_VERSION").parse::<u32>().is_ok_and(|v| v >= 5000)
+        || matches!(env("TERM").as_str(), "xterm-kitty" | "alacritty" | "foot")
+}
+
+/// Resolve an issue's file path from its included `path` resource.
+fn resolve_path(
+    relationships: Option<&serde_json::Value>,
+    included_map: &std::collections::HashMap<String, &serde_json::Value>,
+) -> String {
+    relationships
+        .and_then(|r| r.pointer("/path/data/id"))
+        .and_then(|id| id.as_str())
+        .and_then(|id| included_map.get(&format!("path:{id}")))
+        .and_then(|v| v.pointer("/attributes/path"))
+        .and_then(|v| v.as_array())
+        .map(|arr| {
+            arr.iter()
+                .filter_map(|v| v.as_str())
+                .collect::<Vec<_>>()
+                .join("/")
+        })
+        .unwrap_or_else(|| "-".to_string())
+}
+
+fn print_issue_detail(
+    val: &serde_json::Value,
+    base_url: &str,
+    project_id: &str,
+    branch_id: &str,
+    dates: DateStyle,
+    link: Option<&PathLink>,
+) -> Result<()> {
+    for line in issue_detail_lines(val, base_url, project_id, branch_id, dates, link) {
+        outln!("{line}");
+    }
+    Ok(())
+}
+
+/// The `Field: value` lines shown for an issue, shared by `issue` and the TUI detail pane.
+fn issue_detail_lines(
+    val: &serde_json::Value,
+    base_url: &str,
+    project_id: &str,
+    branch_id: &str,
+    dates: DateStyle,
+    link: Option<&PathLink>,
+) -> Vec<String> {
     let data = val.get("data").unwrap_or(val);
 
     let id = data.pointer("/id").and_then(|v| v.as_str()).unwrap_or("-");
@@ -953,7 +3669,7 @@ fn print_issue_detail(val: &serde_json::Value, base_url: &str, project_id: &str,
     let first_detected = data
         .pointer("/attributes/first-detected-on")
         .and_then(|v| v.as_str())
-        .unwrap_or("-");
+        .map_or_else(|| "-".to_string(), |d| dates.show(d));
 
     // Resolve included resources
     let included = val.get("included").and_then(|v| v.as_array());
@@ -991,17 +3707,11 @@ fn print_issue_detail(val: &serde_json::Value, base_url: &str, project_id: &str,
         .unwrap_or("-");
 
     let path_val = data.pointer("/relationships/path/data/id").and_then(|v| v.as_str());
-    let path = path_val
-        .and_then(|id| included_map.get(&format!("path:{id}")))
-        .and_then(|v| v.pointer("/attributes/path"))
-        .and_then(|v| v.as_array())
-        .map(|arr| {
-            arr.iter()
-                .filter_map(|v| v.as_str())
-                .collect::<Vec<_>>()
-                .join("/")
-        })
-        .unwrap_or_else(|| "-".to_string());
+    let path = resolve_path(data.get("relationships"), &included_map);
+    let path = match link {
+        Some(link) if path != "-" => link.render(&path),
+        _ => path,
+    };
 
     // Resolve revision ID from included transition resource
     let revision_id = included
@@ -1028,209 +3738,589 @@ fn print_issue_detail(val: &serde_json::Value, base_url: &str, project_id: &str,
             format!("[{}]", parts.join(","))
         });
 
-    println!("Issue:          {issue_key}");
-    println!("ID:             {id}");
-    println!("Severity:       {severity}");
-    println!("Type:           {issue_type}");
-    println!("Checker:        {sub_tool}");
-    println!("Tool:           {tool}");
-    println!("Path:           {path}");
-    println!("Finding key:    {finding_key}");
-    println!("First detected: {first_detected}");
+    let mut lines = vec![
+        format!("Issue:          {issue_key}"),
+        format!("ID:             {id}"),
+        format!("Severity:       {severity}"),
+        format!("Type:           {issue_type}"),
+        format!("Checker:        {sub_tool}"),
+        format!("Tool:           {tool}"),
+        format!("Path:           {path}"),
+        format!("Finding key:    {finding_key}"),
+        format!("First detected: {first_detected}"),
+    ];
+
+    if let Ok(url) = web_url::issue_url(base_url, project_id, branch_id, revision_id, id, path_query.as_deref()) {
+        lines.push(format!("URL:            {url}"));
+    }
+    lines
+}
+
+// ── Event accessors ──
+//
+// The events API mixes kebab-case (`line-number`) and camelCase (`filePath`) keys.
+// All event rendering goes through these accessors, which accept either spelling.
+
+/// Look up a field by its kebab-case name, falling back to the camelCase spelling.
+fn event_field<'a>(obj: &'a serde_json::Value, key: &str) -> Option<&'a serde_json::Value> {
+    obj.get(key).or_else(|| obj.get(kebab_to_camel(key)))
+}
 
-    // Construct web URL
-    let mut url = format!("{base_url}/projects/{project_id}/branches/{branch_id}");
-    if let Some(rev_id) = revision_id {
-        url.push_str(&format!("/revisions/{rev_id}"));
+fn kebab_to_camel(key: &str) -> String {
+    let mut out = String::with_capacity(key.len());
+    let mut upper = false;
+    for c in key.chars() {
+        if c == '-' {
+            upper = true;
+        } else if upper {
+            out.extend(c.to_uppercase());
+            upper = false;
+        } else {
+            out.push(c);
+        }
     }
-    url.push_str(&format!("/issues/{id}?pagingOffset=0"));
-    if let Some(ref pq) = path_query {
-        url.push_str(&format!("&path={}", urlencoding::encode(pq)));
+    out
+}
+
+fn event_str<'a>(obj: &'a serde_json::Value, key: &str) -> Option<&'a str> {
+    event_field(obj, key).and_then(|v| v.as_str())
+}
+
+fn event_description(evt: &serde_json::Value) -> &str {
+    event_str(evt, "event-description").unwrap_or("-")
+}
+
+fn event_file(evt: &serde_json::Value) -> &str {
+    event_str(evt, "file-path").unwrap_or("-")
+}
+
+fn event_line(evt: &serde_json::Value) -> String {
+    event_field(evt, "line-number")
+        .and_then(|v| v.as_u64())
+        .map(|n| n.to_string())
+        .unwrap_or_else(|| "-".to_string())
+}
+
+fn event_type(evt: &serde_json::Value) -> &str {
+    event_str(evt, "event-type").unwrap_or("")
+}
+
+fn event_tag(evt: &serde_json::Value) -> &'static str {
+    match event_type(evt) {
+        "main" => "►",
+        "path" => "→",
+        "evidence" => "╴",
+        "example" => "◆",
+        _ => " ",
     }
-    println!("URL:            {url}");
+}
+
+fn evidence_events(evt: &serde_json::Value) -> &[serde_json::Value] {
+    event_field(evt, "evidence-events")
+        .and_then(|v| v.as_array())
+        .map(Vec::as_slice)
+        .unwrap_or_default()
+}
+
+fn tree_events(tree: &serde_json::Value) -> &[serde_json::Value] {
+    event_field(tree, "events")
+        .and_then(|v| v.as_array())
+        .map(Vec::as_slice)
+        .unwrap_or_default()
+}
+
+/// Main event location of an event tree as `(file, line, language)`.
+fn tree_main_location(tree: &serde_json::Value) -> (String, String, &str) {
+    let file = event_field(tree, "main-event-file-path")
+        .and_then(|v| v.as_array())
+        .map(|arr| {
+            arr.iter()
+                .filter_map(|v| v.as_str())
+                .collect::<Vec<_>>()
+                .join("/")
+        })
+        .unwrap_or_else(|| "-".to_string());
+    let line = event_field(tree, "main-event-line-number")
+        .and_then(|v| v.as_u64())
+        .map(|n| n.to_string())
+        .unwrap_or_else(|| "-".to_string());
+    let language = event_str(tree, "language").unwrap_or("-");
+    (file, line, language)
 }
 
 /// Print a short summary of events (used in issue show).
-fn print_events_summary(events: &serde_json::Value) {
+/// The first events of each tree, with their source snippets when `source` is set.
+fn print_events_summary(events: &serde_json::Value, source: bool) -> Result<()> {
     let data = events.get("data").and_then(|v| v.as_array());
     let data = match data {
         Some(d) if !d.is_empty() => d,
-        _ => return,
+        _ => return Ok(()),
     };
 
-    println!("\n── Event Summary ──");
+    outln!("\n── Event Summary ──");
     for event_tree in data {
-        let main_file = event_tree
-            .get("main-event-file-path")
-            .and_then(|v| v.as_array())
-            .map(|arr| {
-                arr.iter()
-                    .filter_map(|v| v.as_str())
-                    .collect::<Vec<_>>()
-                    .join("/")
-            })
-            .unwrap_or_else(|| "-".to_string());
-        let main_line = event_tree
-            .get("main-event-line-number")
-            .and_then(|v| v.as_u64())
-            .map(|n| n.to_string())
-            .unwrap_or_else(|| "-".to_string());
-        let language = event_tree
-            .get("language")
-            .and_then(|v| v.as_str())
-            .unwrap_or("-");
-
-        println!("Main event:     {main_file}:{main_line} ({language})");
+        let (main_file, main_line, language) = tree_main_location(event_tree);
+        outln!("Main event:     {main_file}:{main_line} ({language})");
 
         // Show first few events
-        if let Some(evts) = event_tree.get("events").and_then(|v| v.as_array()) {
-            for evt in evts.iter().take(5) {
-                let desc = evt
-                    .get("event-description")
-                    .and_then(|v| v.as_str())
-                    .unwrap_or("-");
-                let file = evt
-                    .get("filePath")
-                    .and_then(|v| v.as_str())
-                    .unwrap_or("-");
-                let line = evt
-                    .get("line-number")
-                    .and_then(|v| v.as_u64())
-                    .map(|n| n.to_string())
-                    .unwrap_or_else(|| "-".to_string());
-                let etype = evt
-                    .get("event-type")
-                    .and_then(|v| v.as_str())
-                    .unwrap_or("");
-                let tag = match etype {
-                    "main" => "►",
-                    "path" => "→",
-                    "evidence" => "╴",
-                    _ => " ",
-                };
-                println!("  {tag} {file}:{line}: {desc}");
+        let evts = tree_events(event_tree);
+        for evt in evts.iter().take(5) {
+            outln!("  {} {}:{}: {}", event_tag(evt), event_file(evt), event_line(evt), event_description(evt));
 
-                // Show source snippet if available
-                if let Some(src) = evt.get("source-before") {
-                    print_snippet(src);
-                }
-                if let Some(src) = evt.get("source-after") {
-                    print_snippet(src);
-                }
+            // Show source snippet if available
+            if source {
+                print_snippets(evt, 2)?;
             }
-            if evts.len() > 5 {
-                println!("  ... and {} more events (use `polaris events` for full tree)", evts.len() - 5);
+        }
+        if evts.len() > 5 {
+            outln!("  ... and {} more events (use `polaris events` for full tree)", evts.len() - 5);
+        }
+    }
+    Ok(())
+}
+
+fn event_trees(events: &serde_json::Value) -> &[serde_json::Value] {
+    events
+        .get("data")
+        .and_then(|v| v.as_array())
+        .map(Vec::as_slice)
+        .unwrap_or_default()
+}
+
+/// `file:line` of every `path` event in traversal order, falling back to the
+/// main event when the tree has no path events.
+fn event_path(tree: &serde_json::Value) -> Vec<String> {
+    fn collect(events: &[serde_json::Value], out: &mut Vec<String>) {
+        for evt in events {
+            if event_type(evt) == "path" {
+                out.push(format!("{}:{}", short_path(event_file(evt)), event_line(evt)));
             }
+            collect(evidence_events(evt), out);
         }
     }
+
+    let mut path = Vec::new();
+    collect(tree_events(tree), &mut path);
+    if path.is_empty() {
+        let (file, line, _) = tree_main_location(tree);
+        path.push(format!("{}:{line}", short_path(&file)));
+    }
+    path
+}
+
+fn short_path(path: &str) -> &str {
+    path.rsplit('/').next().unwrap_or(path)
+}
+
+/// Print one call-path line per event tree (`events --summary`).
+fn print_event_paths(events: &serde_json::Value) -> Result<()> {
+    let data = event_trees(events);
+    if data.is_empty() {
+        outln!("No events found.");
+        return Ok(());
+    }
+
+    for tree in data {
+        outln!(
+            "{} ({}): {}",
+            event_str(tree, "finding-key").unwrap_or("-"),
+            event_str(tree, "language").unwrap_or("-"),
+            event_path(tree).join(" → ")
+        );
+    }
+    Ok(())
+}
+
+/// Event trees in a stable snake_case schema for `events --json`/`--toon`,
+/// independent of the API's mixed key spelling:
+///
+/// `[{finding_key, language, main_event: {file, line}, events: [event]}]`, where each
+/// event is `{type, description, file, line, source_before, source_after, evidence_events}`
+/// and a source snippet is `{start_line, code}`. Missing values are `null`.
+fn normalized_events(events: &serde_json::Value) -> serde_json::Value {
+    let trees: Vec<serde_json::Value> = event_trees(events)
+        .iter()
+        .map(|tree| {
+            let main_file = event_field(tree, "main-event-file-path")
+                .and_then(|v| v.as_array())
+                .map(|parts| parts.iter().filter_map(|p| p.as_str()).collect::<Vec<_>>().join("/"));
+            serde_json::json!({
+                "finding_key": event_str(tree, "finding-key"),
+                "language": event_str(tree, "language"),
+                "main_event": {
+                    "file": main_file,
+                    "line": event_field(tree, "main-event-line-number").and_then(|v| v.as_u64()),
+                },
+                "events": tree_events(tree).iter().map(normalized_event).collect::<Vec<_>>(),
+            })
+        })
+        .collect();
+    serde_json::json!(trees)
+}
+
+fn normalized_event(evt: &serde_json::Value) -> serde_json::Value {
+    let snippet = |key: &str| {
+        event_field(evt, key).map(|src| {
+            serde_json::json!({
+                "start_line": event_field(src, "start-line").and_then(|v| v.as_u64()),
+                "code": event_str(src, "source-code"),
+            })
+        })
+    };
+    serde_json::json!({
+        "type": event_str(evt, "event-type"),
+        "description": event_str(evt, "event-description"),
+        "file": event_str(evt, "file-path"),
+        "line": event_field(evt, "line-number").and_then(|v| v.as_u64()),
+        "source_before": snippet("source-before"),
+        "source_after": snippet("source-after"),
+        "evidence_events": evidence_events(evt).iter().map(normalized_event).collect::<Vec<_>>(),
+    })
+}
+
+const RUNS_HINT: &str = "list valid run IDs with `polaris runs --project-id ...`";
+
+/// Explain a failed events lookup: which of the run ID and finding key the server
+/// rejected, going by the error detail, with a pointer to valid run IDs.
+fn events_error(err: PolarisError, finding_key: &str, run_id: &str) -> anyhow::Error {
+    let detail = match &err {
+        PolarisError::NotFound(detail) | PolarisError::Api { status: 400 | 422, detail } => detail.to_lowercase(),
+        _ => return anyhow::Error::new(err).context("Failed to get events"),
+    };
+    if !looks_like_uuid(run_id) {
+        anyhow::anyhow!("run ID `{run_id}` doesn't look like a run ID (expected a UUID); {RUNS_HINT}")
+    } else if detail.contains("run") {
+        anyhow::anyhow!("run not found: {run_id}; {RUNS_HINT}")
+    } else if detail.contains("finding") {
+        anyhow::anyhow!("finding {finding_key} not found in run {run_id}; it may not have been observed in that run")
+    } else if matches!(err, PolarisError::NotFound(_)) {
+        anyhow::anyhow!("finding/run not found: {finding_key}/{run_id}; {RUNS_HINT}")
+    } else {
+        anyhow::Error::new(err).context(format!("Failed to get events ({RUNS_HINT})"))
+    }
+}
+
+/// `8-4-4-4-12` hex digits, the shape of Polaris run IDs.
+fn looks_like_uuid(id: &str) -> bool {
+    let groups: Vec<&str> = id.split('-').collect();
+    groups.len() == 5
+        && groups.iter().zip([8, 4, 4, 4, 12]).all(|(g, len)| g.len() == len && g.chars().all(|c| c.is_ascii_hexdigit()))
+}
+
+/// Why an events response has nothing to show, or `None` when it has events.
+fn empty_events_hint(events: &serde_json::Value, finding_key: &str, run_id: &str, occurrence: Option<u32>) -> Option<String> {
+    // Some instances answer 200 with JSON:API `errors` instead of a 4xx.
+    let errors: Vec<&str> = events
+        .get("errors")
+        .and_then(|e| e.as_array())
+        .into_iter()
+        .flatten()
+        .filter_map(|e| ["detail", "title"].iter().find_map(|k| e.get(k).and_then(|v| v.as_str())))
+        .collect();
+    if !errors.is_empty() {
+        return Some(format!("the server reported: {}", errors.join("; ")));
+    }
+
+    let trees = event_trees(events);
+    if trees.is_empty() {
+        return Some(match occurrence {
+            Some(n) if n > 1 => format!("finding {finding_key} has no occurrence {n} in run {run_id}; try a lower --occurrence"),
+            _ => format!(
+                "finding {finding_key} has no event trees in run {run_id}; it may not have been observed in that run \
+                 (`polaris issue` shows the run that last observed it, or {RUNS_HINT})"
+            ),
+        });
+    }
+    if trees.iter().all(|tree| tree_events(tree).is_empty()) {
+        let occurrence = occurrence.unwrap_or(1);
+        return Some(format!("finding {finding_key} exists in run {run_id} but has no events for occurrence {occurrence}"));
+    }
+    None
 }
 
-/// Print the full event tree (used in `events` command).
-fn print_event_tree(events: &serde_json::Value) {
+/// Print the full event tree (used in `events` command), with source snippets under
+/// each event when `source` is set.
+fn print_event_tree(events: &serde_json::Value, source: bool) -> Result<()> {
     let data = events.get("data").and_then(|v| v.as_array());
     let data = match data {
         Some(d) if !d.is_empty() => d,
         _ => {
-            println!("No events found.");
-            return;
+            outln!("No events found.");
+            return Ok(());
         }
     };
 
     for event_tree in data {
-        let main_file = event_tree
-            .get("main-event-file-path")
-            .and_then(|v| v.as_array())
-            .map(|arr| {
-                arr.iter()
-                    .filter_map(|v| v.as_str())
-                    .collect::<Vec<_>>()
-                    .join("/")
-            })
-            .unwrap_or_else(|| "-".to_string());
-        let main_line = event_tree
-            .get("main-event-line-number")
-            .and_then(|v| v.as_u64())
-            .map(|n| n.to_string())
-            .unwrap_or_else(|| "-".to_string());
-        let language = event_tree
-            .get("language")
-            .and_then(|v| v.as_str())
-            .unwrap_or("-");
+        let (main_file, main_line, language) = tree_main_location(event_tree);
 
-        println!("Finding:  {}", event_tree.get("finding-key").and_then(|v| v.as_str()).unwrap_or("-"));
-        println!("Main:     {main_file}:{main_line}");
-        println!("Language: {language}\n");
+        outln!("Finding:  {}", event_str(event_tree, "finding-key").unwrap_or("-"));
+        outln!("Main:     {main_file}:{main_line}");
+        outln!("Language: {language}\n");
 
-        if let Some(evts) = event_tree.get("events").and_then(|v| v.as_array()) {
-            print_events_recursive(evts, 0);
+        let events = tree_events(event_tree);
+        if events.is_empty() {
+            outln!("(no events recorded for this occurrence)");
         }
+        print_events_recursive(events, 0, source)?;
     }
+    Ok(())
 }
 
-fn print_events_recursive(events: &[serde_json::Value], indent: usize) {
+fn print_events_recursive(events: &[serde_json::Value], indent: usize, source: bool) -> Result<()> {
     let pad = "  ".repeat(indent);
     for evt in events {
-        let desc = evt
-            .get("event-description")
-            .and_then(|v| v.as_str())
-            .unwrap_or("-");
-        let file = evt
-            .get("filePath")
-            .and_then(|v| v.as_str())
-            .unwrap_or("-");
-        let line = evt
-            .get("line-number")
-            .and_then(|v| v.as_u64())
-            .map(|n| n.to_string())
-            .unwrap_or_else(|| "-".to_string());
-        let etype = evt
-            .get("event-type")
-            .and_then(|v| v.as_str())
-            .unwrap_or("");
-        let tag = match etype {
-            "main" => "►",
-            "path" => "→",
-            "evidence" => "╴",
-            "example" => "◆",
-            _ => " ",
-        };
-
-        println!("{pad}{tag} {file}:{line}: {desc}");
+        outln!("{pad}{} {}:{}: {}", event_tag(evt), event_file(evt), event_line(evt), event_description(evt));
 
-        // Source snippets
-        if let Some(src) = evt.get("source-before") {
-            print_snippet_indented(src, indent + 1);
-        }
-        if let Some(src) = evt.get("source-after") {
-            print_snippet_indented(src, indent + 1);
+        if source {
+            print_snippets(evt, indent + 1)?;
         }
 
         // Recurse into evidence events
-        if let Some(children) = evt.get("evidence-events").and_then(|v| v.as_array())
-            && !children.is_empty()
-        {
-            print_events_recursive(children, indent + 1);
+        let children = evidence_events(evt);
+        if !children.is_empty() {
+            print_events_recursive(children, indent + 1, source)?;
         }
     }
+    Ok(())
 }
 
-fn print_snippet(src: &serde_json::Value) {
-    print_snippet_indented(src, 2);
+/// An event's `source-before` and `source-after` snippets.
+fn print_snippets(evt: &serde_json::Value, indent: usize) -> Result<()> {
+    for key in ["source-before", "source-after"] {
+        if let Some(src) = event_field(evt, key) {
+            print_snippet_indented(src, indent)?;
+        }
+    }
+    Ok(())
 }
 
-fn print_snippet_indented(src: &serde_json::Value, indent: usize) {
-    let code = match src.get("source-code").and_then(|v| v.as_str()) {
+fn print_snippet_indented(src: &serde_json::Value, indent: usize) -> Result<()> {
+    let code = match event_str(src, "source-code") {
         Some(c) if !c.is_empty() => c,
-        _ => return,
+        _ => return Ok(()),
     };
-    let start = src
-        .get("start-line")
+    let start = event_field(src, "start-line")
         .and_then(|v| v.as_u64())
         .unwrap_or(0);
     let pad = "  ".repeat(indent);
 
     for (i, line) in code.lines().enumerate() {
         let lineno = start + i as u64;
-        println!("{pad}  {lineno:>5} │ {line}");
+        outln!("{pad}  {lineno:>5} │ {line}");
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used, clippy::expect_used)]
+mod tests {
+    use super::*;
+
+    fn issue(key: &str) -> Issue {
+        serde_json::from_value(serde_json::json!({
+            "type": "issue",
+            "id": format!("id-{key}"),
+            "attributes": { "issue-key": key, "finding-key": format!("f-{key}") },
+        }))
+        .unwrap()
+    }
+
+    fn triage(key: &str, status: &str) -> TriageCurrent {
+        serde_json::from_value(serde_json::json!({
+            "type": "triage-current",
+            "id": key,
+            "attributes": { "issue-key": key, "project-id": "p1", "dismissal-status": status },
+        }))
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn one_failing_triage_fetch_does_not_fail_the_export() {
+        let issues = vec![issue("a"), issue("bad"), issue("c")];
+        let (rows, errors) = export_records(&issues, &Default::default(), 2, |keys| async move {
+            keys.iter()
+                .map(|&key| match key {
+                    "bad" => Err(PolarisError::Other("boom".into())),
+                    "a" => Ok(Some(triage(key, "NOT_DISMISSED"))),
+                    _ => Ok(None),
+                })
+                .collect()
+        })
+        .await;
+
+        assert_eq!(rows.len(), 3);
+        assert_eq!(rows[0]["triage"]["dismissal_status"], "NOT_DISMISSED");
+        assert!(rows[1]["triage"].is_null());
+        assert!(rows[2]["triage"].is_null());
+        assert_eq!(errors, vec![serde_json::json!({ "issue_key": "bad", "error": "boom" })]);
+    }
+
+    #[tokio::test]
+    async fn export_keeps_issue_order_across_batches() {
+        let issues: Vec<Issue> = (0..TRIAGE_BATCH_SIZE * 2 + 1).map(|i| issue(&i.to_string())).collect();
+        let (rows, errors) = export_records(&issues, &Default::default(), 3, |keys| async move {
+            keys.iter().map(|&key| Ok(Some(triage(key, key)))).collect()
+        })
+        .await;
+
+        assert!(errors.is_empty());
+        for (i, row) in rows.iter().enumerate() {
+            assert_eq!(row["issue_key"], i.to_string());
+            assert_eq!(row["triage"]["dismissal_status"], i.to_string());
+        }
+    }
+
+    #[test]
+    fn kebab_to_camel_converts_each_dash() {
+        assert_eq!(kebab_to_camel("main-event-file-path"), "mainEventFilePath");
+        assert_eq!(kebab_to_camel("language"), "language");
+        assert_eq!(kebab_to_camel("trailing-"), "trailing");
+        assert_eq!(kebab_to_camel(""), "");
+    }
+
+    #[test]
+    fn event_field_accepts_either_spelling() {
+        let kebab = serde_json::json!({ "line-number": 3 });
+        let camel = serde_json::json!({ "lineNumber": 4 });
+        let both = serde_json::json!({ "line-number": 5, "lineNumber": 6 });
+        assert_eq!(event_field(&kebab, "line-number"), Some(&serde_json::json!(3)));
+        assert_eq!(event_field(&camel, "line-number"), Some(&serde_json::json!(4)));
+        assert_eq!(event_field(&both, "line-number"), Some(&serde_json::json!(5)), "kebab-case wins");
+        assert_eq!(event_field(&kebab, "file-path"), None);
+    }
+
+    /// An events response in the API's mixed spelling: kebab-case trees and main
+    /// events, a camelCase evidence event nested under it, and fields left out.
+    fn mixed_events() -> serde_json::Value {
+        serde_json::json!({
+            "data": [
+                {
+                    "finding-key": "fk0",
+                    "language": "c",
+                    "main-event-file-path": ["src", "a.c"],
+                    "main-event-line-number": 3,
+                    "events": [{
+                        "event-type": "main",
+                        "event-description": "Dereferencing p",
+                        "file-path": "src/a.c",
+                        "line-number": 3,
+                        "source-before": { "start-line": 1, "source-code": "int f(int *p) {" },
+                        "evidence-events": [{
+                            "eventType": "evidence",
+                            "eventDescription": "p may be null",
+                            "filePath": "src/a.c",
+                            "lineNumber": 2,
+                            "sourceAfter": { "startLine": 2, "sourceCode": "  if (!p) {}" },
+                            "evidenceEvents": [{ "eventType": "example" }],
+                        }],
+                    }],
+                },
+                { "findingKey": "fk1", "mainEventFilePath": ["b.c"] },
+            ]
+        })
+    }
+
+    #[test]
+    fn normalized_events_unify_spellings() {
+        let trees = normalized_events(&mixed_events());
+        assert_eq!(trees[0]["finding_key"], "fk0");
+        assert_eq!(trees[0]["main_event"], serde_json::json!({ "file": "src/a.c", "line": 3 }));
+
+        let main = &trees[0]["events"][0];
+        assert_eq!(main["type"], "main");
+        assert_eq!(main["source_before"], serde_json::json!({ "start_line": 1, "code": "int f(int *p) {" }));
+        assert!(main["source_after"].is_null());
+
+        let evidence = &main["evidence_events"][0];
+        assert_eq!(evidence["description"], "p may be null");
+        assert_eq!(evidence["line"], 2);
+        assert_eq!(evidence["source_after"], serde_json::json!({ "start_line": 2, "code": "  if (!p) {}" }));
+        assert_eq!(evidence["evidence_events"][0]["type"], "example");
+    }
+
+    #[test]
+    fn normalized_events_null_missing_fields() {
+        let trees = normalized_events(&mixed_events());
+        let sparse = &trees[1];
+        assert_eq!(sparse["finding_key"], "fk1");
+        assert!(sparse["language"].is_null());
+        assert_eq!(sparse["main_event"], serde_json::json!({ "file": "b.c", "line": null }));
+        assert_eq!(sparse["events"], serde_json::json!([]));
+
+        let bare = &trees[0]["events"][0]["evidence_events"][0]["evidence_events"][0];
+        for field in ["description", "file", "line", "source_before", "source_after"] {
+            assert!(bare[field].is_null(), "{field}");
+        }
+        assert_eq!(bare["evidence_events"], serde_json::json!([]));
+        assert_eq!(normalized_events(&serde_json::json!({})), serde_json::json!([]));
+    }
+
+    #[test]
+    fn json_and_toon_conflict() {
+        let err = try_parse_cli(["polaris", "--json", "--toon", "projects"]).err().unwrap();
+        assert_eq!(err.kind(), clap::error::ErrorKind::ArgumentConflict);
+    }
+
+    #[test]
+    fn typed_format_conflicts_with_shorthands() {
+        for args in [["--format", "json", "--json"], ["--toon", "--format", "pretty"]] {
+            let err = try_parse_cli(["polaris"].into_iter().chain(args).chain(["projects"])).err().unwrap();
+            assert_eq!(err.kind(), clap::error::ErrorKind::ArgumentConflict, "{args:?}");
+            assert!(err.to_string().contains("'--format <FORMAT>' cannot be used with"), "{err}");
+        }
+    }
+
+    #[test]
+    fn shorthand_overrides_polaris_format() {
+        // SAFETY: no other test reads or writes POLARIS_FORMAT.
+        unsafe { std::env::set_var("POLARIS_FORMAT", "toon") };
+        let cli = try_parse_cli(["polaris", "--json", "projects"]);
+        unsafe { std::env::remove_var("POLARIS_FORMAT") };
+        assert!(matches!(cli.ok().unwrap().output_format(), OutputFormat::Json));
+    }
+
+    #[test]
+    fn pad_counts_wide_characters_as_two_cells() {
+        assert_eq!(pad("abc", 6), "abc   ");
+        assert_eq!(pad("日本", 6), "日本  ");
+        assert_eq!(pad("🦀x", 6), "🦀x   ");
+        assert_eq!(UnicodeWidthStr::width(pad("漢字テスト", 6).as_str()), 10, "too wide to pad, left as is");
+    }
+
+    #[test]
+    fn truncate_width_cuts_on_char_boundaries_within_the_width() {
+        assert_eq!(truncate_width("abcdef", 4), "abcd");
+        assert_eq!(truncate_width("ab", 4), "ab");
+        // The third character would end mid-cell at 5 of 4.
+        assert_eq!(truncate_width("日本語", 4), "日本");
+        assert_eq!(truncate_width("日本語", 5), "日本");
+        assert_eq!(truncate_width("a🦀🦀", 4), "a🦀");
+        for max in 0..8 {
+            let cut = truncate_width("a日🦀b語", max);
+            assert!(UnicodeWidthStr::width(cut) <= max, "{max}: {cut:?}");
+        }
+    }
+
+    /// An object with `pairs` inserted in the order given.
+    fn object(pairs: Vec<(&str, serde_json::Value)>) -> serde_json::Value {
+        serde_json::Value::Object(pairs.into_iter().map(|(k, v)| (k.to_string(), v)).collect())
+    }
+
+    #[test]
+    fn sorted_keys_makes_toon_independent_of_insertion_order() {
+        let row = |reversed: bool| {
+            let mut pairs = vec![("id", "i1".into()), ("severity", "high".into()), ("checker", "NULL_RETURNS".into())];
+            if reversed {
+                pairs.reverse();
+            }
+            object(pairs)
+        };
+        let a = object(vec![("project", "p1".into()), ("issues", serde_json::json!([row(false), row(false)]))]);
+        let b = object(vec![("issues", serde_json::json!([row(true), row(true)])), ("project", "p1".into())]);
+        let toon = |v: &serde_json::Value| toon_rs::encode_to_string(v, &toon_rs::Options::default()).unwrap();
+
+        assert_ne!(toon(&a), toon(&b), "order is preserved without sorting");
+        assert_eq!(toon(&sorted_keys(&a)), toon(&sorted_keys(&b)));
+        assert!(toon(&sorted_keys(&a)).starts_with("issues[2]{checker,id,severity}:"), "{}", toon(&sorted_keys(&a)));
     }
 }
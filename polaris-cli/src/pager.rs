@@ -0,0 +1,150 @@
+use std::fmt;
+use std::io::{IsTerminal, Write};
+use std::process::{Child, Command, Stdio};
+use std::sync::Mutex;
+
+use clap::ValueEnum;
+
+/// Write a line of command output, routed through the pager when one is active.
+/// Returns early with the write error once the reader has gone away (see [`write_line`]).
+macro_rules! outln {
+    () => {
+        $crate::pager::write_line(format_args!(""))?
+    };
+    ($($arg:tt)*) => {
+        $crate::pager::write_line(format_args!($($arg)*))?
+    };
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+pub enum PagerMode {
+    /// Page pretty output when stdout is a terminal (default)
+    Auto,
+    /// Always page output
+    Always,
+    /// Never page output
+    Never,
+}
+
+struct Sink {
+    /// Spawn a pager on the first write.
+    wanted: bool,
+    pager: Option<Child>,
+    /// Writing failed; drop further output.
+    closed: bool,
+    /// The reader went away; further writes fail with `BrokenPipe`.
+    broken_pipe: bool,
+    /// `--raw`: drop formatted output so only raw response bodies are written.
+    muted: bool,
+}
+
+static SINK: Mutex<Sink> = Mutex::new(Sink {
+    wanted: false,
+    pager: None,
+    closed: false,
+    broken_pipe: false,
+    muted: false,
+});
+
+/// Decide whether output should be paged. The pager itself is spawned lazily so
+/// prompts and errors printed before any output aren't swallowed by it.
+pub fn init(mode: PagerMode, pretty: bool) {
+    let wanted = match mode {
+        PagerMode::Always => true,
+        PagerMode::Never => false,
+        PagerMode::Auto => pretty && std::io::stdout().is_terminal(),
+    };
+    if let Ok(mut sink) = SINK.lock() {
+        sink.wanted = wanted;
+    }
+}
+
+/// Spawn `$PAGER`, falling back to `less -FRX`. An empty `$PAGER` or `cat` disables paging.
+fn spawn_pager() -> Option<Child> {
+    let cmd = std::env::var("PAGER").unwrap_or_else(|_| "less -FRX".to_string());
+    let mut parts = cmd.split_whitespace();
+    let program = parts.next()?;
+    if program == "cat" {
+        return None;
+    }
+    let mut command = Command::new(program);
+    command.args(parts).stdin(Stdio::piped());
+    if std::env::var_os("LESS").is_none() {
+        command.env("LESS", "FRX");
+    }
+    command.spawn().ok()
+}
+
+/// Drop all further `outln!` output; [`write_raw`] still goes through.
+pub fn mute() {
+    if let Ok(mut sink) = SINK.lock() {
+        sink.muted = true;
+    }
+}
+
+/// Write a line of command output. Fails with [`std::io::ErrorKind::BrokenPipe`]
+/// once the reader is gone (`polaris issues | head`, or the pager was quit), so the
+/// command stops instead of fetching output nobody will see; `main` treats that as
+/// success, like other Unix filters. Any other write error is reported once and
+/// later output is dropped.
+pub fn write_line(args: fmt::Arguments) -> std::io::Result<()> {
+    write(args, false)
+}
+
+/// Write an unprocessed API response body, even when formatted output is muted.
+/// Called from the client's raw-response hook, which can't fail, so a closed pipe
+/// only shows at the next [`write_line`].
+pub fn write_raw(body: &str) {
+    let _ = write(format_args!("{body}"), true);
+}
+
+/// Whether `err` is the broken pipe [`write_line`] reports when the reader is gone.
+pub fn is_broken_pipe(err: &anyhow::Error) -> bool {
+    err.chain()
+        .any(|e| e.downcast_ref::<std::io::Error>().is_some_and(|e| e.kind() == std::io::ErrorKind::BrokenPipe))
+}
+
+fn write(args: fmt::Arguments, raw: bool) -> std::io::Result<()> {
+    let Ok(mut sink) = SINK.lock() else {
+        return Ok(());
+    };
+    if sink.broken_pipe {
+        return Err(std::io::ErrorKind::BrokenPipe.into());
+    }
+    if sink.closed || (sink.muted && !raw) {
+        return Ok(());
+    }
+    if sink.wanted {
+        sink.wanted = false;
+        sink.pager = spawn_pager();
+    }
+
+    let result = match sink.pager.as_mut().and_then(|p| p.stdin.as_mut()) {
+        Some(stdin) => writeln!(stdin, "{args}"),
+        None => writeln!(std::io::stdout().lock(), "{args}"),
+    };
+    match result {
+        Err(e) if e.kind() == std::io::ErrorKind::BrokenPipe => {
+            sink.closed = true;
+            sink.broken_pipe = true;
+            Err(e)
+        }
+        Err(e) => {
+            eprintln!("Error writing output: {e}");
+            sink.closed = true;
+            Ok(())
+        }
+        Ok(()) => Ok(()),
+    }
+}
+
+/// Close the pager's input and wait for the user to quit it.
+pub fn finish() {
+    let Ok(mut sink) = SINK.lock() else {
+        return;
+    };
+    if let Some(mut child) = sink.pager.take() {
+        drop(child.stdin.take());
+        let _ = child.wait();
+    }
+}
@@ -0,0 +1,289 @@
+//! Interactive issue browser (`polaris tui`), built with the `tui` feature.
+
+use std::collections::HashMap;
+use std::collections::hash_map::Entry;
+use std::time::Duration;
+
+use anyhow::Result;
+use polaris_api::client::{PolarisClient, TriageOutcomeStatus, TriageValues};
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEventKind};
+use ratatui::layout::{Constraint, Layout};
+use ratatui::style::{Modifier, Style};
+use ratatui::widgets::{Block, Borders, List, ListItem, ListState, Paragraph, Wrap};
+use ratatui::{DefaultTerminal, Frame};
+
+use crate::{DateStyle, build_included_map, issue_detail_lines, pad, resolve_included};
+
+/// Dismiss values offered by the `d` picker, in key order.
+const DISMISS_CHOICES: &[&str] = &[
+    "NOT_DISMISSED",
+    "TO_BE_FIXED",
+    "DISMISSED_FALSE_POSITIVE",
+    "DISMISSED_INTENTIONAL",
+    "DISMISSED_OTHER",
+];
+
+/// Everything the browser needs to query and triage one branch.
+pub struct Session<'a> {
+    pub client: &'a PolarisClient,
+    pub project_id: &'a str,
+    pub branch_id: &'a str,
+    pub base_url: &'a str,
+    pub page_size: u32,
+    pub dates: DateStyle,
+}
+
+struct Row {
+    id: String,
+    issue_key: String,
+    checker: String,
+    severity: String,
+}
+
+enum Mode {
+    Browse,
+    Dismiss,
+    Input { owner: bool, buffer: String },
+}
+
+struct App {
+    rows: Vec<Row>,
+    list: ListState,
+    details: HashMap<String, Vec<String>>,
+    mode: Mode,
+    status: String,
+}
+
+/// Fetch the branch's issues and run the browser until the user quits.
+pub async fn run(session: Session<'_>) -> Result<()> {
+    let resp = session
+        .client
+        .list_all_issues(session.project_id, Some(session.branch_id), None, session.page_size)
+        .await?;
+    if resp.data.is_empty() {
+        outln!("No issues found.");
+        return Ok(());
+    }
+
+    let included_map = build_included_map(&resp.included);
+    let rows = resp
+        .data
+        .iter()
+        .map(|issue| Row {
+            id: issue.id.clone(),
+            issue_key: issue.attributes.issue_key.clone(),
+            checker: issue.attributes.sub_tool.clone().unwrap_or_else(|| "-".into()),
+            severity: resolve_included(&issue.relationships, "/severity/data/id", "taxon", &included_map).to_string(),
+        })
+        .collect();
+
+    let mut app = App {
+        rows,
+        list: ListState::default().with_selected(Some(0)),
+        details: HashMap::new(),
+        mode: Mode::Browse,
+        status: String::new(),
+    };
+
+    let mut terminal = ratatui::try_init()?;
+    let result = app.event_loop(&mut terminal, &session).await;
+    ratatui::restore();
+    result
+}
+
+impl App {
+    async fn event_loop(&mut self, terminal: &mut DefaultTerminal, session: &Session<'_>) -> Result<()> {
+        loop {
+            if let Some(row) = self.selected() {
+                let id = &self.rows[row].id;
+                if let Entry::Vacant(slot) = self.details.entry(id.clone()) {
+                    let lines = match session.client.get_issue(id, session.project_id, session.branch_id).await {
+                        Ok(issue) => {
+                            issue_detail_lines(&issue, session.base_url, session.project_id, session.branch_id, session.dates, None)
+                        }
+                        Err(e) => vec![format!("Could not load issue: {e}")],
+                    };
+                    slot.insert(lines);
+                }
+            }
+
+            terminal.draw(|frame| self.draw(frame))?;
+
+            if !event::poll(Duration::from_millis(250))? {
+                continue;
+            }
+            let Event::Key(key) = event::read()? else {
+                continue;
+            };
+            if key.kind != KeyEventKind::Press {
+                continue;
+            }
+
+            match &mut self.mode {
+                Mode::Browse => {
+                    self.status.clear();
+                    match key.code {
+                        KeyCode::Char('q') | KeyCode::Esc => return Ok(()),
+                        KeyCode::Down | KeyCode::Char('j') => self.list.select_next(),
+                        KeyCode::Up | KeyCode::Char('k') => self.list.select_previous(),
+                        KeyCode::Home | KeyCode::Char('g') => self.list.select_first(),
+                        KeyCode::End | KeyCode::Char('G') => self.list.select_last(),
+                        KeyCode::Char('d') => self.mode = Mode::Dismiss,
+                        KeyCode::Char('o') => self.mode = Mode::Input { owner: true, buffer: String::new() },
+                        KeyCode::Char('c') => self.mode = Mode::Input { owner: false, buffer: String::new() },
+                        _ => {}
+                    }
+                }
+                Mode::Dismiss => {
+                    let choice = match key.code {
+                        KeyCode::Char(c) => c.to_digit(10).and_then(|n| DISMISS_CHOICES.get((n as usize).wrapping_sub(1))),
+                        _ => None,
+                    };
+                    self.mode = Mode::Browse;
+                    if let Some(value) = choice {
+                        let values = TriageValues {
+                            dismiss: Some(value.to_string()),
+                            ..Default::default()
+                        };
+                        self.triage(session, &values).await;
+                    }
+                }
+                Mode::Input { owner, buffer } => match key.code {
+                    KeyCode::Esc => self.mode = Mode::Browse,
+                    KeyCode::Backspace => {
+                        buffer.pop();
+                    }
+                    KeyCode::Char(c) => buffer.push(c),
+                    KeyCode::Enter => {
+                        let text = std::mem::take(buffer);
+                        let values = if *owner {
+                            TriageValues {
+                                owner: Some(text),
+                                ..Default::default()
+                            }
+                        } else {
+                            TriageValues {
+                                commentary: Some(text),
+                                ..Default::default()
+                            }
+                        };
+                        self.mode = Mode::Browse;
+                        self.triage(session, &values).await;
+                    }
+                    _ => {}
+                },
+            }
+        }
+    }
+
+    /// Index of the selected row. `select_last` parks the selection past the end
+    /// until the next render, so clamp it here.
+    fn selected(&self) -> Option<usize> {
+        self.list.selected().map(|i| i.min(self.rows.len() - 1))
+    }
+
+    async fn triage(&mut self, session: &Session<'_>, values: &TriageValues) {
+        let Some(row) = self.selected() else {
+            return;
+        };
+        let key = self.rows[row].issue_key.clone();
+        self.status = match session.client.update_triage(session.project_id, &[&key], values).await {
+            Ok(resp) => match resp.outcomes(&[&key]).first() {
+                Some(o) if o.status != TriageOutcomeStatus::Applied => {
+                    format!("Triage {} for {key}: {}", o.status.as_str(), o.reason.as_deref().unwrap_or("-"))
+                }
+                _ => format!("Triage applied to {key}"),
+            },
+            Err(e) => format!("Triage failed for {key}: {e}"),
+        };
+    }
+
+    fn draw(&mut self, frame: &mut Frame) {
+        let [main, footer] = Layout::vertical([Constraint::Min(0), Constraint::Length(1)]).areas(frame.area());
+        let [left, right] = Layout::horizontal([Constraint::Percentage(45), Constraint::Percentage(55)]).areas(main);
+
+        let items: Vec<ListItem> = self
+            .rows
+            .iter()
+            .map(|r| ListItem::new(format!("{} {} {}", pad(&r.severity, 9), pad(&r.checker, 20), r.issue_key)))
+            .collect();
+        let list = List::new(items)
+            .block(Block::default().borders(Borders::ALL).title(format!("Issues ({})", self.rows.len())))
+            .highlight_style(Style::default().add_modifier(Modifier::REVERSED));
+        frame.render_stateful_widget(list, left, &mut self.list);
+
+        let detail = self
+            .selected()
+            .and_then(|row| self.details.get(&self.rows[row].id))
+            .map(|lines| lines.join("\n"))
+            .unwrap_or_default();
+        let pane = Paragraph::new(detail)
+            .block(Block::default().borders(Borders::ALL).title("Issue"))
+            .wrap(Wrap { trim: false });
+        frame.render_widget(pane, right);
+
+        let prompt = match &self.mode {
+            Mode::Browse if !self.status.is_empty() => self.status.clone(),
+            Mode::Browse => "↑/↓ move  d dismiss  o owner  c comment  q quit".to_string(),
+            Mode::Dismiss => DISMISS_CHOICES
+                .iter()
+                .enumerate()
+                .map(|(i, v)| format!("{} {v}", i + 1))
+                .collect::<Vec<_>>()
+                .join("  "),
+            Mode::Input { owner: true, buffer } => format!("Owner email: {buffer}_"),
+            Mode::Input { owner: false, buffer } => format!("Comment: {buffer}_"),
+        };
+        frame.render_widget(Paragraph::new(prompt), footer);
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used, clippy::expect_used)]
+mod tests {
+    use super::*;
+    use polaris_api::client::PolarisConfig;
+
+    #[tokio::test]
+    async fn triage_on_a_read_only_client_sends_nothing() {
+        // Bound but never accepted from: any request would queue a connection here.
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let client = PolarisClient::new(PolarisConfig {
+            base_url: format!("http://{}", listener.local_addr().unwrap()),
+            api_token: "test-token".into(),
+            max_retries: 0,
+            read_only: true,
+            ..Default::default()
+        });
+        let session = Session {
+            client: &client,
+            project_id: "p1",
+            branch_id: "b1",
+            base_url: "http://127.0.0.1",
+            page_size: 100,
+            dates: DateStyle { raw: true, utc: true },
+        };
+        let mut app = App {
+            rows: vec![Row {
+                id: "i1".into(),
+                issue_key: "k1".into(),
+                checker: "-".into(),
+                severity: "-".into(),
+            }],
+            list: ListState::default().with_selected(Some(0)),
+            details: HashMap::new(),
+            mode: Mode::Browse,
+            status: String::new(),
+        };
+        let values = TriageValues {
+            dismiss: Some("DISMISSED_FALSE_POSITIVE".into()),
+            ..Default::default()
+        };
+
+        app.triage(&session, &values).await;
+
+        assert!(app.status.contains("read-only mode"), "{}", app.status);
+        listener.set_nonblocking(true).unwrap();
+        assert!(listener.accept().is_err(), "the client connected to the server");
+    }
+}
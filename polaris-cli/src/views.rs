@@ -0,0 +1,320 @@
+//! Records emitted by `--format json`/`toon` for the list commands. Fields serialize
+//! in declaration order, and these structs are the output contract: add a field here
+//! (and to `FIELDS`) and every structured format and `--fields-file` picks it up.
+
+use std::collections::HashMap;
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use polaris_api::client::Issue;
+use polaris_api::common::{Branch, Project};
+use serde::{Deserialize, Serialize};
+use unicode_width::UnicodeWidthStr;
+
+use crate::junit::Severity;
+use crate::{OutputFormat, emit, is_main, pad, resolve_included};
+
+/// One `projects` record.
+#[derive(Serialize)]
+pub struct ProjectView<'a> {
+    pub id: &'a str,
+    pub name: &'a str,
+    pub description: Option<&'a str>,
+    pub owner: Option<&'a str>,
+    pub created: Option<&'a str>,
+    pub updated: Option<&'a str>,
+}
+
+impl<'a> From<&'a Project> for ProjectView<'a> {
+    fn from(p: &'a Project) -> Self {
+        Self {
+            id: &p.id,
+            name: &p.attributes.name,
+            description: p.attributes.description.as_deref(),
+            owner: p.attributes.owner(),
+            created: p.attributes.date_created.as_deref(),
+            updated: p.attributes.date_modified.as_deref(),
+        }
+    }
+}
+
+/// One `branches` record.
+#[derive(Serialize)]
+pub struct BranchView<'a> {
+    pub id: &'a str,
+    pub name: &'a str,
+    pub main: bool,
+}
+
+impl<'a> From<&'a Branch> for BranchView<'a> {
+    fn from(b: &'a Branch) -> Self {
+        Self {
+            id: &b.id,
+            name: &b.attributes.name,
+            main: is_main(b),
+        }
+    }
+}
+
+/// One `issues` record, with severity, type and tool names resolved from the included
+/// resources (`-` when missing). `tool` is the analysis engine (e.g. SAST vs SCA),
+/// `checker` the rule within it.
+#[derive(Serialize)]
+pub struct IssueView<'a> {
+    pub id: &'a str,
+    pub issue_key: &'a str,
+    pub finding_key: &'a str,
+    pub checker: Option<&'a str>,
+    pub tool: &'a str,
+    pub severity: &'a str,
+    #[serde(rename = "type")]
+    pub issue_type: &'a str,
+    /// Only with `--all-branches`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub branch: Option<&'a str>,
+}
+
+impl<'a> IssueView<'a> {
+    pub fn new(
+        issue: &'a Issue,
+        included_map: &'a HashMap<String, &serde_json::Value>,
+        branch: Option<&'a str>,
+    ) -> Self {
+        Self {
+            id: &issue.id,
+            issue_key: &issue.attributes.issue_key,
+            finding_key: &issue.attributes.finding_key,
+            checker: issue.attributes.sub_tool.as_deref(),
+            tool: resolve_included(
+                &issue.relationships,
+                "/tool-domain-service/data/id",
+                "tool-domain-service",
+                included_map,
+            ),
+            severity: resolve_included(&issue.relationships, "/severity/data/id", "taxon", included_map),
+            issue_type: resolve_included(&issue.relationships, "/issue-type/data/id", "issue-type", included_map),
+            branch,
+        }
+    }
+}
+
+/// The `issues --group-by-finding` record: every issue sharing one `finding_key`.
+#[derive(Serialize)]
+pub struct FindingGroup<'a> {
+    pub finding_key: &'a str,
+    pub count: usize,
+    /// The severity name of the most severe issue in the group.
+    pub max_severity: &'a str,
+    pub issues: Vec<IssueView<'a>>,
+}
+
+/// Group issues by finding key, in order of each finding's first issue.
+pub fn group_by_finding(items: Vec<IssueView<'_>>) -> Vec<FindingGroup<'_>> {
+    let mut groups: Vec<FindingGroup> = Vec::new();
+    let mut index: HashMap<&str, usize> = HashMap::new();
+    for item in items {
+        let i = *index.entry(item.finding_key).or_insert_with(|| {
+            groups.push(FindingGroup {
+                finding_key: item.finding_key,
+                count: 0,
+                max_severity: item.severity,
+                issues: Vec::new(),
+            });
+            groups.len() - 1
+        });
+        let group = &mut groups[i];
+        if Severity::from_name(item.severity) > Severity::from_name(group.max_severity) {
+            group.max_severity = item.severity;
+        }
+        group.count += 1;
+        group.issues.push(item);
+    }
+    groups
+}
+
+impl ProjectView<'_> {
+    pub const FIELDS: &'static [&'static str] = &["id", "name", "description", "owner", "created", "updated"];
+}
+
+impl BranchView<'_> {
+    pub const FIELDS: &'static [&'static str] = &["id", "name", "main"];
+}
+
+impl IssueView<'_> {
+    pub const FIELDS: &'static [&'static str] = &["id", "issue_key", "finding_key", "checker", "tool", "severity", "type", "branch"];
+}
+
+/// A `--fields-file` report layout: which fields to show, in order, under which headers.
+///
+/// ```toml
+/// [[columns]]
+/// header = "Issue"
+/// field = "issue_key"
+/// ```
+///
+/// `.json` files hold the same structure (`{"columns": [{"header": ..., "field": ...}]}`).
+#[derive(Debug, Deserialize)]
+pub struct Template {
+    columns: Vec<Column>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Column {
+    header: String,
+    field: String,
+}
+
+impl Template {
+    /// Read and check a template against the fields a command's records have.
+    pub fn load(path: &Path, known: &[&str]) -> Result<Self> {
+        let text = std::fs::read_to_string(path).with_context(|| format!("Failed to read {}", path.display()))?;
+        let template: Template = if path.extension().is_some_and(|e| e.eq_ignore_ascii_case("json")) {
+            serde_json::from_str(&text).with_context(|| format!("Invalid fields file {}", path.display()))?
+        } else {
+            toml::from_str(&text).with_context(|| format!("Invalid fields file {}", path.display()))?
+        };
+        if template.columns.is_empty() {
+            anyhow::bail!("fields file {} declares no columns", path.display());
+        }
+        for column in &template.columns {
+            if !known.contains(&column.field.as_str()) {
+                anyhow::bail!(
+                    "unknown field `{}` in {} (available: {})",
+                    column.field,
+                    path.display(),
+                    known.join(", ")
+                );
+            }
+        }
+        Ok(template)
+    }
+
+    /// Print `rows` with only the template's columns: a padded table for pretty output,
+    /// or records keyed by header for JSON/TOON. Missing values show as `-` in tables.
+    pub fn render<T: Serialize>(&self, rows: &[T], fmt: &OutputFormat) -> Result<()> {
+        let rows: Vec<Vec<serde_json::Value>> = rows
+            .iter()
+            .map(|row| {
+                let value = serde_json::to_value(row)?;
+                Ok(self
+                    .columns
+                    .iter()
+                    .map(|c| value.get(&c.field).cloned().unwrap_or(serde_json::Value::Null))
+                    .collect())
+            })
+            .collect::<Result<_>>()?;
+
+        match fmt {
+            OutputFormat::Pretty => {
+                let cell = |v: &serde_json::Value| match v {
+                    serde_json::Value::Null => "-".to_string(),
+                    serde_json::Value::String(s) => s.clone(),
+                    other => other.to_string(),
+                };
+                let cells: Vec<Vec<String>> = rows.iter().map(|r| r.iter().map(cell).collect()).collect();
+                let widths: Vec<usize> = self
+                    .columns
+                    .iter()
+                    .enumerate()
+                    .map(|(i, c)| {
+                        cells
+                            .iter()
+                            .map(|r| UnicodeWidthStr::width(r[i].as_str()))
+                            .chain([UnicodeWidthStr::width(c.header.as_str())])
+                            .max()
+                            .unwrap_or(0)
+                    })
+                    .collect();
+                let line = |values: Vec<&str>| {
+                    let last = values.len() - 1;
+                    values
+                        .iter()
+                        .enumerate()
+                        .map(|(i, v)| if i == last { v.to_string() } else { pad(v, widths[i]) })
+                        .collect::<Vec<_>>()
+                        .join(" ")
+                };
+                outln!("{}", line(self.columns.iter().map(|c| c.header.as_str()).collect()));
+                outln!("{}", "-".repeat(widths.iter().sum::<usize>() + widths.len() - 1));
+                for row in &cells {
+                    outln!("{}", line(row.iter().map(String::as_str).collect()));
+                }
+            }
+            OutputFormat::Junit => anyhow::bail!("--fields-file does not apply to --format junit"),
+            _ => {
+                let records: Vec<serde_json::Map<String, serde_json::Value>> = rows
+                    .into_iter()
+                    .map(|row| self.columns.iter().map(|c| c.header.clone()).zip(row).collect())
+                    .collect();
+                emit(&serde_json::to_value(records)?, fmt)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used, clippy::expect_used)]
+mod tests {
+    use super::*;
+
+    /// The keys of `record` as serialized, in order.
+    fn keys(record: &impl Serialize) -> Vec<String> {
+        match serde_json::to_value(record).unwrap() {
+            serde_json::Value::Object(map) => map.keys().cloned().collect(),
+            other => panic!("not an object: {other}"),
+        }
+    }
+
+    fn issue(branch: Option<&str>) -> IssueView<'_> {
+        IssueView {
+            id: "i1",
+            issue_key: "k1",
+            finding_key: "f1",
+            checker: Some("NULL_RETURNS"),
+            tool: "Coverity",
+            severity: "high",
+            issue_type: "Null pointer dereference",
+            branch,
+        }
+    }
+
+    #[test]
+    fn project_view_serializes_fields_in_order() {
+        let view = ProjectView {
+            id: "p1",
+            name: "web",
+            description: None,
+            owner: Some("dev@example.com"),
+            created: Some("2024-01-02T03:04:05Z"),
+            updated: None,
+        };
+        assert_eq!(
+            serde_json::to_string(&view).unwrap(),
+            r#"{"id":"p1","name":"web","description":null,"owner":"dev@example.com","created":"2024-01-02T03:04:05Z","updated":null}"#
+        );
+        assert_eq!(keys(&view), ProjectView::FIELDS);
+    }
+
+    #[test]
+    fn branch_view_serializes_fields_in_order() {
+        let view = BranchView { id: "b1", name: "main", main: true };
+        assert_eq!(serde_json::to_string(&view).unwrap(), r#"{"id":"b1","name":"main","main":true}"#);
+        assert_eq!(keys(&view), BranchView::FIELDS);
+    }
+
+    #[test]
+    fn issue_view_serializes_fields_in_order() {
+        assert_eq!(
+            serde_json::to_string(&issue(None)).unwrap(),
+            r#"{"id":"i1","issue_key":"k1","finding_key":"f1","checker":"NULL_RETURNS","tool":"Coverity","severity":"high","type":"Null pointer dereference"}"#
+        );
+        assert_eq!(keys(&issue(Some("main"))), IssueView::FIELDS);
+    }
+
+    #[test]
+    fn finding_group_serializes_fields_in_order() {
+        let groups = group_by_finding(vec![issue(None)]);
+        assert_eq!(keys(&groups[0]), ["finding_key", "count", "max_severity", "issues"]);
+    }
+}
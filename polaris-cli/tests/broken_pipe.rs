@@ -0,0 +1,44 @@
+//! `polaris ... | head -1`: the reader leaving early ends the command quietly with
+//! exit status 0.
+#![cfg(unix)]
+#![allow(clippy::unwrap_used, clippy::expect_used)]
+
+mod common;
+
+use std::io::Read;
+use std::process::{Command, Stdio};
+
+const PROJECTS: usize = 5000;
+
+/// `common/v0/projects` pages of [`PROJECTS`] numbered projects.
+fn projects_page(target: &str) -> String {
+    let offset = common::query_param(target, "page[offset]").unwrap_or(0);
+    let limit = common::query_param(target, "page[limit]").unwrap_or(100);
+    let data: Vec<String> = (offset..(offset + limit).min(PROJECTS))
+        .map(|i| format!(r#"{{"type":"project","id":"p{i}","attributes":{{"name":"Project {i}"}}}}"#))
+        .collect();
+    format!(r#"{{"data":[{}],"meta":{{"total":{PROJECTS},"offset":{offset},"limit":{limit}}}}}"#, data.join(","))
+}
+
+#[test]
+fn output_piped_into_head_exits_zero() {
+    let url = common::serve(projects_page);
+    let mut polaris = common::polaris(&url)
+        .arg("projects")
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+    let head = Command::new("head")
+        .arg("-1")
+        .stdin(polaris.stdout.take().unwrap())
+        .output()
+        .unwrap();
+    let status = polaris.wait().unwrap();
+    let mut stderr = String::new();
+    polaris.stderr.take().unwrap().read_to_string(&mut stderr).unwrap();
+
+    assert_eq!(String::from_utf8_lossy(&head.stdout).lines().count(), 1);
+    assert!(status.success(), "exit status {status}, stderr: {stderr}");
+    assert_eq!(stderr, "");
+}
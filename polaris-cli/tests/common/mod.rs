@@ -0,0 +1,70 @@
+//! Running the `polaris` binary against a local stand-in for the API.
+#![allow(dead_code, clippy::unwrap_used, clippy::expect_used)]
+
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::TcpListener;
+use std::process::Command;
+
+/// Serve JSON:API responses from `handler`, given each request's path and query,
+/// until the test process exits. Logins are answered with a long-lived JWT.
+pub fn serve(handler: fn(&str) -> String) -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    std::thread::spawn(move || {
+        for stream in listener.incoming() {
+            let Ok(mut stream) = stream else { continue };
+            let mut reader = BufReader::new(stream.try_clone().unwrap());
+            let mut start = String::new();
+            if reader.read_line(&mut start).is_err() {
+                continue;
+            }
+            let mut length = 0;
+            loop {
+                let mut line = String::new();
+                if reader.read_line(&mut line).unwrap_or(0) == 0 || line == "\r\n" {
+                    break;
+                }
+                if let Some((name, value)) = line.split_once(':')
+                    && name.eq_ignore_ascii_case("content-length")
+                {
+                    length = value.trim().parse().unwrap_or(0);
+                }
+            }
+            let mut body = vec![0; length];
+            let _ = reader.read_exact(&mut body);
+
+            let target = start.split(' ').nth(1).unwrap_or_default();
+            let reply = if target.starts_with("/api/auth/v2/authenticate") {
+                r#"{"jwt":"x.eyJleHAiOjk5OTk5OTk5OTl9.y"}"#.to_string()
+            } else {
+                handler(target)
+            };
+            let _ = write!(
+                stream,
+                "HTTP/1.1 200 OK\r\nContent-Type: application/vnd.api+json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{reply}",
+                reply.len()
+            );
+        }
+    });
+    url
+}
+
+/// `polaris` pointed at `url`, with a token, no pager and no response cache.
+pub fn polaris(url: &str) -> Command {
+    let mut command = Command::new(env!("CARGO_BIN_EXE_polaris"));
+    command
+        .args(["--pager", "never", "--no-cache"])
+        .env("POLARIS_BASE_URL", url)
+        .env("POLARIS_API_TOKEN", "test-token")
+        .env("RUST_BACKTRACE", "0");
+    command
+}
+
+/// The value of query parameter `name` (as sent, percent-encoded or not) in `target`.
+pub fn query_param(target: &str, name: &str) -> Option<usize> {
+    let encoded = name.replace('[', "%5B").replace(']', "%5D");
+    target.split(['?', '&']).find_map(|pair| {
+        let (key, value) = pair.split_once('=')?;
+        (key == name || key == encoded).then(|| value.parse().ok())?
+    })
+}
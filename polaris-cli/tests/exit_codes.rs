@@ -0,0 +1,66 @@
+//! Exit codes of `issues --format junit` with `--require-results` and `--fail-severity`,
+//! and of `auth status` without a token.
+#![allow(clippy::unwrap_used, clippy::expect_used)]
+
+mod common;
+
+use std::process::Output;
+
+fn no_issues(_: &str) -> String {
+    r#"{"data":[],"included":[],"meta":{"total":0,"offset":0,"limit":100}}"#.to_string()
+}
+
+fn one_high_issue(_: &str) -> String {
+    r#"{"data":[{"type":"issue","id":"i1","attributes":{"issue-key":"k1","finding-key":"f1","sub-tool":"NULL_RETURNS"},
+        "relationships":{"severity":{"data":{"type":"taxon","id":"high"}}}}],
+       "included":[{"type":"taxon","id":"high","attributes":{"name":"High"}}],
+       "meta":{"total":1,"offset":0,"limit":100}}"#
+        .to_string()
+}
+
+fn junit_issues(handler: fn(&str) -> String, extra: &[&str]) -> Output {
+    let url = common::serve(handler);
+    common::polaris(&url)
+        .args(["--format", "junit", "issues", "--project-id", "p1", "--branch-id", "b1", "--fail-severity", "low"])
+        .args(extra)
+        .output()
+        .unwrap()
+}
+
+#[test]
+fn empty_listing_exits_4_with_require_results() {
+    let out = junit_issues(no_issues, &["--require-results"]);
+    assert_eq!(out.status.code(), Some(4), "{}", String::from_utf8_lossy(&out.stderr));
+    // The (empty) report is still written for the CI step that reads it.
+    assert!(String::from_utf8_lossy(&out.stdout).contains("<testsuite"));
+}
+
+#[test]
+fn empty_listing_exits_0_without_require_results() {
+    let out = junit_issues(no_issues, &[]);
+    assert_eq!(out.status.code(), Some(0), "{}", String::from_utf8_lossy(&out.stderr));
+}
+
+#[test]
+fn fail_severity_marks_failures_without_changing_the_exit_code() {
+    let out = junit_issues(one_high_issue, &["--require-results"]);
+    assert_eq!(out.status.code(), Some(0), "{}", String::from_utf8_lossy(&out.stderr));
+    assert!(String::from_utf8_lossy(&out.stdout).contains("<failure"));
+}
+
+#[test]
+fn auth_status_without_a_token_exits_3() {
+    // An empty home: no file token store and no config to find a token in.
+    let home = std::env::temp_dir().join(format!("polaris-no-token-{}", std::process::id()));
+    std::fs::create_dir_all(&home).unwrap();
+    let out = common::polaris("http://127.0.0.1:9")
+        .args(["auth", "status"])
+        .env_remove("POLARIS_API_TOKEN")
+        .env_remove("POLARIS_API_TOKEN_FILE")
+        .env("HOME", &home)
+        .output()
+        .unwrap();
+    std::fs::remove_dir_all(&home).unwrap();
+    assert_eq!(out.status.code(), Some(3), "{}", String::from_utf8_lossy(&out.stderr));
+    assert!(String::from_utf8_lossy(&out.stdout).contains("Token source:  none"));
+}
@@ -0,0 +1,28 @@
+//! `issues details` and `issue` with several IDs share one fetch-and-print path.
+#![allow(clippy::unwrap_used, clippy::expect_used)]
+
+mod common;
+
+/// `api/query/v1/issues/<id>`: an issue whose key echoes its ID.
+fn issue(target: &str) -> String {
+    let id = target.split('?').next().unwrap_or_default().rsplit('/').next().unwrap_or_default();
+    format!(r#"{{"data":{{"type":"issue","id":"{id}","attributes":{{"issue-key":"key-{id}","finding-key":"f-{id}"}}}}}}"#)
+}
+
+fn run(url: &str, args: &[&str]) -> String {
+    let out = common::polaris(url).args(args).output().unwrap();
+    assert!(out.status.success(), "{args:?}: {}", String::from_utf8_lossy(&out.stderr));
+    String::from_utf8(out.stdout).unwrap()
+}
+
+#[test]
+fn issue_with_several_ids_prints_what_issues_details_prints() {
+    let url = common::serve(issue);
+    let ids = ["--issue-id", "i1,i2,i3", "--project-id", "p1", "--branch-id", "b1"];
+    for format in ["json", "pretty"] {
+        let details = run(&url, &[&["--format", format, "issues", "details"], &ids[..]].concat());
+        let issue = run(&url, &[&["--format", format, "issue"], &ids[..]].concat());
+        assert_eq!(details, issue, "--format {format}");
+        assert!(details.find("key-i1") < details.find("key-i3"), "{details}");
+    }
+}